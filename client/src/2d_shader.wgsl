@@ -2,6 +2,7 @@ struct VertexInput {
     @location(0) position: vec2<f32>,
     @location(1) color: vec4<f32>,
     @location(2) texture: vec2<f32>,
+    @location(7) sweep: f32,
 };
 struct InstanceInput {
     @location(3) inst_matrix_0: vec4<f32>,
@@ -14,6 +15,7 @@ struct VertexOutput {
     @builtin(position) @invariant clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
     @location(1) tex_coords: vec2<f32>,
+    @location(2) sweep: f32,
 };
 
 const DEPTH :f32 = 0.0;
@@ -33,6 +35,7 @@ fn vs_main(
     out.color = model.color;
     out.clip_position = model_matrix * vec4(model.position, DEPTH, 1.0);
     out.tex_coords = model.texture;
+    out.sweep = model.sweep;
     return out;
 }
 
@@ -60,6 +63,14 @@ var<uniform> camera: CameraUniform;
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // radial wipe (e.g. cooldown timers), angle measured clockwise from the top
+    if (in.sweep < 1.0) {
+        let d = in.tex_coords - vec2<f32>(0.5, 0.5);
+        let angle = fract(atan2(d.x, -d.y) / 6.2831853 + 1.0);
+        if (angle >= in.sweep) {
+            discard;
+        }
+    }
     var t = textureSample(t_diffuse, s_diffuse, in.tex_coords);
     var mask = textureSample(t_mask, s_mask, in.tex_coords);
     var color = in.color * mask * t;
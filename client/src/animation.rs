@@ -47,6 +47,14 @@ impl AnimationController {
         let model = object;
         if let Some(animated_model) = model.as_any_mut().downcast_mut::<AnimatedModel>() {
             let next_state = match animation_state {
+                AnimationState::Playing { animation_id, time }
+                    if !animated_model.animations.contains_key(animation_id) =>
+                {
+                    // models without this animation fall back to their default one
+                    AnimationState::Stopped {
+                        time: *time % animated_model.default_animation().duration(),
+                    }
+                }
                 AnimationState::Playing { animation_id, time } => {
                     let animation = animated_model.animations.get(animation_id)?;
                     let animation_duration = animation.duration();
@@ -16,7 +16,10 @@ use std::{
     thread,
 };
 
-use common::core::{events::SoundSpec, states::GameState};
+use common::core::{
+    events::{GameEvent, SoundSpec},
+    states::{GameState, MatchPhase},
+};
 use common::{configs::audio_config::ConfigAudioAssets, core::states::GameLifeCycleState};
 
 pub const AUDIO_POS_AT_CLIENT: [f32; 3] = [0.0, 10.0, 0.0];
@@ -50,6 +53,15 @@ pub enum AudioAsset {
     REFILL = 18,
     POINTS_GAIN = 19,
     WEATHER_ENV = 20,
+    FOG = 21,
+    STORM = 22,
+    THUNDER = 23,
+    ANNOUNCER_FIGHT = 24,
+    ANNOUNCER_FLAG_CONTESTED = 25,
+    ANNOUNCER_FLAG_CAPTURED = 26,
+    ANNOUNCER_RING_OUT = 27,
+    ANNOUNCER_OVERTIME = 28,
+    ANNOUNCER_MATCH_END = 29,
 }
 
 pub struct SoundInstance {
@@ -65,12 +77,17 @@ pub struct SoundInstance {
 #[derive(Debug, Clone, Default)]
 pub struct SoundQueue {
     sound_queue: Vec<SoundSpec>,
+    announcements: Vec<AudioAsset>, // played at the client whatever the state of the match
 }
 
 impl SoundQueue {
     pub fn add_sound(&mut self, sound: SoundSpec) {
         self.sound_queue.push(sound);
     }
+
+    pub fn announce(&mut self, announcement: AudioAsset) {
+        self.announcements.push(announcement);
+    }
 }
 
 pub struct Audio {
@@ -80,6 +97,7 @@ pub struct Audio {
     sound_controllers_ambient: HashMap<AudioAsset, SoundInstance>, // for sound events that should only ever be played once at a time e.g. weather
     fading_out: HashMap<AudioAsset, SoundInstance>,
     sound_controller_background: (Option<ambisonic::SoundController>, bool),
+    announcer: Option<ambisonic::SoundController>,
     time: SystemTime,
     sfx_queue: Arc<Mutex<SoundQueue>>,
     curr_state: GameLifeCycleState,
@@ -97,6 +115,7 @@ impl Audio {
             sound_controllers_ambient: HashMap::new(),
             fading_out: HashMap::new(),
             sound_controller_background: (None, true),
+            announcer: None,
             time: SystemTime::now(),
             sfx_queue: q,
             curr_state: GameLifeCycleState::Waiting,
@@ -133,6 +152,17 @@ impl Audio {
         self.sound_controller_background = (Some(sound), false);
     }
 
+    /// The announcer doesn't talk over itself, a new announcement cuts the previous one
+    pub fn play_announcement(&mut self, announcement: AudioAsset) {
+        let source = self.audio_assets[announcement as usize].0.clone();
+        let sound = self
+            .audio_scene
+            .play_at(source.convert_samples(), AUDIO_POS_AT_CLIENT);
+        if let Some(previous) = self.announcer.replace(sound) {
+            previous.stop();
+        }
+    }
+
     pub fn update_bkgd_track(&mut self, state: GameLifeCycleState, curr_player: u32, winner: u32) {
         if std::mem::discriminant(&self.curr_state) != std::mem::discriminant(&state) {
             // println!("audio registered state change: {:?}", self.curr_state);
@@ -412,6 +442,12 @@ impl Audio {
             self.update_bkgd_track(gs.life_cycle_state.clone(), client_id as u32, gs.game_winner.unwrap_or(0));
             self.handle_fade_out();
 
+            // the end of the match is announced too, so not only while it runs
+            let announcements = std::mem::take(&mut self.sfx_queue.lock().unwrap().announcements);
+            for announcement in announcements {
+                self.play_announcement(announcement);
+            }
+
             match player_curr {
                 Ok(player) => {
                     cf = player_curr.unwrap().camera_forward;
@@ -450,6 +486,21 @@ impl Audio {
     }
 }
 
+/// What the announcer says about a gameplay event, if anything
+pub fn announcement(event: &GameEvent) -> Option<AudioAsset> {
+    match event {
+        GameEvent::MatchStarted => Some(AudioAsset::ANNOUNCER_FIGHT),
+        GameEvent::FlagContested(_) => Some(AudioAsset::ANNOUNCER_FLAG_CONTESTED),
+        GameEvent::FlagCaptured(_) => Some(AudioAsset::ANNOUNCER_FLAG_CAPTURED),
+        GameEvent::PlayerKnockedOut { .. } => Some(AudioAsset::ANNOUNCER_RING_OUT),
+        GameEvent::MatchPhaseChanged(MatchPhase::Overtime | MatchPhase::SuddenDeath) => {
+            Some(AudioAsset::ANNOUNCER_OVERTIME)
+        }
+        GameEvent::MatchEnded { .. } => Some(AudioAsset::ANNOUNCER_MATCH_END),
+        _ => None,
+    }
+}
+
 pub fn to_audio_asset(sound_id: String) -> Option<AudioAsset> {
     match sound_id.as_str() {
         "wind" => Some(AudioAsset::WIND),
@@ -461,6 +512,10 @@ pub fn to_audio_asset(sound_id: String) -> Option<AudioAsset> {
         "die" => Some(AudioAsset::DIE),
         "spawn" => Some(AudioAsset::SPAWN),
         "wind_weather" => Some(AudioAsset::WIND_WEATHER),
+        "snow_weather" => Some(AudioAsset::WEATHER_ENV),
+        "fog_weather" => Some(AudioAsset::FOG),
+        "storm_weather" => Some(AudioAsset::STORM),
+        "thunder" => Some(AudioAsset::THUNDER),
         "pickup" => Some(AudioAsset::PICKUP),
         "ice" => Some(AudioAsset::ICE),
         "flash" => Some(AudioAsset::FLASH),
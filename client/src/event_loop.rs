@@ -16,8 +16,10 @@ use winit::{
     window::WindowBuilder,
 };
 
+use common::core::events::GameplayEventQueue;
 use common::core::states::{GameState, ParticleQueue};
 
+use crate::audio::SoundQueue;
 use crate::inputs::Input;
 use crate::State;
 
@@ -26,6 +28,8 @@ pub struct PlayerLoop {
     inputs: Sender<Input>,
     game_state: Arc<Mutex<GameState>>,
     particle_queue: Arc<Mutex<ParticleQueue>>,
+    sound_queue: Arc<Mutex<SoundQueue>>,
+    gameplay_events: Arc<Mutex<GameplayEventQueue>>,
     // current player id
     client_id: u8,
     // audio flag
@@ -41,6 +45,8 @@ impl PlayerLoop {
         commands: Sender<Input>,
         game_state: Arc<Mutex<GameState>>,
         particle_queue: Arc<Mutex<ParticleQueue>>,
+        sound_queue: Arc<Mutex<SoundQueue>>,
+        gameplay_events: Arc<Mutex<GameplayEventQueue>>,
         id: u8,
         audio_flag: Arc<AtomicBool>,
         audio_thread_handle: JoinHandle<()>,
@@ -49,6 +55,8 @@ impl PlayerLoop {
             inputs: commands,
             game_state,
             particle_queue,
+            sound_queue,
+            gameplay_events,
             client_id: id,
             audio_flag,
             audio_thread_handle,
@@ -94,10 +102,6 @@ impl PlayerLoop {
         //To check
         let mut last_render_time = instant::Instant::now();
 
-        // get config
-        let config_instance = ConfigurationManager::get_configuration();
-        let weather_config = config_instance.game.weather_config.clone();
-
         event_loop.run_return(move |event, _, control_flow| {
             control_flow.set_poll();
             match event {
@@ -199,7 +203,10 @@ impl PlayerLoop {
                     let dt = now - last_render_time;
                     last_render_time = now;
 
-                    state.update(self.game_state.clone(), self.particle_queue.clone(), dt, weather_config.clone());
+                    // read every frame, the server may have synced a reloaded config
+                    let config = ConfigurationManager::get_configuration();
+                    let weather_config = config.game.weather_config.clone();
+                    state.update(self.game_state.clone(), self.particle_queue.clone(), self.sound_queue.clone(), self.gameplay_events.clone(), dt, weather_config);
 
                     // send camera position to input processor
                     self.inputs.send(Input::Camera {
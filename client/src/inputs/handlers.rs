@@ -95,27 +95,18 @@ fn handle_press_release_key(
     protocol: &mut Protocol,
     client_id: u8,
 ) -> bool {
-    match button_state {
-        // if pressed or held, keep sending command
-        ButtonState::Pressed | ButtonState::Held => {
-            let message: Message = Message::new(
-                HostRole::Client(client_id),
-                Payload::Command(command.clone()),
-            );
-            protocol.send_message(&message).expect("send message fails");
-            debug!("Sent command: {:?}", command);
-        }
-        // if released, do the corresponding action and then remove from the held map
-        ButtonState::Released => {
-            let message: Message = Message::new(
-                HostRole::Client(client_id),
-                Payload::Command(command.clone()),
-            );
-            protocol.send_message(&message).expect("send message fails");
-            debug!("Sent command: {:?}", command);
-            return false;
-        }
-        _ => {}
+    let command = match button_state {
+        // if pressed, begin the action, the server keeps track of how long it is held
+        ButtonState::Pressed => command,
+        // if released, end the action and then remove from the held map
+        ButtonState::Released => command.on_release(),
+        _ => return true,
     };
-    true
+    let message: Message = Message::new(
+        HostRole::Client(client_id),
+        Payload::Command(command.clone()),
+    );
+    protocol.send_message(&message).expect("send message fails");
+    debug!("Sent command: {:?}", command);
+    !matches!(button_state, ButtonState::Released)
 }
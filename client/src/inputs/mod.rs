@@ -11,11 +11,14 @@ use winit::event::{DeviceEvent, ElementState, KeyboardInput, VirtualKeyCode};
 
 use common::communication::commons::Protocol;
 use common::communication::message::{HostRole, Message, Payload};
+use common::configs::ConfigurationManager;
 use common::core::choices::FinalChoices;
 use common::core::command::Command::{
     AreaAttack, Attack, CastPowerUp, Dash, Die, Flash, Jump, Refill, Spawn,
 };
-use common::core::command::{CheatCodeControl, CheatKeyWeather, Command, ServerSync};
+use common::core::command::{
+    ChargePhase, CheatCodeControl, CheatKeyWeather, Command, ServerSync,
+};
 use common::core::powerup_system::PowerUp;
 
 use crate::inputs::handlers::{handle_camera_update, handle_game_key_input, GameKeyKind};
@@ -102,42 +105,21 @@ impl InputEventProcessor {
             VirtualKeyCode::E => Some((GameKeyKind::Pressable, AreaAttack)),
 
             // match PressRelease keys
-
-            // VirtualKeyCode::LShift => Some((GameKeyKind::PressRelease, Spawn)),
-
-            //  Blizzard,
-            //     WindEnhancement,
-            //     Dash,
-            //     Flash,
-            //     Invisible,
-            //     TripleJump,
-            //     Invincible, // maybe
-
-            // cheatkeys
-            VirtualKeyCode::F1 => Some((
-                GameKeyKind::Pressable,
-                Command::CheatCode(PowerUp::Blizzard),
-            )),
-            VirtualKeyCode::F2 => Some((
-                GameKeyKind::Pressable,
-                Command::CheatCode(PowerUp::WindEnhancement),
-            )),
-            VirtualKeyCode::F3 => Some((GameKeyKind::Pressable, Command::CheatCode(PowerUp::Dash))),
-            VirtualKeyCode::F4 => {
-                Some((GameKeyKind::Pressable, Command::CheatCode(PowerUp::Flash)))
-            }
-            VirtualKeyCode::F5 => Some((
-                GameKeyKind::Pressable,
-                Command::CheatCode(PowerUp::Invisible),
-            )),
-            VirtualKeyCode::F6 => Some((
-                GameKeyKind::Pressable,
-                Command::CheatCode(PowerUp::TripleJump),
-            )),
-            VirtualKeyCode::F7 => Some((
-                GameKeyKind::Pressable,
-                Command::CheatCode(PowerUp::Invincible),
+            VirtualKeyCode::Q => Some((
+                GameKeyKind::PressRelease,
+                Command::ChargeAttack(ChargePhase::Begin),
             )),
+
+            // cheatkeys, F1-F7 give the power-ups in the order of the game config
+            VirtualKeyCode::F1 => cheat_power_up(0),
+            VirtualKeyCode::F2 => cheat_power_up(1),
+            VirtualKeyCode::F3 => cheat_power_up(2),
+            VirtualKeyCode::F4 => cheat_power_up(3),
+            VirtualKeyCode::F5 => cheat_power_up(4),
+            VirtualKeyCode::F6 => cheat_power_up(5),
+            VirtualKeyCode::F7 => cheat_power_up(6),
+            // collider outlines and attack queries streamed by the server, toggled
+            VirtualKeyCode::F8 => Some((GameKeyKind::Pressable, Command::PhysicsDebug)),
             VirtualKeyCode::F9 => Some((
                 GameKeyKind::Pressable,
                 Command::CheatCodeControl(CheatCodeControl::Deactivate),
@@ -150,6 +132,18 @@ impl InputEventProcessor {
                 GameKeyKind::Pressable,
                 Command::WeatherCheatKey(CheatKeyWeather::Rain),
             )),
+            VirtualKeyCode::LBracket => Some((
+                GameKeyKind::Pressable,
+                Command::WeatherCheatKey(CheatKeyWeather::Snow),
+            )),
+            VirtualKeyCode::RBracket => Some((
+                GameKeyKind::Pressable,
+                Command::WeatherCheatKey(CheatKeyWeather::Fog),
+            )),
+            VirtualKeyCode::Backslash => Some((
+                GameKeyKind::Pressable,
+                Command::WeatherCheatKey(CheatKeyWeather::Storm),
+            )),
             VirtualKeyCode::Back => Some((
                 GameKeyKind::Pressable,
                 Command::WeatherCheatKey(CheatKeyWeather::Reset),
@@ -247,9 +241,12 @@ impl InputEventProcessor {
                     }
 
                     // Signal the poller to send data as soon as possible
-                    // Should be only for "Pressable" keys since otherwise the sampling rate will be inconsistent
+                    // Should be only for "Pressable" and "PressRelease" keys since otherwise the sampling rate will be inconsistent
                     // This optimization will be significant if we decide to use a longer polling interval (e.g. > 100ms) to save bandwidth
-                    if let Some((GameKeyKind::Pressable, _)) = Self::map_key(key_code) {
+                    // For "PressRelease" keys it also keeps the held time measured by the server accurate
+                    if let Some((GameKeyKind::Pressable | GameKeyKind::PressRelease, _)) =
+                        Self::map_key(key_code)
+                    {
                         let (lock, cvar) = &*self.poller_signal;
                         let mut signal = lock.lock().unwrap();
                         *signal = true;
@@ -403,3 +400,12 @@ impl InputEventProcessor {
         }
     }
 }
+
+fn cheat_power_up(index: usize) -> Option<(GameKeyKind, Command)> {
+    let config_instance = ConfigurationManager::get_configuration();
+    let definition = config_instance.game.powerup_config.power_ups.get(index)?;
+    Some((
+        GameKeyKind::Pressable,
+        Command::CheatCode(PowerUp(definition.id.clone())),
+    ))
+}
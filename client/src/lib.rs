@@ -18,7 +18,7 @@ use wgpu_glyph::{
 use winit::event::*;
 use winit::window::Window;
 
-use audio::CURR_DISP;
+use audio::{SoundQueue, CURR_DISP};
 use common::configs;
 use common::configs::game_config::ConfigWeather;
 use common::configs::parameters::{
@@ -28,13 +28,17 @@ use common::configs::*;
 use common::core::choices::OBJECT_PLAYER_MODEL;
 use common::core::command::Command;
 use common::core::events;
+use common::core::events::{GameEvent, GameplayEventQueue};
+use common::core::physics_debug::{PhysicsDebugFrame, PHYSICS_DEBUG_PERIOD};
 use common::core::powerup_system::StatusEffect::Power;
 use common::core::powerup_system::{
-    PowerUp, PowerUpEffects, PowerUpStatus, StatusEffect, POWER_UP_TO_EFFECT_MAP,
+    PowerUpEffect, PowerUpEffects, PowerUpStatus, StatusEffect,
 };
 use common::core::states::GameLifeCycleState::Ended;
 use common::core::states::GameLifeCycleState::Running;
 use common::core::states::{GameLifeCycleState, GameState, ParticleQueue};
+use common::core::status_effects::{effective_stat, Stat};
+use common::core::stats::MatchSummary;
 use common::core::weather::Weather;
 use model::Vertex;
 use other_players::OtherPlayer;
@@ -43,6 +47,8 @@ use resources::{KOROK_MTL_LIB, KOROK_MTL_LIBRARY_PATH};
 use crate::animation::AnimatedModel;
 use crate::inputs::Input;
 use crate::model::{Model, StaticModel};
+use crate::screen::objects::Screen;
+use crate::screen::text_feed::{self, TextFeed};
 
 mod animation;
 pub mod audio;
@@ -88,7 +94,7 @@ struct State {
     glyph_brush: GlyphBrush<()>,
     color_bind_group_layout: wgpu::BindGroupLayout,
     animation_controller: animation::AnimationController,
-    previous_game_life_cycle_state: GameLifeCycleState,
+    match_summary: Option<MatchSummary>,
 }
 
 impl State {
@@ -744,8 +750,6 @@ impl State {
             })
             .collect();
 
-        let previous_game_life_cycle_state = GameLifeCycleState::Waiting;
-
         Self {
             window,
             surface,
@@ -768,7 +772,7 @@ impl State {
             glyph_brush,
             color_bind_group_layout,
             animation_controller,
-            previous_game_life_cycle_state,
+            match_summary: None,
         }
     }
 
@@ -865,9 +869,13 @@ impl State {
         &mut self,
         game_state: Arc<Mutex<GameState>>,
         particle_queue: Arc<Mutex<ParticleQueue>>,
+        sound_queue: Arc<Mutex<SoundQueue>>,
+        gameplay_events: Arc<Mutex<GameplayEventQueue>>,
         dt: instant::Duration,
         weather_config: ConfigWeather,
     ) {
+        let gameplay_events = std::mem::take(&mut gameplay_events.lock().unwrap().events);
+
         // Only update if we're in game/lobby
         if self.display.current != self.display.game_display.clone()
             && self.display.current != "display:lobby"
@@ -882,150 +890,88 @@ impl State {
 
         let game_state_clone = game_state.lock().unwrap().clone();
 
-        // check whether all players are ready, if so launch the game
-        match game_state_clone.life_cycle_state {
-            GameLifeCycleState::Running(..) => {
-                if self.previous_game_life_cycle_state == GameLifeCycleState::Waiting {
-                    // should only run once
-                    //println!("once");
+        // launch the game or show the end screen when the server says so
+        let mut match_ended = false;
+        for event in gameplay_events {
+            if let Some(announcement) = audio::announcement(&event) {
+                sound_queue.lock().unwrap().announce(announcement);
+            }
+            match event {
+                GameEvent::MatchStarted => {
                     self.display.change_to(self.display.game_display.clone());
                     self.add_game_particles();
                     self.window.set_cursor_visible(false);
-                    /*
-                    self.display.current = self.display.game_display.clone();
-                    *CURR_DISP.get().unwrap().lock().unwrap() = self.display.current.clone();
-                    */
-                }
-
-                let change_rate_coef = weather_config.environment_lighting_rate_change_coefficient;
-                // update lighting based on weather
-                match game_state_clone.world.weather {
-                    Some(Weather::Rainy) => {
-                        let rainy_ambient_multiplier =
-                            weather_config.rainy_weather_ambient_multiplier;
-                        self.gradual_convert_lighting(
-                            (
-                                rainy_ambient_multiplier,
-                                rainy_ambient_multiplier,
-                                rainy_ambient_multiplier,
-                            ),
-                            change_rate_coef,
-                        );
-                    }
-                    _ => {
-                        let default_ambient_multiplier =
-                            weather_config.default_weather_ambient_multiplier;
-                        self.gradual_convert_lighting(
-                            (
-                                default_ambient_multiplier,
-                                default_ambient_multiplier,
-                                default_ambient_multiplier,
-                            ),
-                            change_rate_coef,
-                        );
+                    if let Some(screen) = self.game_screen_mut() {
+                        screen.text_feeds.iter_mut().for_each(TextFeed::clear);
                     }
                 }
+                GameEvent::MatchEnded { winner } => {
+                    self.show_end_screen(winner, &game_state_clone, &weather_config);
+                    match_ended = true;
+                }
+                GameEvent::LightningStruck(_) => {
+                    // flash, the weather lighting fades back in gradually
+                    let flash = weather_config.lightning_flash_ambient_multiplier;
+                    self.camera_state.camera.ambient_multiplier = glm::vec3(flash, flash, flash);
+                }
+                GameEvent::PhysicsDebug(ref frame) => self.add_physics_debug_lines(frame),
+                _ => {}
             }
-            GameLifeCycleState::_Ended => {}
-
-            // check if the game has ended and set corresponding end screen
-            current_life_cycle_state => {
-                if let GameLifeCycleState::Running(_) = self.previous_game_life_cycle_state {
-                    {
-                        if current_life_cycle_state != self.previous_game_life_cycle_state {
-                            self.window.set_cursor_visible(true);
-                            //println!("{:?}", game_state_clone.life_cycle_state);
-
-                            if !game_state_clone.prev_winner.is_some() {
-                                return;
-                            }
-
-                            let (winner, winner_custom) = game_state_clone.prev_winner.unwrap();
-
-                            if winner == self.client_id as u32 {
-                                self.display.change_to("display:victory".to_owned());
-                            } else {
-                                self.display.change_to("display:defeat".to_owned());
-                            }
-                            //*CURR_DISP.get().unwrap().lock().unwrap() = self.display.current.clone();
-
-                            // Reset camera and player for lobby
-                            self.camera_state.camera.position = glm::vec3(
-                                DEFAULT_CAMERA_POS.0,
-                                DEFAULT_CAMERA_POS.1,
-                                DEFAULT_CAMERA_POS.2,
-                            );
-                            self.camera_state.camera.target = glm::vec3(
-                                DEFAULT_CAMERA_TARGET.0,
-                                DEFAULT_CAMERA_TARGET.1,
-                                DEFAULT_CAMERA_TARGET.2,
-                            );
-                            self.camera_state.projection.fovy = DEFAULT_CAMERA_FOV.to_radians();
-
-                            let reset_ambient_multiplier =
-                                weather_config.default_weather_ambient_multiplier;
-                            self.camera_state.camera.ambient_multiplier = glm::vec3(
-                                reset_ambient_multiplier,
-                                reset_ambient_multiplier,
-                                reset_ambient_multiplier,
-                            );
 
-                            self.camera_state.camera_uniform.update_view_proj(
-                                &self.camera_state.camera,
-                                &self.camera_state.projection,
-                            );
-                            self.queue.write_buffer(
-                                &self.camera_state.camera_buffer,
-                                0,
-                                bytemuck::cast_slice(&[self.camera_state.camera_uniform]),
-                            );
-
-                            if let Some(scene) =
-                                self.display.scene_map.get_mut("scene:end_screen_scene")
-                            {
-                                if let Some(node) = scene.scene_graph.get_mut("object:winner_model")
-                                {
-                                    node.model = Some(winner_custom.model.clone());
-                                    node.colors = Some(winner_custom.color.clone());
-                                    node.materials = Some(winner_custom.materials.clone());
-                                }
-                                scene.draw_scene_dfs();
-                            }
-
-                            let loser_screen =
-                                self.display.screen_map.get_mut("screen:loser").unwrap();
-                            let winner_icon_index =
-                                *loser_screen.icon_id_map.get("icon:winner_number").unwrap();
-                            loser_screen.icons[winner_icon_index].texture =
-                                format!("icon:player_{winner}");
-
-                            self.previous_game_life_cycle_state = GameLifeCycleState::_Ended;
-
-                            return;
-                        }
-                    }
+            // kill feed and announcements
+            for (feed_id, line) in text_feed::feed_lines(
+                &event,
+                &game_state_clone,
+                &game_config.powerup_config,
+            ) {
+                if let Some(feed) = self
+                    .game_screen_mut()
+                    .and_then(|screen| screen.text_feed_mut(feed_id))
+                {
+                    feed.push(line);
                 }
             }
         }
+        // the end screen is up, the game no longer needs updating
+        if match_ended {
+            return;
+        }
+        if let Some(screen) = self.game_screen_mut() {
+            for feed in screen.text_feeds.iter_mut() {
+                feed.update(dt.as_secs_f32());
+            }
+        }
 
-        self.previous_game_life_cycle_state = game_state_clone.life_cycle_state.clone();
+        if let GameLifeCycleState::Running(..) = game_state_clone.life_cycle_state {
+            let change_rate_coef = weather_config.environment_lighting_rate_change_coefficient;
+            // update lighting based on weather
+            let ambient_multiplier = match game_state_clone.world.weather {
+                Some(Weather::Rainy) => weather_config.rainy_weather_ambient_multiplier,
+                Some(Weather::Snowy) => weather_config.snowy_weather_ambient_multiplier,
+                Some(Weather::Foggy) => weather_config.foggy_weather_ambient_multiplier,
+                Some(Weather::Stormy) => weather_config.stormy_weather_ambient_multiplier,
+                _ => weather_config.default_weather_ambient_multiplier,
+            };
+            self.gradual_convert_lighting(
+                (ambient_multiplier, ambient_multiplier, ambient_multiplier),
+                change_rate_coef,
+            );
+        }
 
         // game state to scene graph conversion and update
         {
-            // new block because we need to drop scene_id before continuing
-            // it borrows self
             let scene_id = self
                 .display
                 .groups
                 .get(&self.display.game_display)
                 .unwrap()
                 .scene
-                .as_ref()
+                .clone()
                 .unwrap();
 
             self.display
                 .scene_map
-                .get_mut(scene_id)
+                .get_mut(&scene_id)
                 .unwrap()
                 .load_game_state(
                     game_state.lock().unwrap(),
@@ -1114,32 +1060,14 @@ impl State {
                     }
                 }
 
-                // update the wind charge
-                {
-                    let screen_id = self
-                        .display
-                        .groups
-                        .get(&self.display.game_display)
-                        .unwrap()
-                        .screen
-                        .as_ref()
-                        .unwrap();
-
-                    let screen = self.display.screen_map.get_mut(screen_id).unwrap();
-
-                    // update player ammo barhead
-                    let ind_barhead = *screen.icon_id_map.get("icon:barhead").unwrap();
-                    screen.icons[ind_barhead].inst_range = 0..1;
-
-                    // update player number of charges
-                    let ind_charge = *screen.icon_id_map.get("icon:charge").unwrap();
-                    screen.icons[ind_charge].inst_range = 0..self.player.wind_charge;
-
-                    // Update empty ammo
-                    let ind_empty_ammo = *screen.icon_id_map.get("icon:empty_charge").unwrap();
-                    screen.icons[ind_empty_ammo].inst_range = self.player.wind_charge..10;
-                    // Adjust the range as per your total ammos
-                }
+                // update the HUD
+                self.display.update_hud(
+                    &self.player,
+                    &game_state_clone,
+                    &game_config,
+                    &physics_config,
+                    &self.queue,
+                );
 
                 // update weather icon
                 {
@@ -1179,165 +1107,10 @@ impl State {
                     };
                 }
 
-                // update cooldowns
-                // hard coded for now... TODO: separate function
-                // is it necessary? would need to pass around lots of references
-                // might be better to create dedicated function in screen/command_handlers
-                {
-                    let atk_load = String::from("icon:atk_forward_overlay");
-                    let atk_area_load = String::from("icon:atk_wave_overlay");
-
-                    if self.player.on_cooldown.contains_key(&Command::Attack) {
-                        let cd_left = self.player.on_cooldown.get(&Command::Attack).unwrap()
-                            / physics_config.attack_config.attack_cooldown;
-                        self.display.transition_map.insert(
-                            atk_load.clone(),
-                            screen::object_transitions::Transition::SqueezeDown(cd_left),
-                        );
-                    } else {
-                        self.display.transition_map.remove(&atk_load);
-                    }
-
-                    if self.player.on_cooldown.contains_key(&Command::AreaAttack) {
-                        let cd_left = self.player.on_cooldown.get(&Command::AreaAttack).unwrap()
-                            / physics_config.attack_config.area_attack_cooldown;
-                        self.display.transition_map.insert(
-                            atk_area_load.clone(),
-                            screen::object_transitions::Transition::SqueezeDown(cd_left),
-                        );
-                    } else {
-                        self.display.transition_map.remove(&atk_area_load);
-                    }
-                }
-
-                // update for powerup
-                {
-                    let screen_id = self
-                        .display
-                        .groups
-                        .get(&self.display.game_display)
-                        .unwrap()
-                        .screen
-                        .as_ref()
-                        .unwrap();
-
-                    let screen = self.display.screen_map.get_mut(screen_id).unwrap();
-                    let ind_atk_ult = *screen.icon_id_map.get("icon:atk_ult_specific").unwrap();
-
-                    let prev_transp = screen.icons[ind_atk_ult].tint[3];
-                    if let Some(power_up) = self.player.power_up.as_ref() {
-                        // Adjust the properties for both icons
-                        match power_up.0 {
-                            PowerUp::Blizzard => {
-                                screen.icons[ind_atk_ult].texture =
-                                    String::from("icon:power_blizzard");
-                            }
-                            PowerUp::WindEnhancement => {
-                                screen.icons[ind_atk_ult].texture = String::from("icon:power_wind");
-                            }
-                            PowerUp::Dash => {
-                                screen.icons[ind_atk_ult].texture = String::from("icon:power_dash");
-                            }
-                            PowerUp::Flash => {
-                                screen.icons[ind_atk_ult].texture =
-                                    String::from("icon:power_flash");
-                            }
-                            PowerUp::Invisible => {
-                                screen.icons[ind_atk_ult].texture =
-                                    String::from("icon:power_invisible");
-                            }
-                            PowerUp::TripleJump => {
-                                screen.icons[ind_atk_ult].texture =
-                                    String::from("icon:power_triple_jump");
-                            }
-                            PowerUp::Invincible => {
-                                screen.icons[ind_atk_ult].texture =
-                                    String::from("icon:power_invincible");
-                            }
-                        }
-                        screen.icons[ind_atk_ult].tint[3] = 1.0;
-                    } else {
-                        // Reset the properties for both icons to their default values
-                        screen.icons[ind_atk_ult].tint[3] = 0.0;
-                    }
-                    if prev_transp != screen.icons[ind_atk_ult].tint[3] {
-                        let tint = screen.icons[ind_atk_ult].tint;
-                        for v in &mut screen.icons[ind_atk_ult].vertices {
-                            v.color = tint.into();
-                        }
-                        self.queue.write_buffer(
-                            &screen.icons[ind_atk_ult].vbuf,
-                            0,
-                            bytemuck::cast_slice(&screen.icons[ind_atk_ult].vertices),
-                        );
-                    }
-                }
-
-                // update cooldown for powerup
-                {
-                    let screen_id = self
-                        .display
-                        .groups
-                        .get(&self.display.game_display)
-                        .unwrap()
-                        .screen
-                        .as_ref()
-                        .unwrap();
-
-                    let screen = self.display.screen_map.get_mut(screen_id).unwrap();
-
-                    let power_up_overlay_id = "icon:atk_powerup_overlay";
-                    let ind_atk_powerup_overlay =
-                        *screen.icon_id_map.get(power_up_overlay_id).unwrap();
-
-                    if let Some((power_up, status)) = self.player.power_up.as_ref() {
-                        if *status == PowerUpStatus::Active {
-                            // Set the overlay icon texture to the corresponding "pure" power-up icon
-                            let power_up_overlay_texture = match power_up {
-                                PowerUp::Blizzard => "icon:power_blizzard_overlay",
-                                PowerUp::WindEnhancement => "icon:power_wind_overlay",
-                                PowerUp::Dash => "icon:power_dash_overlay",
-                                PowerUp::Flash => "icon:power_flash_overlay",
-                                PowerUp::Invisible => "icon:power_invisible_overlay",
-                                PowerUp::TripleJump => "icon:power_triple_jump_overlay",
-                                PowerUp::Invincible => "icon:power_invincible_overlay",
-                            };
-                            screen.icons[ind_atk_powerup_overlay].texture =
-                                String::from(power_up_overlay_texture);
-
-                            let power_up_status = POWER_UP_TO_EFFECT_MAP
-                                .get(&power_up.value())
-                                .unwrap_or(&StatusEffect::None);
-                            // Calculate the fraction of the power-up time left
-                            if let Some(time_left) = self.player.status_effects.get(power_up_status)
-                            {
-                                //TODO: store that const into config
-                                let fraction_left = *time_left / 10.0;
-
-                                // Apply the SqueezeDown transition
-                                self.display.transition_map.insert(
-                                    String::from(power_up_overlay_id),
-                                    screen::object_transitions::Transition::SqueezeDown(
-                                        fraction_left,
-                                    ),
-                                );
-                            }
-                        } else {
-                            // Remove the overlay icon if the power-up is not active
-                            self.display.transition_map.remove(power_up_overlay_id);
-                            screen.icons[ind_atk_powerup_overlay].texture =
-                                String::from("icon:empty");
-                        }
-                    } else {
-                        // in case you died holding a powerup
-                        screen.icons[ind_atk_powerup_overlay].texture = String::from("icon:empty");
-                    }
-                }
-
                 let player_loc = self
                     .display
                     .scene_map
-                    .get(scene_id)
+                    .get(&scene_id)
                     .unwrap()
                     .get_player_positions();
 
@@ -1357,10 +1130,13 @@ impl State {
 
             self.display
                 .scene_map
-                .get_mut(scene_id)
+                .get_mut(&scene_id)
                 .unwrap()
                 .draw_scene_dfs();
 
+            self.add_charge_particles(&game_state_clone, dt);
+            self.add_projectile_particles(&game_state_clone, dt);
+
             {
                 let particle_queue = particle_queue.lock().unwrap();
                 self.add_powerup_particles(game_state_clone, particle_queue, dt);
@@ -1417,6 +1193,81 @@ impl State {
         );
     }
 
+    fn game_screen_mut(&mut self) -> Option<&mut Screen> {
+        let screen_id = self
+            .display
+            .groups
+            .get(&self.display.game_display)?
+            .screen
+            .as_ref()?;
+        self.display.screen_map.get_mut(screen_id)
+    }
+
+    fn show_end_screen(
+        &mut self,
+        winner: u32,
+        game_state: &GameState,
+        weather_config: &ConfigWeather,
+    ) {
+        self.window.set_cursor_visible(true);
+
+        let winner_custom = match game_state.players_customization.get(&winner) {
+            Some(c) => c.clone(),
+            None => return,
+        };
+        self.match_summary = game_state.match_summary.clone();
+
+        if winner == self.client_id as u32 {
+            self.display.change_to("display:victory".to_owned());
+        } else {
+            self.display.change_to("display:defeat".to_owned());
+        }
+        //*CURR_DISP.get().unwrap().lock().unwrap() = self.display.current.clone();
+
+        // Reset camera and player for lobby
+        self.camera_state.camera.position = glm::vec3(
+            DEFAULT_CAMERA_POS.0,
+            DEFAULT_CAMERA_POS.1,
+            DEFAULT_CAMERA_POS.2,
+        );
+        self.camera_state.camera.target = glm::vec3(
+            DEFAULT_CAMERA_TARGET.0,
+            DEFAULT_CAMERA_TARGET.1,
+            DEFAULT_CAMERA_TARGET.2,
+        );
+        self.camera_state.projection.fovy = DEFAULT_CAMERA_FOV.to_radians();
+
+        let reset_ambient_multiplier = weather_config.default_weather_ambient_multiplier;
+        self.camera_state.camera.ambient_multiplier = glm::vec3(
+            reset_ambient_multiplier,
+            reset_ambient_multiplier,
+            reset_ambient_multiplier,
+        );
+
+        self.camera_state.camera_uniform.update_view_proj(
+            &self.camera_state.camera,
+            &self.camera_state.projection,
+        );
+        self.queue.write_buffer(
+            &self.camera_state.camera_buffer,
+            0,
+            bytemuck::cast_slice(&[self.camera_state.camera_uniform]),
+        );
+
+        if let Some(scene) = self.display.scene_map.get_mut("scene:end_screen_scene") {
+            if let Some(node) = scene.scene_graph.get_mut("object:winner_model") {
+                node.model = Some(winner_custom.model.clone());
+                node.colors = Some(winner_custom.color.clone());
+                node.materials = Some(winner_custom.materials.clone());
+            }
+            scene.draw_scene_dfs();
+        }
+
+        let loser_screen = self.display.screen_map.get_mut("screen:loser").unwrap();
+        let winner_icon_index = *loser_screen.icon_id_map.get("icon:winner_number").unwrap();
+        loser_screen.icons[winner_icon_index].texture = format!("icon:player_{winner}");
+    }
+
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         let output = self.surface.get_current_texture()?;
         let view = output
@@ -1472,6 +1323,47 @@ impl State {
         {
             self.animation_controller
                 .play_animation("dance".to_string(), "object:winner_model".to_string());
+
+            // render match stats
+            if let Some(summary) = &self.match_summary {
+                let text_size = 0.03 * size.height as f32;
+                let mut lines = vec![Text::new(
+                    "Player    Ring-outs    Deaths    Hits    Wind    Power-ups    Flag\n",
+                )
+                .with_color([0.0, 0.0, 0.0, 1.0])
+                .with_scale(text_size)];
+                let stats_text = summary
+                    .ranked_players()
+                    .into_iter()
+                    .map(|id| {
+                        let stats = &summary.players[&id];
+                        format!(
+                            "P{}    {}    {}    {}    {}    {}    {:.1}s\n",
+                            id,
+                            stats.ring_outs,
+                            stats.deaths,
+                            stats.attacks_landed,
+                            stats.wind_spent,
+                            stats.power_ups_used,
+                            stats.flag_time
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                for line in stats_text.iter() {
+                    lines.push(
+                        Text::new(line.as_str())
+                            .with_color([0.0, 0.0, 0.0, 1.0])
+                            .with_scale(text_size),
+                    );
+                }
+                self.glyph_brush.queue(Section {
+                    screen_position: (size.width as f32 * 0.5, size.height as f32 * 0.75),
+                    bounds: (size.width as f32, size.height as f32),
+                    text: lines,
+                    layout: Layout::default().h_align(HorizontalAlign::Center),
+                    ..Section::default()
+                });
+            }
         }
         // temporary fix
         else {
@@ -1480,6 +1372,9 @@ impl State {
         }
 
         if self.display.current == self.display.game_display.clone() {
+            self.display
+                .queue_match_clock(&mut self.glyph_brush, size.width, size.height);
+
             // render respawn cooldown
             if self.player.on_cooldown.contains_key(&Command::Spawn) {
                 let spawn_cooldown = self.player.on_cooldown.get(&Command::Spawn).unwrap();
@@ -1499,6 +1394,18 @@ impl State {
                 });
             }
         }
+        // kill feed, announcements and other text feeds of the current screen
+        let screen_id = self
+            .display
+            .groups
+            .get(&self.display.current)
+            .and_then(|g| g.screen.as_ref());
+        if let Some(screen) = screen_id.and_then(|id| self.display.screen_map.get(id)) {
+            for feed in screen.text_feeds.iter() {
+                feed.queue(&mut self.glyph_brush, size.width, size.height);
+            }
+        }
+
         // Draw the text!
         self.glyph_brush
             .draw_queued(
@@ -1573,6 +1480,26 @@ impl State {
         self.display.particles.systems.push(ribbon);
     }
 
+    /// Draws the physics debug frame until the next one comes
+    fn add_physics_debug_lines(&mut self, frame: &PhysicsDebugFrame) {
+        // a bit longer than the period of the stream, so that the overlay doesn't flicker
+        let show_time = PHYSICS_DEBUG_PERIOD * 1.5;
+        let lines = particles::ParticleSystem::new(
+            std::time::Duration::ZERO,
+            show_time * 10.0,
+            0.0,
+            glm::vec4(1.0, 1.0, 1.0, 1.0),
+            particles::debug_lines::DebugLineGenerator::from_frame(frame, show_time),
+            (
+                particles::constants::SOLID_IND,
+                particles::constants::SOLID_IND + 1,
+            ),
+            &self.device,
+            &mut self.rng,
+        );
+        self.display.particles.systems.push(lines);
+    }
+
     fn add_powerup_particles(
         &mut self,
         game_state: GameState,
@@ -1581,6 +1508,7 @@ impl State {
     ) {
         let config_instance = ConfigurationManager::get_configuration();
         let particle_config = config_instance.particles.clone();
+        let powerup_config = &config_instance.game.powerup_config;
 
         let powerup_players = game_state
             .players
@@ -1599,28 +1527,18 @@ impl State {
             let player_vel = player_state.physics.velocity;
 
             let (player_power_up, player_power_up_status) = player_state.power_up.clone().unwrap();
-            let mut aura_color_string = match player_power_up {
-                PowerUp::Blizzard => "blizzard",
-                _ => "default",
-            };
-
-            if player_power_up_status == PowerUpStatus::Active {
-                aura_color_string = match player_power_up {
-                    PowerUp::WindEnhancement => "wind_enhancement",
-                    PowerUp::Dash => "dash",
-                    PowerUp::Flash => "flash",
-                    PowerUp::Invisible => "invisibility",
-                    PowerUp::Invincible => "invincibility",
-                    PowerUp::TripleJump => "triple_jump",
-                    _ => "default",
-                }
-            }
+            let aura_color_string = powerup_config.definition(&player_power_up).map_or(
+                "default",
+                |d| match player_power_up_status {
+                    PowerUpStatus::Held => d.held_aura.as_str(),
+                    PowerUpStatus::Active => d.active_aura.as_str(),
+                },
+            );
 
-            let aura_color = *particle_config
-                .powerup_aura_particle_config
-                .aura_colors
+            let aura_colors = &particle_config.powerup_aura_particle_config.aura_colors;
+            let aura_color = *aura_colors
                 .get(aura_color_string)
-                .unwrap();
+                .unwrap_or_else(|| aura_colors.get("default").unwrap());
             particle_queue.add_particle(events::ParticleSpec::new(
                 events::ParticleType::POWERUP_AURA,
                 player_pos + player_vel * (dt.as_secs_f32()),
@@ -1633,6 +1551,111 @@ impl State {
         }
     }
 
+    // trail of the projectiles in flight, in the color of the active aura of their power-up
+    fn add_projectile_particles(&mut self, game_state: &GameState, dt: instant::Duration) {
+        let config_instance = ConfigurationManager::get_configuration();
+        let projectile_config = &config_instance.particles.projectile_particle_config;
+        let aura_colors = &config_instance
+            .particles
+            .powerup_aura_particle_config
+            .aura_colors;
+
+        for projectile in game_state.world.projectiles.values() {
+            let aura = config_instance
+                .game
+                .powerup_config
+                .definition(&projectile.power_up)
+                .map_or("default", |d| d.active_aura.as_str());
+            let color = *aura_colors
+                .get(aura)
+                .unwrap_or_else(|| aura_colors.get("default").unwrap());
+
+            let trail_gen = particles::gen::SphereGenerator::new(
+                projectile.position + projectile.velocity * (dt.as_secs_f32()),
+                projectile_config.linear_speed,
+                projectile_config.linear_variance,
+                PI,
+                projectile_config.angular_variance,
+                projectile_config.size,
+                projectile_config.size_variance,
+                projectile_config.size_growth,
+                false,
+            );
+            let trail = particles::ParticleSystem::new(
+                std::time::Duration::from_secs_f32(0.05),
+                projectile_config.time / config_instance.particles.time_divider,
+                projectile_config.gen_speed,
+                glm::vec4(color.0, color.1, color.2, color.3),
+                trail_gen,
+                (
+                    particles::constants::SOFT_CIRCLE_IND,
+                    particles::constants::SOFT_CIRCLE_IND + 1,
+                ),
+                &self.device,
+                &mut self.rng,
+            );
+            self.display.particles.systems.push(trail);
+        }
+    }
+
+    // aura around players charging an attack, growing with the charge level
+    fn add_charge_particles(&mut self, game_state: &GameState, dt: instant::Duration) {
+        let config_instance = ConfigurationManager::get_configuration();
+        let charge_config = &config_instance.particles.charge_particle_config;
+        let attack_config = &config_instance.physics.attack_config;
+
+        for (&player_id, player_state) in game_state.players.iter() {
+            let charge_time = match player_state.charge_time {
+                Some(charge_time) => charge_time,
+                None => continue,
+            };
+            if player_id != self.client_id as u32 && self.invisible_players.contains(&player_id) {
+                continue;
+            }
+
+            let level = attack_config.charge_level(charge_time);
+            let lerp = |min: f32, max: f32| min + (max - min) * level;
+            let color = game_state
+                .players_customization
+                .get(&player_id)
+                .and_then(|c| c.color.get(common::core::choices::LEAF_MESH))
+                .map_or(charge_config.default_color, |c| {
+                    (c.rgb_color[0], c.rgb_color[1], c.rgb_color[2], 1.0)
+                });
+
+            let charge_gen = particles::gen::CylinderGenerator::new(
+                player_state.transform.translation
+                    + player_state.physics.velocity * (dt.as_secs_f32()),
+                glm::vec3(0.0, 1.0, 0.0),
+                glm::vec3(0.0, 1.0, 0.0),
+                lerp(charge_config.min_r, charge_config.max_r),
+                charge_config.half_height,
+                charge_config.linear_speed,
+                charge_config.linear_variance,
+                PI,
+                charge_config.angular_variance,
+                lerp(charge_config.min_size, charge_config.max_size),
+                charge_config.size_variance,
+                charge_config.size_growth,
+                false,
+            );
+            let charge = particles::ParticleSystem::new(
+                std::time::Duration::from_secs_f32(0.05),
+                charge_config.time / config_instance.particles.time_divider,
+                lerp(charge_config.min_gen_speed, charge_config.max_gen_speed),
+                glm::vec4(color.0, color.1, color.2, color.3),
+                charge_gen,
+                (
+                    particles::constants::SOFT_CIRCLE_IND,
+                    particles::constants::SOFT_CIRCLE_IND + 1,
+                ),
+                &self.device,
+                &mut self.rng,
+            );
+            self.display.particles.systems.push(charge);
+        }
+    }
+
     fn load_particles(&mut self, mut particle_queue: MutexGuard<ParticleQueue>) {
         let config_instance = ConfigurationManager::get_configuration();
         let game_config = config_instance.game.clone();
@@ -1640,26 +1663,15 @@ impl State {
         let particle_config = config_instance.particles.clone();
 
         // modify when wind enhanced
-        let wind_enhanced = self
-            .player
-            .status_effects
-            .contains_key(&StatusEffect::Power(PowerUpEffects::EnhancedWind));
-        let scalar = game_config.powerup_config.wind_enhancement_scalar;
-        let max_attack_angle = if wind_enhanced {
-            physics_config.attack_config.max_attack_angle * scalar
-        } else {
-            physics_config.attack_config.max_attack_angle
-        };
-        let max_attack_dist = if wind_enhanced {
-            physics_config.attack_config.max_attack_dist * scalar
-        } else {
-            physics_config.attack_config.max_attack_dist
-        };
-        let max_area_attack_dist = if wind_enhanced {
-            physics_config.attack_config.max_area_attack_dist * scalar
-        } else {
-            physics_config.attack_config.max_area_attack_dist
-        };
+        let scalar = effective_stat(
+            &self.player.status_effects,
+            Stat::AttackRange,
+            1.0,
+            &game_config.status_effects,
+        );
+        let max_attack_angle = physics_config.attack_config.max_attack_angle * scalar;
+        let max_attack_dist = physics_config.attack_config.max_attack_dist * scalar;
+        let max_area_attack_dist = physics_config.attack_config.max_area_attack_dist * scalar;
 
         // attack const
         let attack_cd = physics_config.attack_config.attack_cooldown;
@@ -1783,13 +1795,29 @@ impl State {
                     self.display.particles.systems.push(system);
                 }
                 events::ParticleType::BLIZZARD => {
+                    // the cone covers the area debuff of the power-up emitting it
+                    let (max_angle, max_dist) = game_config
+                        .powerup_config
+                        .power_ups
+                        .iter()
+                        .filter(|d| d.particle == Some(events::ParticleType::BLIZZARD))
+                        .flat_map(|d| d.effects.iter())
+                        .find_map(|e| match e {
+                            PowerUpEffect::AreaDebuff {
+                                max_angle,
+                                max_dist,
+                                ..
+                            } => Some((*max_angle, *max_dist)),
+                            _ => None,
+                        })
+                        .unwrap_or_default();
                     let time = particle_config.blizzard_particle_config.time / time_divider;
                     let blizz_gen = particles::gen::ConeGenerator::new(
                         p.position,
                         p.direction,
                         p.up,
-                        game_config.powerup_config.blizzard_max_attack_angle,
-                        game_config.powerup_config.blizzard_max_attack_dist / time,
+                        max_angle,
+                        max_dist / time,
                         particle_config.blizzard_particle_config.linear_variance,
                         PI,
                         particle_config.blizzard_particle_config.angular_variance,
@@ -1905,6 +1933,128 @@ impl State {
                     );
                     self.display.particles.systems.push(atk);
                 }
+                events::ParticleType::SNOW => {
+                    let time = 4.;
+                    let snow_gen = particles::gen::RainGenerator::new(
+                        p.position + Vec3::new(0., 15., 0.),
+                        (20.0, 15.0, 20.0),
+                        p.direction,
+                        1.0,
+                        0.3,
+                        40.0,
+                        10.0,
+                        0.0,
+                        false,
+                    );
+                    let snow = particles::ParticleSystem::new(
+                        std::time::Duration::from_secs_f32(1.0),
+                        time,
+                        600.0,
+                        p.color,
+                        snow_gen,
+                        (
+                            particles::constants::SNOW_BASE_IND,
+                            particles::constants::SNOW_BASE_IND
+                                + particles::constants::SNOW_NUM_TEX_TYPES,
+                        ),
+                        &self.device,
+                        &mut self.rng,
+                    );
+                    self.display.particles.systems.push(snow);
+                }
+                events::ParticleType::FOG => {
+                    let time = 3.;
+                    let fog_gen = particles::gen::RainGenerator::new(
+                        p.position + Vec3::new(0., 1., 0.),
+                        (25.0, 3.0, 25.0),
+                        glm::vec3(p.direction.x, 0.0, p.direction.z) + Vec3::new(0., 0.01, 0.),
+                        0.1,
+                        0.1,
+                        800.0,
+                        200.0,
+                        0.0,
+                        false,
+                    );
+                    let fog = particles::ParticleSystem::new(
+                        std::time::Duration::from_secs_f32(1.0),
+                        time,
+                        150.0,
+                        p.color,
+                        fog_gen,
+                        (
+                            particles::constants::SOFT_CIRCLE_IND,
+                            particles::constants::SOFT_CIRCLE_IND + 1,
+                        ),
+                        &self.device,
+                        &mut self.rng,
+                    );
+                    self.display.particles.systems.push(fog);
+                }
+                events::ParticleType::TELEGRAPH => {
+                    // marks the area of the strike until it hits
+                    let time = game_config.weather_config.lightning_warning_time;
+                    let telegraph_gen = particles::gen::CylinderGenerator::new(
+                        p.position,
+                        p.direction,
+                        p.up,
+                        game_config.weather_config.lightning_radius,
+                        0.2,
+                        1.5,
+                        0.3,
+                        PI,
+                        0.0,
+                        30.0,
+                        5.0,
+                        0.0,
+                        false,
+                    );
+                    let telegraph = particles::ParticleSystem::new(
+                        std::time::Duration::from_secs_f32(time),
+                        0.5,
+                        400.0,
+                        p.color,
+                        telegraph_gen,
+                        (
+                            particles::constants::SOFT_CIRCLE_IND,
+                            particles::constants::SOFT_CIRCLE_IND + 1,
+                        ),
+                        &self.device,
+                        &mut self.rng,
+                    );
+                    self.display.particles.systems.push(telegraph);
+                }
+                events::ParticleType::LIGHTNING => {
+                    let time = 0.3;
+                    let lightning_gen = particles::gen::CylinderGenerator::new(
+                        p.position + Vec3::new(0., 15., 0.),
+                        p.direction,
+                        p.up,
+                        0.3,
+                        15.0,
+                        5.0,
+                        0.5,
+                        PI,
+                        0.0,
+                        60.0,
+                        10.0,
+                        5.0,
+                        false,
+                    );
+                    let lightning = particles::ParticleSystem::new(
+                        std::time::Duration::from_secs_f32(0.1),
+                        time,
+                        20000.0,
+                        p.color,
+                        lightning_gen,
+                        (
+                            particles::constants::STREAK_IND,
+                            particles::constants::STREAK_IND + 1,
+                        ),
+                        &self.device,
+                        &mut self.rng,
+                    );
+                    self.display.particles.systems.push(lightning);
+                }
                 events::ParticleType::WIND => {
                     let time = 1.2;
                     let gen = particles::ribbon::LineRibbonGenerator::new(
@@ -9,7 +9,6 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 
-use bus::Bus;
 use env_logger::Builder;
 use log::{debug, error, info};
 
@@ -19,7 +18,7 @@ use client::inputs::{Input, InputEventProcessor};
 use common::communication::commons::*;
 use common::communication::message::{HostRole, Message, Payload};
 use common::configs::*;
-use common::core::events::GameEvent;
+use common::core::events::{GameEvent, GameplayEventQueue};
 use common::core::states::{GameState, ParticleQueue};
 
 use async_std::task;
@@ -34,8 +33,7 @@ fn main() {
     let particle_queue = Arc::new(Mutex::new(ParticleQueue::default()));
     let sound_queue = Arc::new(Mutex::new(SoundQueue::default()));
 
-    let game_events_bus = Bus::new(1);
-    // let mut particle_rcvr = game_events_bus.add_rx();
+    let gameplay_events = Arc::new(Mutex::new(GameplayEventQueue::default()));
 
     let dest: SocketAddr = if cfg!(feature = "prod") {
         DEMO_SERVER_ADDR.parse().expect("server addr parse fails")
@@ -53,6 +51,8 @@ fn main() {
 
     // need to clone the protocol to be able to receive events and game states from different threads
     let mut write_protocol = protocol.try_clone().unwrap();
+    // the server measures our latency with the pings we echo
+    let echo_protocol = protocol.try_clone().unwrap();
     let mut read_protocol = protocol.try_clone_into().unwrap();
 
     // TODO: make the string path a Const/Static, debug mode for reconnection
@@ -110,6 +110,8 @@ fn main() {
         tx,
         game_state.clone(),
         particle_queue.clone(),
+        sound_queue.clone(),
+        gameplay_events.clone(),
         client_id,
         audio_flag,
         audio_thread_handle,
@@ -127,10 +129,12 @@ fn main() {
     thread::spawn(move || {
         recv_server_updates(
             read_protocol,
+            echo_protocol,
+            client_id,
             game_state.clone(),
             particle_queue,
             sound_queue.clone(),
-            game_events_bus,
+            gameplay_events,
         );
     });
 
@@ -154,17 +158,28 @@ fn dump_ids(session_data_path: PathBuf, client_id: u8, session_id: u64) {
 }
 
 fn init_connection(read_protocol: &mut Protocol) -> Result<(u8, u64), ()> {
+    let mut ids = None;
     while let Ok(msg) = read_protocol.read_message::<Message>() {
-        if let Message {
-            host_role: HostRole::Server,
-            payload: Payload::Init(incoming_ids),
-            ..
-        } = msg
-        {
-            info!("Received connection init: {:?}", incoming_ids);
-            return Ok(incoming_ids);
-        } else {
-            error!("Unexpected message before connection init: {:?}", msg);
+        match msg {
+            Message {
+                host_role: HostRole::Server,
+                payload: Payload::Init(incoming_ids),
+                ..
+            } => {
+                info!("Received connection init: {:?}", incoming_ids);
+                ids = Some(incoming_ids);
+            }
+            Message {
+                host_role: HostRole::Server,
+                payload: Payload::ConfigGameplay(gameplay),
+                ..
+            } => {
+                // follows the init, applied before anything reads the config
+                info!("Received the gameplay configs of the server");
+                ConfigurationManager::apply_gameplay(*gameplay);
+                return ids.ok_or(());
+            }
+            _ => error!("Unexpected message before connection init: {:?}", msg),
         }
     }
     Err(())
@@ -173,10 +188,12 @@ fn init_connection(read_protocol: &mut Protocol) -> Result<(u8, u64), ()> {
 #[allow(unreachable_code)]
 fn recv_server_updates(
     mut protocol: Protocol,
+    echo_protocol: Protocol,
+    client_id: u8,
     game_state: Arc<Mutex<GameState>>,
     particle_queue: Arc<Mutex<ParticleQueue>>,
     sound_queue: Arc<Mutex<SoundQueue>>,
-    _game_events: Bus<GameEvent>,
+    gameplay_events: Arc<Mutex<GameplayEventQueue>>,
 ) {
     // check for new state & update local game state
     loop {
@@ -210,6 +227,38 @@ fn recv_server_updates(
                     } => {
                         sound_queue.lock().unwrap().add_sound(s);
                     }
+                    Message {
+                        host_role: HostRole::Server,
+                        payload: Payload::ServerEvent(event),
+                        ..
+                    } => {
+                        debug!("Received gameplay event: {:?}", event);
+                        gameplay_events.lock().unwrap().add_event(event);
+                    }
+                    Message {
+                        host_role: HostRole::Server,
+                        payload: Payload::ConfigGameplay(gameplay),
+                        ..
+                    } => {
+                        // the server reloaded its tuning, our rules follow so that both agree
+                        info!("Received the reloaded gameplay configs of the server");
+                        ConfigurationManager::apply_gameplay(*gameplay);
+                    }
+                    Message {
+                        host_role: HostRole::Server,
+                        timestamp,
+                        payload: Payload::Ping,
+                    } => {
+                        // sent back as is, the server compares the timestamp to its clock
+                        let echo = Message {
+                            host_role: HostRole::Client(client_id),
+                            timestamp,
+                            payload: Payload::Ping,
+                        };
+                        if let Err(e) = echo_protocol.send_message(&echo) {
+                            error!("Error echoing ping: {:?}", e);
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -0,0 +1,96 @@
+use common::core::physics_debug::PhysicsDebugFrame;
+use nalgebra_glm as glm;
+
+use crate::particles::gen::ParticleGenerator;
+use crate::particles::ribbon::RibbonSection;
+use crate::particles::Particle;
+
+// width of the lines in cm, as the ribbons
+const LINE_WIDTH: f32 = 2.0;
+const STATIC_COLOR: [f32; 4] = [0.2, 0.9, 0.3, 0.8];
+const MOVING_COLOR: [f32; 4] = [0.2, 0.6, 1.0, 0.9];
+const SENSOR_COLOR: [f32; 4] = [1.0, 0.9, 0.2, 0.6];
+const QUERY_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.7];
+const HIT_COLOR: [f32; 4] = [1.0, 0.1, 0.1, 1.0];
+const BLOCKED_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+
+/// Still lines of the physics debug overlay, drawn as ribbons for show_time seconds.
+/// The particle lifetime of the system must be above 5 * show_time for the lines to show.
+pub struct DebugLineGenerator {
+    lines: Vec<(glm::Vec3, glm::Vec3, glm::Vec4)>, // from, to, color
+    show_time: f32,
+}
+
+impl DebugLineGenerator {
+    pub fn new(lines: Vec<(glm::Vec3, glm::Vec3, glm::Vec4)>, show_time: f32) -> Self {
+        Self { lines, show_time }
+    }
+
+    /// The collider outlines, the attack volumes and their rays, colored by kind
+    pub fn from_frame(frame: &PhysicsDebugFrame, show_time: f32) -> Self {
+        let mut lines = Vec::new();
+        let mut add = |new_lines: Vec<(glm::Vec3, glm::Vec3)>, color: [f32; 4]| {
+            lines.extend(
+                new_lines
+                    .into_iter()
+                    .map(|(from, to)| (from, to, glm::Vec4::from(color))),
+            );
+        };
+        for collider in frame.colliders.iter() {
+            let color = if collider.sensor {
+                SENSOR_COLOR
+            } else if collider.moving {
+                MOVING_COLOR
+            } else {
+                STATIC_COLOR
+            };
+            add(collider.outline.lines(), color);
+        }
+        for query in frame.queries.iter() {
+            add(query.volume.lines(), QUERY_COLOR);
+            for ray in query.rays.iter() {
+                let color = if ray.hit { HIT_COLOR } else { BLOCKED_COLOR };
+                add(vec![(ray.from, ray.to)], color);
+            }
+        }
+        Self::new(lines, show_time)
+    }
+}
+
+impl ParticleGenerator for DebugLineGenerator {
+    fn generate(
+        &self,
+        list: &mut Vec<Particle>,
+        _spawning_time: std::time::Duration,
+        _spawn_rate: f32,
+        _halflife: f32,
+        tex_range: (u32, u32),
+        _color: glm::Vec4,
+        _rng: &mut rand::rngs::ThreadRng,
+    ) -> f32 {
+        // ribbons fade out over the last 80% of their visible time, see the particle shader
+        let visible_time = self.show_time * 5.0;
+        for (from, to, color) in self.lines.iter() {
+            if glm::distance(from, to) <= f32::EPSILON {
+                continue;
+            }
+            let direction = glm::vec3_to_vec4(&glm::normalize(&(to - from)));
+            let line = RibbonSection {
+                pos_1: *from,
+                pos_2: *to,
+                width_1: LINE_WIDTH,
+                width_2: LINE_WIDTH,
+                color: *color,
+                n1: direction,
+                n2: direction,
+                t1: visible_time,
+                t2: visible_time,
+                tex_id: tex_range.0 as i32,
+                z_max: 0.0,
+                visible_time,
+            };
+            list.push(line.to_particle());
+        }
+        self.show_time
+    }
+}
@@ -6,6 +6,7 @@ use wgpu::util::DeviceExt;
 
 //exports
 pub mod constants;
+pub mod debug_lines;
 pub mod gen;
 pub mod ribbon;
 pub mod trail;
@@ -1,6 +1,7 @@
 use common::core::command::Command;
 use common::core::powerup_system::{PowerUp, PowerUpStatus, StatusEffect};
 use common::core::states::PlayerState;
+use common::core::status_effects::ActiveStatusEffect;
 use instant::Duration;
 use std::collections::HashMap;
 use std::f32::consts::FRAC_PI_2;
@@ -46,7 +47,7 @@ pub struct Player {
     pub wind_charge: u32,
     pub on_cooldown: HashMap<Command, f32>,
     pub power_up: Option<(PowerUp, PowerUpStatus)>,
-    pub status_effects: HashMap<StatusEffect, f32 /* time till status effect expire */>,
+    pub status_effects: HashMap<StatusEffect, ActiveStatusEffect>,
 }
 
 impl Player {
@@ -16,7 +16,7 @@ use common::core::choices::BODY_MESH;
 use common::core::mesh_color::MeshColor;
 use common::core::powerup_system::OtherEffects::{Slippery, Stun};
 use common::core::powerup_system::{PowerUpEffects, StatusEffect};
-use common::core::states::GameState;
+use common::core::states::{GameState, HazardPhase};
 
 use crate::player::{Player, PlayerController};
 
@@ -118,6 +118,8 @@ pub struct Scene {
     pub objects: HashMap<ModelIndex, Box<dyn Model>>,
     pub scene_graph: HashMap<NodeId, Node>,
     pub objects_and_instances: HashMap<ModelIndex, Vec<InstanceBundle>>,
+    // transforms from the config of the nodes moved by the server
+    pub hazard_transforms: HashMap<NodeId, Transform>,
 }
 
 pub enum NodeKind {
@@ -161,6 +163,7 @@ impl Scene {
                 Node::with_transform(world_node_id, glm::identity()),
             )]),
             objects_and_instances: HashMap::new(),
+            hazard_transforms: HashMap::new(),
         }
     }
 
@@ -236,6 +239,8 @@ impl Scene {
     ) {
         let player_id = client_id as u32; // TODO: why are we using u8 for client_id and u32 for player_id?
 
+        self.load_hazards(&game_state);
+
         // only render when i'm there
         if game_state.players.contains_key(&player_id) {
             let invisible_players =
@@ -316,6 +321,28 @@ impl Scene {
         }
     }
 
+    fn load_hazards(&mut self, game_state: &GameState) {
+        for (node_id, hazard) in game_state.world.hazards.iter() {
+            let (Some(config_transform), Some(node)) = (
+                self.hazard_transforms.get(node_id),
+                self.scene_graph.get_mut(node_id),
+            ) else {
+                continue;
+            };
+            let origin =
+                Player::calc_transf_matrix(hazard.origin.translation, hazard.origin.rotation);
+            let mut current =
+                Player::calc_transf_matrix(hazard.transform.translation, hazard.transform.rotation);
+            // shake before falling
+            if let HazardPhase::Crumbling(time_left) = hazard.phase {
+                let shake = glm::vec3((time_left * 60.0).sin() * 0.05, 0.0, 0.0);
+                current = glm::translation(&shake) * current;
+            }
+            // the server moves it in the world, the node is placed relative to its parent
+            node.transform = config_transform * glm::inverse(&origin) * current;
+        }
+    }
+
     /// function to get the player positions after model transforms
     /// assumes: each model is centered around the origin
     ///          the players have ids numerical ids < 10
@@ -433,6 +460,12 @@ impl Scene {
         let node_transform = Transform::new_translation(&json_node.transform.position)
             * glm::quat_to_mat4(&json_node.transform.rotation);
 
+        if json_node.behavior.is_some() {
+            scene
+                .hazard_transforms
+                .insert(json_node.id.clone(), node_transform);
+        }
+
         let node = match parent_id {
             Some(parent_id) => {
                 scene.add_child_node(parent_id, json_node.id.clone(), node_transform)
@@ -455,6 +488,9 @@ impl Scene {
 #[cfg(test)]
 mod test {
     use common::configs::scene_config::ConfigSceneGraph;
+    use common::core::components::{Entity, Transform};
+    use common::core::states::{GameState, HazardState};
+    use nalgebra_glm as glm;
 
     use super::Scene;
 
@@ -498,4 +534,52 @@ mod test {
         assert!(scene.scene_graph.contains_key("object:island"));
         assert!(scene.scene_graph.contains_key("object:ferris"));
     }
+
+    #[test]
+    fn test_hazard_follows_server_transform() {
+        let json_scene_graph_str = r#"
+        {
+          "nodes": [
+            {
+              "id": "object:island",
+              "model": "island",
+              "transform": {
+                "position": [0, -9.7, 0],
+                "rotation": [0, 0, 0, 1]
+              },
+              "children": [
+                {
+                  "id": "object:platform",
+                  "transform": {
+                    "position": [0, 5, 0],
+                    "rotation": [0, 0, 0, 1]
+                  },
+                  "model": "cube",
+                  "behavior": {
+                    "MovingPlatform": { "waypoints": [[4, 0, 0], [0, 0, 0]], "speed": 2.0 }
+                  }
+                }
+              ]
+            }
+          ]
+        }
+        "#;
+        let json_scene_graph: ConfigSceneGraph =
+            serde_json::from_str(json_scene_graph_str).unwrap();
+        let mut scene = Scene::from_config(&json_scene_graph);
+
+        // the server moved the platform 1 along x from where the scene places it
+        let mut game_state = GameState::default();
+        let origin = Transform::from_xyz(0.0, -4.7, 0.0);
+        let mut hazard = HazardState::new(Entity::from_raw(0xBEEF), origin);
+        hazard.transform = Transform::from_xyz(1.0, -4.7, 0.0);
+        game_state
+            .world
+            .hazards
+            .insert("object:platform".to_string(), hazard);
+        scene.load_hazards(&game_state);
+
+        let local = scene.scene_graph["object:platform"].transform * glm::vec4(0., 0., 0., 1.);
+        assert!(glm::distance(&local.xyz(), &glm::vec3(1.0, 5.0, 0.0)) < 1e-5);
+    }
 }
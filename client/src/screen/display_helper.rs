@@ -1,6 +1,7 @@
 use crate::screen::location_helper::{get_coords, to_absolute};
 use crate::screen::objects;
 use crate::screen::objects::ScreenInstance;
+use crate::screen::text_feed::TextFeed;
 use common::configs::display_config::{ConfigDisplay, ConfigScreen};
 use common::core::mesh_color::{MeshColor, MeshColorInstance};
 use nalgebra_glm as glm;
@@ -42,6 +43,7 @@ pub fn create_screen_map(
             icon_id_map,
             buttons,
             btn_id_map,
+            text_feeds: s.text_feeds.iter().cloned().map(TextFeed::new).collect(),
         };
         screen_map.insert(s.id.clone(), screen);
     }
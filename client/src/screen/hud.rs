@@ -0,0 +1,192 @@
+use crate::player::Player;
+use crate::screen::location_helper::to_pixels;
+use crate::screen::object_transitions::Transition;
+use crate::screen::Display;
+use common::configs::game_config::ConfigGame;
+use common::configs::physics_config::ConfigPhysics;
+use common::core::choices::LEAF_MESH;
+use common::core::command::Command;
+use common::core::powerup_system::{PowerUp, PowerUpEffect, PowerUpStatus};
+use common::core::states::{GameState, MatchPhase};
+use nalgebra_glm as glm;
+use wgpu_glyph::{GlyphBrush, HorizontalAlign, Layout, Section, Text, VerticalAlign};
+
+impl Display {
+    /// Updates the in-match HUD described by the display config from the synced game state
+    pub fn update_hud(
+        &mut self,
+        player: &Player,
+        game_state: &GameState,
+        game_config: &ConfigGame,
+        physics_config: &ConfigPhysics,
+        queue: &wgpu::Queue,
+    ) {
+        let screen_id = match self.groups.get(&self.game_display) {
+            Some(group) => group.screen.clone().unwrap(),
+            None => return,
+        };
+        let screen = self.screen_map.get_mut(&screen_id).unwrap();
+        let hud = &self.hud;
+
+        // flag progress of every player, in the color of their leaves
+        for progress in &hud.flag_progress {
+            let ind = *screen.icon_id_map.get(&progress.bar).unwrap();
+            let player_state = game_state.players.get(&progress.player_id);
+            screen.icons[ind].inst_range = 0..u32::from(player_state.is_some());
+
+            if let Some(leaf_color) = game_state
+                .players_customization
+                .get(&progress.player_id)
+                .and_then(|c| c.color.get(LEAF_MESH))
+            {
+                let c = leaf_color.rgb_color;
+                screen.icons[ind].tint = glm::vec4(c[0], c[1], c[2], 1.0);
+            }
+            let score =
+                player_state.map_or(0.0, |p| p.on_flag_time) / game_config.winning_threshold;
+            self.transition_map.insert(
+                progress.bar.clone(),
+                Transition::SqueezeLeft(score.clamp(0.0, 1.0)),
+            );
+        }
+
+        // wind charges left out of max_wind_charge, limited by the icon's instances
+        let ind_filled = *screen.icon_id_map.get(&hud.wind_charge.filled).unwrap();
+        let ind_empty = *screen.icon_id_map.get(&hud.wind_charge.empty).unwrap();
+        let max_charge = game_config
+            .max_wind_charge
+            .min(screen.icons[ind_empty].instance_raw.len() as u32);
+        let charge = player.wind_charge.min(max_charge);
+        screen.icons[ind_filled].inst_range = 0..charge;
+        screen.icons[ind_empty].inst_range = charge..max_charge;
+
+        // cooldown radials, several commands may share an overlay
+        for cooldown in &hud.cooldowns {
+            if let Some(cd_left) = player.on_cooldown.get(&cooldown.command) {
+                let full = full_cooldown(&cooldown.command, player, game_config, physics_config);
+                self.transition_map.insert(
+                    cooldown.overlay.clone(),
+                    Transition::Radial((cd_left / full).clamp(0.0, 1.0)),
+                );
+            } else if !hud.cooldowns.iter().any(|c| {
+                c.overlay == cooldown.overlay && player.on_cooldown.contains_key(&c.command)
+            }) {
+                // radial fully swept, nothing left to draw
+                self.transition_map
+                    .insert(cooldown.overlay.clone(), Transition::Radial(0.0));
+            }
+        }
+
+        // held or active power-up
+        let ind_slot = *screen.icon_id_map.get(&hud.power_up.slot).unwrap();
+        let prev_transp = screen.icons[ind_slot].tint[3];
+        if let Some((power_up, _)) = player.power_up.as_ref() {
+            screen.icons[ind_slot].texture = power_up_texture(power_up);
+            screen.icons[ind_slot].tint[3] = 1.0;
+        } else {
+            screen.icons[ind_slot].tint[3] = 0.0;
+        }
+        if prev_transp != screen.icons[ind_slot].tint[3] {
+            let tint = screen.icons[ind_slot].tint;
+            for v in &mut screen.icons[ind_slot].vertices {
+                v.color = tint.into();
+            }
+            queue.write_buffer(
+                &screen.icons[ind_slot].vbuf,
+                0,
+                bytemuck::cast_slice(&screen.icons[ind_slot].vertices),
+            );
+        }
+
+        // remaining duration of the active power-up
+        let overlay_id = &hud.power_up.duration_overlay;
+        let ind_overlay = *screen.icon_id_map.get(overlay_id).unwrap();
+        match player.power_up.as_ref() {
+            Some((power_up, PowerUpStatus::Active)) => {
+                screen.icons[ind_overlay].texture =
+                    format!("{}_overlay", power_up_texture(power_up));
+
+                // the first status effect granted by the power-up that is still running
+                let fraction_left = game_config
+                    .powerup_config
+                    .definition(power_up)
+                    .and_then(|d| {
+                        d.effects.iter().find_map(|e| match e {
+                            PowerUpEffect::Status { effect, .. } => player
+                                .status_effects
+                                .get(effect)
+                                .map(|active| active.time_left / active.duration),
+                            _ => None,
+                        })
+                    });
+                if let Some(fraction_left) = fraction_left {
+                    self.transition_map.insert(
+                        overlay_id.clone(),
+                        Transition::SqueezeDown(fraction_left.clamp(0.0, 1.0)),
+                    );
+                }
+            }
+            _ => {
+                // held, or died holding a power-up
+                self.transition_map.remove(overlay_id);
+                screen.icons[ind_overlay].texture = String::from("icon:empty");
+            }
+        }
+    }
+
+    /// Queues the remaining match time, or the phase the match is in once time is up
+    pub fn queue_match_clock(
+        &self,
+        glyph_brush: &mut GlyphBrush<()>,
+        screen_width: u32,
+        screen_height: u32,
+    ) {
+        let match_clock = self.game_state.lock().unwrap().match_clock;
+        let text = match (match_clock.phase, match_clock.time_left) {
+            (_, None) => return,
+            (MatchPhase::Regular, Some(time_left)) => {
+                let secs = time_left.ceil() as u32;
+                format!("{}:{:02}", secs / 60, secs % 60)
+            }
+            (MatchPhase::Overtime, _) => String::from("OVERTIME"),
+            (MatchPhase::SuddenDeath, _) => String::from("SUDDEN DEATH"),
+        };
+
+        let config = &self.hud.match_clock;
+        let [x, y] = to_pixels(&config.location, screen_width, screen_height);
+        glyph_brush.queue(Section {
+            screen_position: (x, y),
+            bounds: (screen_width as f32, screen_height as f32),
+            text: vec![Text::new(text.as_str())
+                .with_color(config.color)
+                .with_scale(config.text_height * screen_height as f32)],
+            layout: Layout::default_single_line()
+                .h_align(HorizontalAlign::Center)
+                .v_align(VerticalAlign::Center),
+        });
+    }
+}
+
+/// Full cooldown of a command, to turn the synced time left into a fraction
+fn full_cooldown(
+    command: &Command,
+    player: &Player,
+    game_config: &ConfigGame,
+    physics_config: &ConfigPhysics,
+) -> f32 {
+    match command {
+        Command::Attack => physics_config.attack_config.attack_cooldown,
+        Command::AreaAttack => physics_config.attack_config.area_attack_cooldown,
+        Command::CastPowerUp => player
+            .power_up
+            .as_ref()
+            .and_then(|(p, _)| game_config.powerup_config.definition(p))
+            .map_or(1.0, |d| d.cooldown),
+        Command::Refill => game_config.refill_rate_limit,
+        _ => 1.0,
+    }
+}
+
+fn power_up_texture(power_up: &PowerUp) -> String {
+    format!("icon:power_{}", power_up.0)
+}
@@ -8,6 +8,12 @@ pub fn to_absolute(location: &ScreenLocation, width: u32, height: u32) -> [f32;
     [w, h]
 }
 
+/// top left based pixel coordinates, as used for text
+pub fn to_pixels(location: &ScreenLocation, width: u32, height: u32) -> [f32; 2] {
+    let [x, y] = to_absolute(location, width, height);
+    [(x + 1.0) / 2.0 * width as f32, (1.0 - y) / 2.0 * height as f32]
+}
+
 pub fn get_width(height: f32, object_aspect: f32, screen_width: u32, screen_height: u32) -> f32 {
     let r_aspect: f32 = (screen_height as f32) / (screen_width as f32);
     height * object_aspect * r_aspect
@@ -1,9 +1,10 @@
 use self::objects::Screen;
 use crate::skybox;
-use common::configs::display_config::{ConfigDisplay, ScreenLocation};
+use common::configs::display_config::{ConfigDisplay, ConfigHud, ScreenLocation};
 use common::configs::ConfigurationManager;
 use common::core::choices::CurrentSelections;
 use common::core::states::GameState;
+use common::core::weather::Weather;
 use nalgebra_glm as glm;
 use std::collections::{HashMap, HashSet};
 use std::sync::{mpsc, Arc, Mutex};
@@ -21,9 +22,11 @@ use crate::screen::ui_interaction::BUTTON_MAP;
 use crate::{camera, lights, model, texture};
 
 pub mod display_helper;
+pub mod hud;
 pub mod location_helper;
 pub mod object_transitions;
 pub mod objects;
+pub mod text_feed;
 pub mod texture_helper;
 pub mod ui_interaction;
 
@@ -34,6 +37,7 @@ pub struct Display {
     pub game_display: String,
     pub leaf_colors: HashMap<String, [f32; 4]>,
     pub wood_colors: HashMap<String, [f32; 4]>,
+    pub hud: ConfigHud,
     pub texture_map: HashMap<String, wgpu::BindGroup>,
     pub screen_map: HashMap<String, Screen>,
     pub scene_map: HashMap<String, Scene>,
@@ -81,6 +85,7 @@ impl Display {
             game_display: config.game_display.clone(),
             leaf_colors: config.leaf_colors.clone(),
             wood_colors: config.wood_colors.clone(),
+            hud: config.hud.clone(),
             texture_map,
             screen_map,
             scene_map,
@@ -163,6 +168,11 @@ impl Display {
             let cam_dir: glm::Vec3 =
                 glm::normalize(&(camera_state.camera.position - camera_state.camera.target));
             let cpos = &camera_state.camera.position;
+            // fog hides the labels of far away players
+            let label_range = match self.game_state.lock().unwrap().world.weather {
+                Some(Weather::Foggy) => Some(game_config.weather_config.fog_label_range),
+                _ => None,
+            };
 
             for player in other_players {
                 let id = player.id;
@@ -171,6 +181,12 @@ impl Display {
                     // skip if player invisible
                     continue;
                 }
+                if let Some(range) = label_range {
+                    let target = &camera_state.camera.target;
+                    if glm::distance(&glm::vec3(pos[0], pos[1], pos[2]), target) > range {
+                        continue;
+                    }
+                }
                 // -1 to cancel out 1.0 in pos, 2.5 to place above the player
                 let pos = pos + glm::vec4(0.0, 2.0, 0.0, -1.0);
                 let vec3pos = glm::vec3(pos[0], pos[1], pos[2]);
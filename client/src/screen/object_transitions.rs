@@ -6,6 +6,8 @@ use crate::screen::objects;
 pub enum Transition {
     FadeOut(f32),
     SqueezeDown(f32),
+    SqueezeLeft(f32),
+    Radial(f32),
 }
 
 impl Transition {
@@ -41,6 +43,29 @@ impl Transition {
                 tmp_vtxs[2].texture[1] = 1.0 - *p;
                 queue.write_buffer(&icon.vbuf, 0, bytemuck::cast_slice(&tmp_vtxs));
             }
+            Transition::SqueezeLeft(p) => {
+                // keeps the left edge in place, e.g. for progress bars
+                let mut tmp_vtxs = icon.vertices;
+                for v in &mut tmp_vtxs {
+                    v.color = icon.tint.into();
+                }
+                let modified_width =
+                    *p * (icon.vertices[3].position[0] - icon.vertices[0].position[0]);
+                tmp_vtxs[2].position[0] = icon.vertices[0].position[0] + modified_width;
+                tmp_vtxs[3].position[0] = icon.vertices[0].position[0] + modified_width;
+                tmp_vtxs[2].texture[0] = *p;
+                tmp_vtxs[3].texture[0] = *p;
+                queue.write_buffer(&icon.vbuf, 0, bytemuck::cast_slice(&tmp_vtxs));
+            }
+            Transition::Radial(p) => {
+                // same as SqueezeDown, the tint should be 0 in the display config
+                let mut tmp_vtxs = icon.vertices;
+                for v in &mut tmp_vtxs {
+                    v.color[3] = 1.0;
+                    v.sweep = *p;
+                }
+                queue.write_buffer(&icon.vbuf, 0, bytemuck::cast_slice(&tmp_vtxs));
+            }
         }
     }
 }
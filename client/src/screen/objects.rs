@@ -1,5 +1,6 @@
 use crate::screen::location_helper::get_coords;
 use crate::screen::location_helper::to_absolute;
+use crate::screen::text_feed::TextFeed;
 use common::configs::display_config::{ConfigScreenTransform, ScreenLocation};
 use common::core::mesh_color::{MeshColor, MeshColorInstance};
 use nalgebra_glm as glm;
@@ -12,11 +13,13 @@ pub struct Vertex {
     pub position: [f32; 2],
     pub color: [f32; 4],
     pub texture: [f32; 2],
+    pub sweep: f32, // fraction of a radial wipe left to draw, clockwise from the top
 }
 
 impl Vertex {
-    const ATTRIBS: [wgpu::VertexAttribute; 3] =
-        wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4, 2 => Float32x2];
+    const ATTRIBS: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+        0 => Float32x2, 1 => Float32x4, 2 => Float32x2, 7 => Float32
+    ];
 
     pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
         use std::mem;
@@ -78,21 +81,25 @@ pub const TITLE_VERT: [Vertex; 4] = [
         position: [-1.0, -1.0],
         color: [1.0, 1.0, 1.0, 1.0],
         texture: [0.0, 1.0],
+        sweep: 1.0,
     }, // A
     Vertex {
         position: [-1.0, 1.0],
         color: [1.0, 1.0, 1.0, 1.0],
         texture: [0.0, 0.0],
+        sweep: 1.0,
     }, // B
     Vertex {
         position: [1.0, 1.0],
         color: [1.0, 1.0, 1.0, 1.0],
         texture: [1.0, 0.0],
+        sweep: 1.0,
     }, // C
     Vertex {
         position: [1.0, -1.0],
         color: [1.0, 1.0, 1.0, 1.0],
         texture: [1.0, 1.0],
+        sweep: 1.0,
     }, // D
 ];
 
@@ -114,6 +121,13 @@ pub struct Screen {
     pub buttons: Vec<Button>,
     pub icon_id_map: HashMap<String, usize>,
     pub btn_id_map: HashMap<String, usize>,
+    pub text_feeds: Vec<TextFeed>,
+}
+
+impl Screen {
+    pub fn text_feed_mut(&mut self, id: &str) -> Option<&mut TextFeed> {
+        self.text_feeds.iter_mut().find(|f| f.config.id == id)
+    }
 }
 
 #[derive(Debug)]
@@ -193,21 +207,25 @@ impl ScreenBackground {
                 position: [-1.0, -1.0],
                 color: [1.0, 1.0, 1.0, 1.0],
                 texture: [0.5 - title_x_span_half, 1.0],
+                sweep: 1.0,
             }, // A
             Vertex {
                 position: [-1.0, 1.0],
                 color: [1.0, 1.0, 1.0, 1.0],
                 texture: [0.5 - title_x_span_half, 0.0],
+                sweep: 1.0,
             }, // B
             Vertex {
                 position: [1.0, 1.0],
                 color: [1.0, 1.0, 1.0, 1.0],
                 texture: [0.5 + title_x_span_half, 0.0],
+                sweep: 1.0,
             }, // C
             Vertex {
                 position: [1.0, -1.0],
                 color: [1.0, 1.0, 1.0, 1.0],
                 texture: [0.5 + title_x_span_half, 1.0],
+                sweep: 1.0,
             }, // D
         ];
         queue.write_buffer(&self.vbuf, 0, bytemuck::cast_slice(&title_vert));
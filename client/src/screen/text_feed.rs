@@ -0,0 +1,258 @@
+use crate::screen::location_helper::to_pixels;
+use common::configs::display_config::{ConfigTextFeed, TextAlign};
+use common::configs::game_config::ConfigPowerUp;
+use common::core::choices::LEAF_MESH;
+use common::core::events::GameEvent;
+use common::core::powerup_system::PowerUp;
+use common::core::states::{GameState, MatchPhase};
+use common::core::weather::Weather;
+use std::collections::VecDeque;
+use wgpu_glyph::{GlyphBrush, HorizontalAlign, Layout, Section, Text};
+
+pub const KILL_FEED: &str = "feed:kill";
+pub const ANNOUNCER_FEED: &str = "feed:announcer";
+
+/// A piece of text and its color (the feed's color if None), entries are made of these
+pub type TextSegment = (String, Option<[f32; 4]>);
+
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    pub segments: Vec<TextSegment>,
+    pub time_left: f32,
+}
+
+/// Runtime state of a ConfigTextFeed, newest entry first
+#[derive(Debug)]
+pub struct TextFeed {
+    pub config: ConfigTextFeed,
+    pub entries: VecDeque<FeedEntry>,
+}
+
+impl TextFeed {
+    pub fn new(config: ConfigTextFeed) -> Self {
+        Self {
+            config,
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, segments: Vec<TextSegment>) {
+        self.entries.push_front(FeedEntry {
+            segments,
+            time_left: self.config.lifetime,
+        });
+        self.entries.truncate(self.config.max_entries);
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for entry in self.entries.iter_mut() {
+            entry.time_left -= dt;
+        }
+        self.entries.retain(|e| e.time_left > 0.0);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn alpha(&self, entry: &FeedEntry) -> f32 {
+        if self.config.fade_time <= 0.0 {
+            return 1.0;
+        }
+        (entry.time_left / self.config.fade_time).clamp(0.0, 1.0)
+    }
+
+    pub fn queue(&self, glyph_brush: &mut GlyphBrush<()>, screen_width: u32, screen_height: u32) {
+        let (width, height) = (screen_width as f32, screen_height as f32);
+        let [x, y] = to_pixels(&self.config.location, screen_width, screen_height);
+        let h_align = match self.config.align {
+            TextAlign::Left => HorizontalAlign::Left,
+            TextAlign::Center => HorizontalAlign::Center,
+            TextAlign::Right => HorizontalAlign::Right,
+        };
+        let scale = self.config.text_height * height;
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            let alpha = self.alpha(entry);
+            let text = entry
+                .segments
+                .iter()
+                .map(|(s, color)| {
+                    let color = color.unwrap_or(self.config.color);
+                    Text::new(s.as_str())
+                        .with_color([color[0], color[1], color[2], color[3] * alpha])
+                        .with_scale(scale)
+                })
+                .collect();
+            glyph_brush.queue(Section {
+                screen_position: (x, y + i as f32 * self.config.line_spacing * height),
+                bounds: (width, height),
+                text,
+                layout: Layout::default_single_line().h_align(h_align),
+            });
+        }
+    }
+}
+
+/// Turns a gameplay event into the lines it should add to the feeds, as (feed id, line)
+pub fn feed_lines(
+    event: &GameEvent,
+    game_state: &GameState,
+    powerup_config: &ConfigPowerUp,
+) -> Vec<(&'static str, Vec<TextSegment>)> {
+    let player = |id: u32| (format!("Player {}", id), player_color(id, game_state));
+    let text = |s: &str| (s.to_owned(), None);
+
+    match event {
+        GameEvent::PlayerKnockedOut {
+            victim,
+            by: Some(attacker),
+        } => vec![(
+            KILL_FEED,
+            vec![
+                player(*attacker),
+                text(" blew "),
+                player(*victim),
+                text(" off the island"),
+            ],
+        )],
+        GameEvent::PlayerKnockedOut { victim, by: None } => {
+            vec![(
+                KILL_FEED,
+                vec![player(*victim), text(" fell off the island")],
+            )]
+        }
+        GameEvent::PowerUpPickedUp {
+            player: id,
+            power_up,
+        } => vec![(
+            KILL_FEED,
+            vec![
+                player(*id),
+                text(&format!(
+                    " picked up {}",
+                    power_up_name(power_up, powerup_config)
+                )),
+            ],
+        )],
+        GameEvent::PowerUpCast {
+            player: id,
+            power_up,
+        } => vec![(
+            KILL_FEED,
+            vec![
+                player(*id),
+                text(&format!(
+                    " used {}",
+                    power_up_name(power_up, powerup_config)
+                )),
+            ],
+        )],
+        GameEvent::PlayerJoined(id) => vec![(KILL_FEED, vec![player(*id), text(" joined")])],
+        GameEvent::PlayerLeft(id) => vec![(KILL_FEED, vec![player(*id), text(" left")])],
+        GameEvent::FlagCaptured(id) => vec![(
+            ANNOUNCER_FEED,
+            vec![player(*id), text(" is capturing the flag!")],
+        )],
+        GameEvent::FlagContested(_) => vec![(ANNOUNCER_FEED, vec![text("The flag is contested!")])],
+        GameEvent::WeatherChanged(weather) => {
+            let line = match weather {
+                Some(Weather::Rainy) => "It starts to rain...",
+                Some(Weather::Windy(_)) => "The wind picks up!",
+                Some(Weather::Snowy) => "It starts to snow, keep moving!",
+                Some(Weather::Foggy) => "A thick fog rolls in...",
+                Some(Weather::Stormy) => "A lightning storm is brewing!",
+                None => "The skies clear up",
+            };
+            vec![(ANNOUNCER_FEED, vec![text(line)])]
+        }
+        GameEvent::MatchStarted => vec![(ANNOUNCER_FEED, vec![text("Fight!")])],
+        GameEvent::MatchPhaseChanged(MatchPhase::Overtime) => {
+            vec![(ANNOUNCER_FEED, vec![text("Overtime!")])]
+        }
+        GameEvent::MatchPhaseChanged(MatchPhase::SuddenDeath) => {
+            vec![(ANNOUNCER_FEED, vec![text("Sudden death! No more respawns")])]
+        }
+        _ => vec![],
+    }
+}
+
+/// Players are named in the color of their leaves
+fn player_color(id: u32, game_state: &GameState) -> Option<[f32; 4]> {
+    game_state
+        .players_customization
+        .get(&id)
+        .and_then(|c| c.color.get(LEAF_MESH))
+        .map(|c| [c.rgb_color[0], c.rgb_color[1], c.rgb_color[2], 1.0])
+}
+
+fn power_up_name<'a>(power_up: &'a PowerUp, powerup_config: &'a ConfigPowerUp) -> &'a str {
+    powerup_config
+        .definition(power_up)
+        .map_or(power_up.0.as_str(), |d| d.name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::configs::display_config::ScreenLocation;
+    use common::configs::from_file;
+    use common::configs::game_config::ConfigGame;
+
+    fn feed(max_entries: usize) -> TextFeed {
+        TextFeed::new(ConfigTextFeed {
+            id: KILL_FEED.to_owned(),
+            location: ScreenLocation {
+                vert_disp: (0.0, 0.0),
+                horz_disp: (0.0, 0.0),
+            },
+            align: TextAlign::Left,
+            text_height: 0.03,
+            line_spacing: 0.04,
+            max_entries,
+            lifetime: 2.0,
+            fade_time: 1.0,
+            color: [1.0, 1.0, 1.0, 1.0],
+        })
+    }
+
+    #[test]
+    fn test_feed_keeps_newest_entries() {
+        let mut feed = feed(2);
+        for s in ["a", "b", "c"] {
+            feed.push(vec![(s.to_owned(), None)]);
+        }
+        let texts: Vec<&str> = feed
+            .entries
+            .iter()
+            .map(|e| e.segments[0].0.as_str())
+            .collect();
+        assert_eq!(texts, vec!["c", "b"]);
+    }
+
+    #[test]
+    fn test_feed_entries_fade_and_expire() {
+        let mut feed = feed(5);
+        feed.push(vec![("a".to_owned(), None)]);
+        feed.update(0.5);
+        assert_eq!(feed.alpha(&feed.entries[0]), 1.0);
+        feed.update(1.0);
+        assert!((feed.alpha(&feed.entries[0]) - 0.5).abs() < 1e-5);
+        feed.update(0.6);
+        assert!(feed.entries.is_empty());
+    }
+
+    #[test]
+    fn test_ring_out_line() {
+        let game_config: ConfigGame = from_file("../game.json").unwrap();
+        let event = GameEvent::PlayerKnockedOut {
+            victim: 3,
+            by: Some(2),
+        };
+        let lines = feed_lines(&event, &GameState::default(), &game_config.powerup_config);
+        let (feed_id, line) = &lines[0];
+        let text: String = line.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(*feed_id, KILL_FEED);
+        assert_eq!(text, "Player 2 blew Player 3 off the island");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::communication::commons::*;
+use crate::configs::ConfigGameplay;
 use crate::core::command::Command;
 use crate::core::events::GameEvent;
 use crate::core::states::GameState;
@@ -42,6 +43,7 @@ pub enum Payload {
     Command(Command),
     Init((u8, u64)),
     ServerEvent(GameEvent),
+    ConfigGameplay(Box<ConfigGameplay>), // the rules of the server, after the init and each reload
 }
 
 /// message kind to u8
@@ -53,6 +55,7 @@ impl From<&Payload> for u8 {
             Payload::Command(_) => 2,
             Payload::Init(_) => 3,
             Payload::ServerEvent(_) => 4,
+            Payload::ConfigGameplay(_) => 5,
         }
     }
 }
@@ -104,6 +107,9 @@ impl Serialize for Message {
             Payload::ServerEvent(event) => {
                 prefix_len::write_bincode(buf, event)?;
             }
+            Payload::ConfigGameplay(gameplay) => {
+                prefix_len::write_bincode(buf, gameplay)?;
+            }
         }
         Ok(())
     }
@@ -123,6 +129,7 @@ impl Deserialize for Message {
             2 => Payload::Command(prefix_len::extract_bincode(&mut buf)?),
             3 => Payload::Init(prefix_len::extract_bincode(&mut buf)?),
             4 => Payload::ServerEvent(prefix_len::extract_bincode(&mut buf)?),
+            5 => Payload::ConfigGameplay(prefix_len::extract_bincode(&mut buf)?),
             _ => panic!("Invalid payload kind {}", payload_kind),
         };
 
@@ -140,6 +147,7 @@ impl Deserialize for Message {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::configs::from_file;
     use nalgebra_glm::vec3;
 
     #[test]
@@ -184,6 +192,60 @@ mod tests {
         assert_eq!(format!("{:?}", msg), format!("{:?}", msg2));
     }
 
+    #[test]
+    fn test_message_round_trip_gameplay_event() {
+        let msg = Message::new(
+            HostRole::Server,
+            Payload::ServerEvent(GameEvent::PlayerKnockedOut {
+                victim: 3,
+                by: Some(2),
+            }),
+        );
+        let mut buf = Vec::new();
+        msg.serialize(&mut buf).unwrap();
+
+        let msg2 = Message::deserialize(&mut buf.as_slice()).unwrap();
+        assert!(matches!(
+            msg2.payload,
+            Payload::ServerEvent(GameEvent::PlayerKnockedOut {
+                victim: 3,
+                by: Some(2)
+            })
+        ));
+    }
+
+    #[test]
+    fn test_message_round_trip_config_gameplay() {
+        let gameplay = ConfigGameplay {
+            game: from_file("../game.json").unwrap(),
+            physics: from_file("../physics.json").unwrap(),
+        };
+        let msg = Message::new(
+            HostRole::Server,
+            Payload::ConfigGameplay(Box::new(gameplay.clone())),
+        );
+        let mut buf = Vec::new();
+        msg.serialize(&mut buf).unwrap();
+
+        let msg2 = Message::deserialize(&mut buf.as_slice()).unwrap();
+        // the maps inside are unordered, so compare a part of each config
+        let Payload::ConfigGameplay(gameplay2) = msg2.payload else {
+            panic!("expected the gameplay configs");
+        };
+        assert_eq!(
+            format!("{:?}", gameplay.game.zones),
+            format!("{:?}", gameplay2.game.zones)
+        );
+        assert_eq!(
+            format!("{:?}", gameplay.game.powerup_config.power_ups),
+            format!("{:?}", gameplay2.game.powerup_config.power_ups)
+        );
+        assert_eq!(
+            format!("{:?}", gameplay.physics),
+            format!("{:?}", gameplay2.physics)
+        );
+    }
+
     #[test]
     fn test_message_serialize_init() {
         let msg = Message::new(HostRole::Server, Payload::Init((10, 100)));
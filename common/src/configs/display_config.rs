@@ -1,3 +1,4 @@
+use crate::core::command::Command;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -11,6 +12,7 @@ pub struct ConfigDisplay {
     pub screens: Vec<ConfigScreen>,
     pub leaf_colors: HashMap<String, [f32; 4]>,
     pub wood_colors: HashMap<String, [f32; 4]>,
+    pub hud: ConfigHud,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -26,6 +28,7 @@ pub struct ConfigScreen {
     pub background: Option<ConfigScreenBackground>,
     pub buttons: Vec<ConfigButton>,
     pub icons: Vec<ConfigIcon>,
+    pub text_feeds: Vec<ConfigTextFeed>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -62,6 +65,71 @@ pub struct ConfigIcon {
     pub instances: Vec<ConfigScreenTransform>,
 }
 
+/// A list of text entries (e.g. kill feed, announcements) that fade out over time
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigTextFeed {
+    pub id: String,
+    pub location: ScreenLocation, // anchor of the newest entry
+    pub align: TextAlign,
+    pub text_height: f32,  // relative to screen height
+    pub line_spacing: f32, // relative to screen height, negative to grow upwards
+    pub max_entries: usize,
+    pub lifetime: f32,  // in seconds
+    pub fade_time: f32, // last seconds of the lifetime spent fading out
+    pub color: [f32; 4],
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// In-match HUD, all ids refer to icons of the game display's screen
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigHud {
+    pub flag_progress: Vec<ConfigHudFlagProgress>,
+    pub wind_charge: ConfigHudWindCharge,
+    pub cooldowns: Vec<ConfigHudCooldown>,
+    pub power_up: ConfigHudPowerUp,
+    pub match_clock: ConfigHudText,
+}
+
+/// Bar filled as the player's on_flag_time approaches the winning threshold
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigHudFlagProgress {
+    pub player_id: u32,
+    pub bar: String,
+}
+
+/// One instance of each icon per charge, up to max_wind_charge
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigHudWindCharge {
+    pub filled: String,
+    pub empty: String,
+}
+
+/// Radial overlay shown while the command is on cooldown
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigHudCooldown {
+    pub command: Command,
+    pub overlay: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigHudPowerUp {
+    pub slot: String,             // shows the held or active power-up
+    pub duration_overlay: String, // shrinks as the active power-up runs out
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigHudText {
+    pub location: ScreenLocation,
+    pub text_height: f32, // relative to screen height
+    pub color: [f32; 4],
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ConfigScreenTransform {
     // Scale, then rotate, then translate
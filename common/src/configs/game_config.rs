@@ -1,28 +1,106 @@
+use crate::core::events::ParticleType;
+use crate::core::powerup_system::{PowerUp, PowerUpActivation, PowerUpEffect, StatusEffect};
+use crate::core::status_effects::{StackingRule, StatModifier};
+use crate::core::weather::WeatherKind;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ConfigGame {
     pub spawn_points: Vec<rapier3d::prelude::Vector<f32>>,
-    pub refill_points: Vec<rapier3d::prelude::Vector<f32>>,
     pub spawn_cooldown: f32,
     pub respawn_coef: f32,
     pub score_lower_x: f32,
     pub score_upper_x: f32,
     pub max_wind_charge: u32,
     pub one_charge: u32,
-    pub flag_xz: (f32, f32),
-    pub flag_radius: f32,
-    pub flag_z_bound: (Option<f32>, Option<f32>),
     pub winning_threshold: f32,
     pub decay_rate: f32,
     pub decay_coef: f32,
-    pub refill_radius: f32,
+    pub refill_radius: f32, // size of the refill particles
     pub refill_rate_limit: f32,
+    pub ring_out_window: f32,
+    pub zones: Vec<ConfigZone>,
+    pub match_config: ConfigMatch,
     pub camera_config: ConfigCamera,
     pub powerup_config: ConfigPowerUp,
+    pub status_effects: Vec<ConfigStatusEffect>,
     pub weather_config: ConfigWeather,
 }
 
+/// What a status effect does while a player is under it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigStatusEffect {
+    pub effect: StatusEffect,
+    pub stacking: StackingRule,
+    pub max_stacks: u32,
+    pub modifiers: Vec<StatModifier>,
+}
+
+/// An area of the level players stand in, a sensor in the physics world
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigZone {
+    pub kind: ZoneKind,
+    pub position: rapier3d::prelude::Vector<f32>,
+    pub shape: ConfigZoneShape,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZoneKind {
+    Flag,
+    Refill,
+    PowerUp(u32), // id in power_up_locations
+}
+
+/// Shapes are centered on the zone position, cylinders stand upright
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ConfigZoneShape {
+    Cylinder {
+        radius: f32,
+        half_height: f32,
+    },
+    Ball {
+        radius: f32,
+    },
+    Cuboid {
+        half_extents: rapier3d::prelude::Vector<f32>,
+    },
+    // a model from the model config, decomposed so that being inside it counts
+    Model {
+        model: String,
+    },
+}
+
+impl ConfigGame {
+    /// Checks what serde can't, the game can't run with an invalid config
+    pub fn validate(&self) -> Result<(), String> {
+        self.weather_config.validate()?;
+        for zone in self.zones.iter() {
+            if let ZoneKind::PowerUp(location) = zone.kind {
+                if !self
+                    .powerup_config
+                    .power_up_locations
+                    .contains_key(&location)
+                {
+                    return Err(format!("power-up zone at unknown location {}", location));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn status_effect(&self, effect: StatusEffect) -> Option<&ConfigStatusEffect> {
+        self.status_effects.iter().find(|d| d.effect == effect)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigMatch {
+    pub time_limit: Option<f32>, // in seconds, the match only ends by score if None
+    pub sudden_death: bool,      // respawns are disabled if the leaders are tied when time is up
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ConfigCamera {
     pub x_sensitivity: f32,
@@ -33,22 +111,40 @@ pub struct ConfigCamera {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ConfigPowerUp {
     pub power_up_locations: std::collections::HashMap<u32, (f32, f32, f32)>,
-    pub power_up_radius: f32,
     pub power_up_respawn_cooldown: f32,
-    pub power_up_buff_duration: f32,
-    pub power_up_debuff_duration: f32,
     pub power_up_cooldown: f32,
-    pub blizzard_max_attack_dist: f32,
-    pub blizzard_max_attack_angle: f32,
-    pub wind_enhancement_scalar: f32,
-    pub dash_impulse: f32,
-    pub flash_distance_scalar: f32,
-    pub invincible_effective_distance: f32,
     pub invincible_effective_impulse: f32,
-    pub flash_cooldown: f32,
     pub spawn_invincible_duration: f32,
-    pub dash_blocking_duration: f32,
-    pub dash_cooldown: f32,
+    pub power_ups: Vec<ConfigPowerUpDefinition>,
+}
+
+/// A power-up that can spawn on the island, built from the effect primitives
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigPowerUpDefinition {
+    pub id: String, // its icon is icon:power_{id}
+    pub name: String,
+    pub spawn_weight: u32,
+    pub activation: PowerUpActivation,
+    pub effects: Vec<PowerUpEffect>, // applied when cast
+    pub on_use: Vec<PowerUpEffect>,  // applied when cast again while active
+    pub cooldown: f32,               // between two uses
+    pub particle: Option<ParticleType>,
+    pub sound: Option<String>,     // heard by everyone when cast
+    pub use_sound: Option<String>, // heard by the caster on every use
+    pub held_aura: String,         // aura colors from the particle config
+    pub active_aura: String,
+}
+
+impl ConfigPowerUp {
+    pub fn definition(&self, power_up: &PowerUp) -> Option<&ConfigPowerUpDefinition> {
+        self.power_ups.iter().find(|d| d.id == power_up.0)
+    }
+
+    /// Picks a power-up to spawn according to the spawn weights, None if nothing can spawn
+    pub fn random_power_up<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<PowerUp> {
+        let weights = WeightedIndex::new(self.power_ups.iter().map(|d| d.spawn_weight)).ok()?;
+        Some(PowerUp(self.power_ups[weights.sample(rng)].id.clone()))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -56,7 +152,138 @@ pub struct ConfigWeather {
     pub default_weather_ambient_multiplier: f32,
     pub rainy_weather_ambient_multiplier: f32,
     pub environment_lighting_rate_change_coefficient: f32,
-    pub rain_ratio: f32,
-    pub wind_ratio: f32,
-    pub no_weather_ratio: f32,
+    // the weather is random after start_delay seconds, unless a timeline is scripted
+    pub start_delay: f32,
+    pub chain: ConfigWeatherChain,
+    pub timeline: Option<Vec<ConfigScriptedWeather>>,
+    pub wind_angle_range: (f32, f32), // in degrees, 0 blows towards +x
+    pub wind_force_range: (f32, f32),
+    pub rainy_friction: f32,
+    pub snowy_weather_ambient_multiplier: f32,
+    pub foggy_weather_ambient_multiplier: f32,
+    pub stormy_weather_ambient_multiplier: f32,
+    pub lightning_flash_ambient_multiplier: f32,
+    // snow slows everyone down, and players standing still may freeze
+    pub snow_move_speed_scale: f32,
+    pub snow_still_speed: f32,
+    pub snow_freeze_chance: f32, // per second standing still
+    pub snow_freeze_duration: f32,
+    // fog hides what is far away
+    pub fog_attack_range_scale: f32,
+    pub fog_label_range: f32,
+    // a lightning storm strikes around a random player every lightning_interval seconds
+    pub lightning_interval: f32,
+    pub lightning_warning_time: f32,
+    pub lightning_scatter: f32,
+    pub lightning_radius: f32,
+    pub lightning_impulse: f32,
+    pub lightning_stun_duration: f32,
+}
+
+/// Weather as a Markov chain, every weather goes back to no weather when it ends
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigWeatherChain {
+    pub no_weather_fraction: f64, // of the time in the long run
+    pub states: Vec<ConfigWeatherState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigWeatherState {
+    pub weather: WeatherKind,
+    pub fraction: f64,      // of the time in the long run
+    pub mean_duration: f64, // in seconds
+}
+
+/// The weather from start (in seconds since the match started) until the next entry
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigScriptedWeather {
+    pub start: f32,
+    pub weather: Option<WeatherKind>,
+}
+
+impl ConfigWeatherChain {
+    pub fn validate(&self) -> Result<(), String> {
+        let fractions = std::iter::once(self.no_weather_fraction)
+            .chain(self.states.iter().map(|s| s.fraction))
+            .collect::<Vec<_>>();
+        if fractions.iter().any(|&f| !(0.0..=1.0).contains(&f)) {
+            return Err("weather fractions must be between 0 and 1".to_string());
+        }
+        let total: f64 = fractions.iter().sum();
+        if (total - 1.0).abs() > 1e-6 {
+            return Err(format!("weather fractions sum to {} instead of 1", total));
+        }
+        if self.no_weather_fraction == 0.0 && !self.states.is_empty() {
+            return Err(
+                "weather always goes back to no weather, its fraction can't be 0".to_string(),
+            );
+        }
+        if let Some(state) = self.states.iter().find(|s| s.mean_duration <= 0.0) {
+            return Err(format!("{:?} must last a positive duration", state.weather));
+        }
+        Ok(())
+    }
+}
+
+impl ConfigWeather {
+    pub fn validate(&self) -> Result<(), String> {
+        self.chain.validate()?;
+        if self.wind_angle_range.0 > self.wind_angle_range.1
+            || self.wind_force_range.0 > self.wind_force_range.1
+        {
+            return Err("wind ranges must be ordered (min, max)".to_string());
+        }
+        // the wind gives its direction, it can't be drawn without force
+        if self.wind_force_range.0 <= 0.0 {
+            return Err("wind force must be positive".to_string());
+        }
+        if let Some(timeline) = &self.timeline {
+            if timeline.windows(2).any(|w| w[0].start > w[1].start) {
+                return Err("weather timeline must be sorted by start".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws a wind from the configured direction and force ranges
+    pub fn random_wind<R: Rng + ?Sized>(&self, rng: &mut R) -> rapier3d::prelude::Vector<f32> {
+        let (min_angle, max_angle) = self.wind_angle_range;
+        let (min_force, max_force) = self.wind_force_range;
+        let angle = rng.gen_range(min_angle..=max_angle).to_radians();
+        let force = rng.gen_range(min_force..=max_force);
+        rapier3d::prelude::Vector::new(angle.cos(), 0.0, angle.sin()) * force
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(no_weather_fraction: f64, rain_fraction: f64) -> ConfigWeatherChain {
+        ConfigWeatherChain {
+            no_weather_fraction,
+            states: vec![ConfigWeatherState {
+                weather: WeatherKind::Rainy,
+                fraction: rain_fraction,
+                mean_duration: 20.0,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_weather_fractions_sum_to_one() {
+        assert!(chain(0.75, 0.25).validate().is_ok());
+        assert!(chain(0.75, 0.5).validate().is_err());
+        assert!(chain(1.25, -0.25).validate().is_err());
+        assert!(chain(0.0, 1.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_wind_force_must_be_positive() {
+        let game: ConfigGame = crate::configs::from_file("../game.json").unwrap();
+        let mut weather_config = game.weather_config;
+        assert!(weather_config.validate().is_ok());
+        weather_config.wind_force_range = (0.0, 10.0);
+        assert!(weather_config.validate().is_err());
+    }
 }
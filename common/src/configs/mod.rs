@@ -9,7 +9,7 @@ pub mod scene_config;
 pub mod texture_config;
 
 use once_cell::sync::Lazy as OnceCellLazy;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
@@ -34,6 +34,9 @@ pub const DISPLAY_CONFIG_PATH: &str = "display.json";
 pub const TEXTURE_CONFIG_PATH: &str = "tex.json";
 pub const PHYSICS_CONFIG_PATH: &str = "physics.json";
 pub const PARTICLE_CONFIG_PATH: &str = "particles.json";
+// the configs of ConfigTuning, reloaded when they change while the server runs
+pub const TUNING_CONFIG_PATHS: [&str; 3] =
+    [GAME_CONFIG_PATH, PHYSICS_CONFIG_PATH, PARTICLE_CONFIG_PATH];
 
 // TODO:
 /* there are some more constants in command_handler,
@@ -49,12 +52,18 @@ pub static CONFIG_INSTANCE: OnceCellLazy<RwLock<Option<Arc<Config>>>> = OnceCell
     let audio: ConfigAudioAssets =
         from_file(AUDIO_CONFIG_PATH).expect("Failed to load audio config");
     let player: ConfigGame = from_file(GAME_CONFIG_PATH).expect("Failed to load player config");
+    if let Err(e) = player.validate() {
+        panic!("Invalid game config: {}", e);
+    }
     let display: ConfigDisplay =
         from_file(DISPLAY_CONFIG_PATH).expect("Failed to load display config");
     let texture: ConfigTexture =
         from_file(TEXTURE_CONFIG_PATH).expect("Failed to load texture config");
     let physics: ConfigPhysics =
         from_file(PHYSICS_CONFIG_PATH).expect("Failed to load physics config");
+    if let Err(e) = physics.validate() {
+        panic!("Invalid physics config: {}", e);
+    }
     let particles: ConfigParticle =
         from_file(PARTICLE_CONFIG_PATH).expect("Failed to load particle config");
     let config = Config::new(
@@ -113,6 +122,73 @@ impl Config {
     }
 }
 
+impl Config {
+    pub fn tuning(&self) -> ConfigTuning {
+        ConfigTuning {
+            game: self.game.clone(),
+            physics: self.physics.clone(),
+            particles: self.particles.clone(),
+        }
+    }
+
+    pub fn gameplay(&self) -> ConfigGameplay {
+        ConfigGameplay {
+            game: self.game.clone(),
+            physics: self.physics.clone(),
+        }
+    }
+}
+
+/// The balance of the game, can be tuned while the server runs, the clients follow its gameplay
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigTuning {
+    pub game: ConfigGame,
+    pub physics: ConfigPhysics,
+    pub particles: ConfigParticle,
+}
+
+impl ConfigTuning {
+    /// Reads the tuning from its files, rejecting what the game can't run with
+    pub fn load() -> Result<Self, String> {
+        let game: ConfigGame =
+            from_file(GAME_CONFIG_PATH).map_err(|e| format!("{}: {}", GAME_CONFIG_PATH, e))?;
+        game.validate()
+            .map_err(|e| format!("{}: {}", GAME_CONFIG_PATH, e))?;
+        let physics: ConfigPhysics = from_file(PHYSICS_CONFIG_PATH)
+            .map_err(|e| format!("{}: {}", PHYSICS_CONFIG_PATH, e))?;
+        physics
+            .validate()
+            .map_err(|e| format!("{}: {}", PHYSICS_CONFIG_PATH, e))?;
+        let particles: ConfigParticle = from_file(PARTICLE_CONFIG_PATH)
+            .map_err(|e| format!("{}: {}", PARTICLE_CONFIG_PATH, e))?;
+        Ok(Self {
+            game,
+            physics,
+            particles,
+        })
+    }
+
+    /// Follows the rules of the server, the presentation stays as it is,
+    /// that is the particles and the camera sensitivity
+    pub fn with_gameplay(self, gameplay: ConfigGameplay) -> Self {
+        Self {
+            game: ConfigGame {
+                camera_config: self.game.camera_config,
+                ..gameplay.game
+            },
+            physics: gameplay.physics,
+            particles: self.particles,
+        }
+    }
+}
+
+/// The rules of the server (the weather is part of the game config), which the clients mirror
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigGameplay {
+    pub game: ConfigGame,
+    pub physics: ConfigPhysics,
+}
+
 pub mod ConfigurationManager {
     use super::*;
 
@@ -124,18 +200,46 @@ pub mod ConfigurationManager {
             .expect("Configuration not loaded.")
             .clone()
     }
+
+    /// Swaps in a configuration with the tuning, who holds the previous one keeps it
+    pub fn apply_tuning(tuning: ConfigTuning) -> Arc<Config> {
+        swap(|_| tuning)
+    }
+
+    /// Swaps in a configuration following the rules of the server, see ConfigTuning::with_gameplay
+    pub fn apply_gameplay(gameplay: ConfigGameplay) -> Arc<Config> {
+        swap(|current| current.tuning().with_gameplay(gameplay))
+    }
+
+    fn swap(tuning_of: impl FnOnce(&Config) -> ConfigTuning) -> Arc<Config> {
+        let mut instance = CONFIG_INSTANCE.write().unwrap();
+        let current = instance.as_ref().expect("Configuration not loaded.");
+        let tuning = tuning_of(current);
+        let config = Arc::new(Config::new(
+            current.models.clone(),
+            current.scene.clone(),
+            current.lobby_scene.clone(),
+            current.end_screen_scene.clone(),
+            current.audio.clone(),
+            tuning.game,
+            current.display.clone(),
+            current.texture.clone(),
+            tuning.physics,
+            tuning.particles,
+        ));
+        *instance = Some(config.clone());
+        config
+    }
 }
 
 pub fn from_file<P: AsRef<Path>, S: serde::de::DeserializeOwned>(
     path: P,
 ) -> Result<S, serde_json::Error> {
-    // clone the path
-    let path_buf = path.as_ref().to_path_buf();
-    let mut file = File::open(path)
-        .unwrap_or_else(|_| panic!("Unable to open the file: {}", path_buf.to_str().unwrap()));
+    // a missing file is an error too, a reload may find it in the middle of being saved
     let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .expect("Unable to read the file");
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut contents))
+        .map_err(serde_json::Error::io)?;
     serde_json::from_str(&contents)
 }
 
@@ -146,3 +250,53 @@ pub fn to_file<P: AsRef<Path>, S: Serialize>(s: &S, path: P) -> Result<(), serde
         .expect("Unable to write to the file");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tuning() -> ConfigTuning {
+        ConfigTuning {
+            game: from_file("../game.json").unwrap(),
+            physics: from_file("../physics.json").unwrap(),
+            particles: from_file("../particles.json").unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_gameplay_keeps_presentation() {
+        let local = tuning();
+        let mut gameplay = ConfigGameplay {
+            game: local.game.clone(),
+            physics: local.physics.clone(),
+        };
+        gameplay.game.camera_config.x_sensitivity = local.game.camera_config.x_sensitivity * 2.0;
+        gameplay.game.winning_threshold = local.game.winning_threshold + 1.0;
+        gameplay.game.weather_config.start_delay = local.game.weather_config.start_delay + 1.0;
+        gameplay.physics.attack_config.attack_impulse += 1.0;
+
+        let merged = local.clone().with_gameplay(gameplay.clone());
+        // the rules are the server's
+        assert_eq!(
+            merged.game.winning_threshold,
+            gameplay.game.winning_threshold
+        );
+        assert_eq!(
+            merged.game.weather_config.start_delay,
+            gameplay.game.weather_config.start_delay
+        );
+        assert_eq!(
+            merged.physics.attack_config.attack_impulse,
+            gameplay.physics.attack_config.attack_impulse
+        );
+        // the presentation is still ours
+        assert_eq!(
+            merged.game.camera_config.x_sensitivity,
+            local.game.camera_config.x_sensitivity
+        );
+        assert_eq!(
+            format!("{:?}", merged.particles.charge_particle_config),
+            format!("{:?}", local.particles.charge_particle_config)
+        );
+    }
+}
@@ -13,6 +13,8 @@ pub struct ConfigParticle {
     pub blizzard_particle_config: BlizzardParticleConfig,
     pub powerup_particle_config: PowerUpParticleConfig,
     pub powerup_aura_particle_config: PowerUpAuraParticleConfig,
+    pub charge_particle_config: ChargeParticleConfig,
+    pub projectile_particle_config: ProjectileParticleConfig,
     pub winning_area_ribbon_particle_config: WinningAreaRibbonParticleConfig,
 }
 
@@ -74,6 +76,38 @@ pub struct PowerUpAuraParticleConfig {
     pub gen_speed: f32,
 }
 
+/// Aura of a charging attack, the min values at no charge and the max values at full charge
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChargeParticleConfig {
+    pub time: f32,
+    pub min_r: f32,
+    pub max_r: f32,
+    pub half_height: f32,
+    pub default_color: (f32, f32, f32, f32),
+    pub linear_speed: f32,
+    pub linear_variance: f32,
+    pub angular_variance: f32,
+    pub min_size: f32,
+    pub max_size: f32,
+    pub size_variance: f32,
+    pub size_growth: f32,
+    pub min_gen_speed: f32,
+    pub max_gen_speed: f32,
+}
+
+/// Trail of a projectile in flight, colored like the active aura of its power-up
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectileParticleConfig {
+    pub time: f32,
+    pub linear_speed: f32,
+    pub linear_variance: f32,
+    pub angular_variance: f32,
+    pub size: f32,
+    pub size_variance: f32,
+    pub size_growth: f32,
+    pub gen_speed: f32,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WinningAreaRibbonParticleConfig {
     pub bounds_min: (f32, f32, f32),
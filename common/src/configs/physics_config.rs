@@ -4,6 +4,53 @@ use serde::{Deserialize, Serialize};
 pub struct ConfigPhysics {
     pub attack_config: ConfigAttack,
     pub movement_config: ConfigAction,
+    pub lag_compensation: ConfigLagCompensation,
+    pub character_controller: ConfigCharacterController,
+}
+
+impl ConfigPhysics {
+    /// Checks what serde can't, the simulation can't run with an invalid config
+    pub fn validate(&self) -> Result<(), String> {
+        let lag_compensation = &self.lag_compensation;
+        if lag_compensation.interpolation_delay < 0.0 || lag_compensation.max_rewind < 0.0 {
+            return Err("lag compensation delays can't be negative".to_string());
+        }
+        let attack = &self.attack_config;
+        if [
+            attack.attack_cooldown,
+            attack.area_attack_cooldown,
+            attack.max_charge_time,
+        ]
+        .iter()
+        .any(|&time| time < 0.0)
+        {
+            return Err("attack cooldowns and charge time can't be negative".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.movement_config.air_control) {
+            return Err("air control must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Attacks are resolved against where the attacker saw the other players,
+/// that is their latency plus interpolation_delay ago, at most max_rewind seconds ago
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ConfigLagCompensation {
+    pub interpolation_delay: f32,
+    pub max_rewind: f32,
+}
+
+/// How the character controller moves players, lengths are absolute and angles in radians
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ConfigCharacterController {
+    pub kinematic: bool, // players are kinematic position-based bodies rather than dynamic ones
+    pub max_slope_climb_angle: f32,
+    pub min_slope_slide_angle: f32,
+    pub autostep_max_height: f32, // 0 to never step up
+    pub autostep_min_width: f32,
+    pub snap_to_ground: f32, // 0 to never snap
+    pub offset: f32,         // gap kept between players and obstacles
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -20,6 +67,39 @@ pub struct ConfigAttack {
     pub area_attack_cost: u32,
     pub area_attack_cooldown: f32,
     pub max_attack_stun_duration: f32,
+    // hold-to-charge attack, scales grow linearly up to their max at max_charge_time
+    pub max_charge_time: f32,
+    pub max_charge_impulse_scale: f32,
+    pub max_charge_range_scale: f32,
+    pub max_charge_cost_scale: f32,
+}
+
+impl ConfigAttack {
+    /// Charge level in [0, 1] of an attack held for charge_time seconds
+    pub fn charge_level(&self, charge_time: f32) -> f32 {
+        if self.max_charge_time <= 0.0 {
+            return 1.0;
+        }
+        (charge_time / self.max_charge_time).clamp(0.0, 1.0)
+    }
+
+    /// Impulse and range scales of an attack charged to the given level
+    pub fn charge_scales(&self, charge_level: f32) -> (f32, f32) {
+        (
+            lerp_scale(self.max_charge_impulse_scale, charge_level),
+            lerp_scale(self.max_charge_range_scale, charge_level),
+        )
+    }
+
+    /// Wind charge consumed by an attack charged to the given level
+    pub fn charged_attack_cost(&self, charge_level: f32) -> u32 {
+        (self.attack_cost as f32 * lerp_scale(self.max_charge_cost_scale, charge_level)).round()
+            as u32
+    }
+}
+
+fn lerp_scale(max_scale: f32, charge_level: f32) -> f32 {
+    1.0 + (max_scale - 1.0) * charge_level
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -31,4 +111,23 @@ pub struct ConfigAction {
     pub max_jump_count: u32,
     pub jump_impulse: f32,
     pub jump_cooldown: f32,
+    pub coyote_time: f32, // seconds after walking off a ledge the ground jump is still there
+    pub jump_buffer: f32, // seconds a jump pressed in the air waits for the landing
+    pub air_control: f32, // fraction of step_size while airborne
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let mut physics = ConfigPhysics::default();
+        assert!(physics.validate().is_ok());
+        physics.movement_config.air_control = 1.5;
+        assert!(physics.validate().is_err());
+        physics.movement_config.air_control = 0.5;
+        physics.lag_compensation.max_rewind = -0.1;
+        assert!(physics.validate().is_err());
+    }
 }
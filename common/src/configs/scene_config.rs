@@ -1,5 +1,6 @@
 use nalgebra_glm::{Quat, TVec3};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ConfigTransform {
@@ -14,9 +15,64 @@ pub struct ConfigNode {
     pub children: Option<Vec<ConfigNode>>,
     pub model: Option<String>,
     pub decompose: Option<bool>,
+    pub behavior: Option<ConfigBehavior>,
+}
+
+/// What a scene node does during a match, nodes without a behavior never move
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ConfigBehavior {
+    // loops through the waypoints, given as offsets from where the node is placed
+    MovingPlatform {
+        waypoints: Vec<TVec3<f32>>,
+        speed: f32,
+    },
+    // launches players touching it along its up direction
+    BouncePad {
+        impulse: f32,
+        cooldown: f32,
+    },
+    // volumes are centered on the node and replace its collider, the force is in its frame
+    WindTunnel {
+        half_extents: TVec3<f32>,
+        force: TVec3<f32>,
+    },
+    KillVolume {
+        half_extents: TVec3<f32>,
+    },
+    // falls crumble_delay seconds after being stood on, back in place after respawn_delay
+    CrumblingPlatform {
+        crumble_delay: f32,
+        respawn_delay: f32,
+    },
+}
+
+impl ConfigNode {
+    /// This node and all of its descendants
+    pub fn descendants(&self) -> Vec<&ConfigNode> {
+        let mut nodes = vec![self];
+        let mut i = 0;
+        while i < nodes.len() {
+            if let Some(children) = &nodes[i].children {
+                nodes.extend(children.iter());
+            }
+            i += 1;
+        }
+        nodes
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ConfigSceneGraph {
     pub nodes: Vec<ConfigNode>,
 }
+
+impl ConfigSceneGraph {
+    /// Behavior of every node that has one, by node id
+    pub fn behaviors(&self) -> HashMap<String, ConfigBehavior> {
+        self.nodes
+            .iter()
+            .flat_map(|n| n.descendants())
+            .filter_map(|n| Some((n.id.clone(), n.behavior.clone()?)))
+            .collect()
+    }
+}
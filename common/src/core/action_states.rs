@@ -7,6 +7,7 @@ pub enum ActionState {
     Walking,
     Attacking,
     SpecialAttacking,
+    Charging,
     CastingPowerUp,
     Frozen,
     Wave
@@ -26,6 +27,7 @@ impl ActionState {
             ActionState::Walking => 2,
             ActionState::Attacking => 3,
             ActionState::SpecialAttacking => 5,
+            ActionState::Charging => 4,
             ActionState::CastingPowerUp => 6,
             ActionState::Frozen => 7,
             ActionState::Wave => 1
@@ -39,6 +41,7 @@ impl ActionState {
             ActionState::Walking => "walk",
             ActionState::Attacking => "regular_attack",
             ActionState::SpecialAttacking => "special_attack",
+            ActionState::Charging => "charge_attack",
             ActionState::CastingPowerUp => "powerup_attack",
             ActionState::Frozen => "frozen",
             ActionState::Wave => "wave"
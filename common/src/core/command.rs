@@ -32,9 +32,19 @@ pub enum CheatCodeControl {
 pub enum CheatKeyWeather {
     Rain,
     Wind,
+    Snow,
+    Fog,
+    Storm,
     Reset,
 }
 
+/// Phases of a hold-to-charge command, sent on press and on release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChargePhase {
+    Begin,
+    Release,
+}
+
 /// Commands that can be issued by the client
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Command {
@@ -47,6 +57,7 @@ pub enum Command {
     UpdateCamera { forward: glm::Vec3 },
     Attack,
     AreaAttack,
+    ChargeAttack(ChargePhase),
     Refill,
     CastPowerUp,
     GivePowerUp,
@@ -55,10 +66,19 @@ pub enum Command {
     Flash,
     UpdateWeather,
     WeatherEffects,
+    UpdateHazards,
+    UpdateGround,
+    UpdateProjectiles,
     CheatCode(PowerUp),
     CheatCodeControl(CheatCodeControl),
     WeatherCheatKey(CheatKeyWeather),
-    Wave
+    Wave,
+    // starts or stops the physics debug stream to the client
+    PhysicsDebug,
+    // issued by the server's client handler only
+    Join,
+    Leave,
+    Latency(u32), // round trip to the client in ms
 }
 
 impl Command {
@@ -68,6 +88,14 @@ impl Command {
             _ => panic!("Command is not a move command"),
         }
     }
+
+    /// The command a press-and-release key sends when it is released
+    pub fn on_release(&self) -> Command {
+        match self {
+            Command::ChargeAttack(_) => Command::ChargeAttack(ChargePhase::Release),
+            command => command.clone(),
+        }
+    }
 }
 
 impl PartialEq for Command {
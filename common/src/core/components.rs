@@ -2,6 +2,35 @@ extern crate nalgebra_glm as glm;
 use glm::{Quat, Vec3};
 use serde::{Deserialize, Serialize};
 
+/// Id of something in the physics world, players are their client id
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Entity(u32);
+
+impl Entity {
+    // client ids are u8, the ids above are never a player
+    pub const MAX_PLAYER_ID: u32 = u8::MAX as u32;
+
+    /// The entity of a player, its client id
+    pub const fn player(player_id: u32) -> Self {
+        assert!(player_id <= Self::MAX_PLAYER_ID, "not a client id");
+        Self(player_id)
+    }
+
+    /// The client id if this is a player
+    pub fn player_id(self) -> Option<u32> {
+        (self.0 <= Self::MAX_PLAYER_ID).then_some(self.0)
+    }
+
+    /// As stored in the user data of a collider, only the entity registry makes up new ones
+    pub const fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    pub fn raw(self) -> u32 {
+        self.0
+    }
+}
+
 /// A component that represents the position, rotation, and scale of an entity.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Transform {
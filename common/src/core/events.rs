@@ -1,6 +1,11 @@
 use derive_more::Constructor;
 use serde::{Deserialize, Serialize};
 
+use crate::core::physics_debug::PhysicsDebugFrame;
+use crate::core::powerup_system::PowerUp;
+use crate::core::states::MatchPhase;
+use crate::core::weather::Weather;
+
 extern crate nalgebra_glm as glm;
 
 /// Event that the server send to the client
@@ -8,6 +13,25 @@ extern crate nalgebra_glm as glm;
 pub enum GameEvent {
     SoundEvent(SoundSpec),
     ParticleEvent(ParticleSpec),
+    PlayerJoined(u32),
+    PlayerLeft(u32),
+    /// `by` is the player credited with the ring-out, if any
+    PlayerKnockedOut { victim: u32, by: Option<u32> },
+    PowerUpPickedUp { player: u32, power_up: PowerUp },
+    PowerUpCast { player: u32, power_up: PowerUp },
+    WeatherChanged(Option<Weather>),
+    /// a telegraphed lightning strike hit the ground
+    LightningStruck(glm::Vec3),
+    /// players standing on the flag when it became contested
+    FlagContested(Vec<u32>),
+    /// a player became the only one holding the flag
+    FlagCaptured(u32),
+    MatchStarted,
+    /// the match clock ran out and the match went to overtime or sudden death
+    MatchPhaseChanged(MatchPhase),
+    MatchEnded { winner: u32 },
+    /// collider outlines and the latest attack queries, only for the clients that asked
+    PhysicsDebug(PhysicsDebugFrame),
 }
 
 /// Sound specification
@@ -30,6 +54,11 @@ pub enum ParticleType {
     REFILL_ATTACK,
     RAIN,
     WIND,
+    SNOW,
+    FOG,
+    /// warning circle of an upcoming lightning strike
+    TELEGRAPH,
+    LIGHTNING,
 }
 /// Particle specification
 #[derive(Constructor, Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -41,3 +70,15 @@ pub struct ParticleSpec {
     pub color: glm::Vec4,
     pub particle_id: String,
 }
+
+/// Gameplay events received from the server, waiting to be consumed by the client
+#[derive(Debug, Clone, Default)]
+pub struct GameplayEventQueue {
+    pub events: Vec<GameEvent>,
+}
+
+impl GameplayEventQueue {
+    pub fn add_event(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+}
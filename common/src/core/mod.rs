@@ -4,6 +4,9 @@ pub mod command;
 pub mod components;
 pub mod events;
 pub mod mesh_color;
+pub mod physics_debug;
 pub mod powerup_system;
 pub mod states;
+pub mod status_effects;
+pub mod stats;
 pub mod weather;
@@ -0,0 +1,227 @@
+extern crate nalgebra_glm as glm;
+
+use glm::Vec3;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// Seconds between two frames of the debug stream
+pub const PHYSICS_DEBUG_PERIOD: f32 = 0.3;
+// straight lines drawn for a full circle
+const CIRCLE_SEGMENTS: usize = 16;
+
+pub type DebugLine = (Vec3, Vec3);
+
+/// Outline of a collider, simplified to keep the debug stream small
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DebugOutline {
+    Aabb { mins: Vec3, maxs: Vec3 },
+    // a ball when a and b are the same
+    Capsule { a: Vec3, b: Vec3, radius: f32 },
+    // edges of a convex hull or of a rotated box
+    Wireframe(Vec<DebugLine>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DebugCollider {
+    pub outline: DebugOutline,
+    pub sensor: bool,
+    pub moving: bool, // not attached to a fixed body
+}
+
+/// The volume an attack reaches, see TargetQuery on the server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DebugVolume {
+    Cone {
+        origin: Vec3,
+        direction: Vec3,
+        half_angle: f32,
+        range: f32,
+    },
+    Sphere {
+        center: Vec3,
+        radius: f32,
+    },
+    CapsuleSweep {
+        origin: Vec3,
+        direction: Vec3,
+        distance: f32,
+        radius: f32,
+    },
+}
+
+/// A ray from where a target was seen towards its center, stopped by what blocked it if not hit
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DebugRay {
+    pub from: Vec3,
+    pub to: Vec3,
+    pub hit: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DebugQuery {
+    pub volume: DebugVolume,
+    pub rays: Vec<DebugRay>,
+}
+
+/// What the server streams to the clients that asked for the physics debug overlay
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PhysicsDebugFrame {
+    pub colliders: Vec<DebugCollider>,
+    pub queries: Vec<DebugQuery>, // latest last
+}
+
+impl DebugOutline {
+    pub fn lines(&self) -> Vec<DebugLine> {
+        match self {
+            DebugOutline::Aabb { mins, maxs } => {
+                let corner = |i: usize| {
+                    Vec3::new(
+                        if i & 1 == 0 { mins.x } else { maxs.x },
+                        if i & 2 == 0 { mins.y } else { maxs.y },
+                        if i & 4 == 0 { mins.z } else { maxs.z },
+                    )
+                };
+                box_lines(std::array::from_fn(corner))
+            }
+            DebugOutline::Capsule { a, b, radius } => {
+                let axis = b - a;
+                if glm::length(&axis) <= f32::EPSILON {
+                    return sphere_lines(a, *radius);
+                }
+                let axis = glm::normalize(&axis);
+                let (u, v) = basis(&axis);
+                let mut lines = Vec::new();
+                for (end, cap) in [(a, -axis), (b, axis)] {
+                    lines.extend(arc(end, &u, &v, *radius, 0.0, 2.0 * PI));
+                    // half circles over the ends
+                    lines.extend(arc(end, &u, &cap, *radius, 0.0, PI));
+                    lines.extend(arc(end, &v, &cap, *radius, 0.0, PI));
+                }
+                for side in [u, -u, v, -v] {
+                    lines.push((a + side * *radius, b + side * *radius));
+                }
+                lines
+            }
+            DebugOutline::Wireframe(lines) => lines.clone(),
+        }
+    }
+}
+
+impl DebugVolume {
+    pub fn lines(&self) -> Vec<DebugLine> {
+        match *self {
+            DebugVolume::Cone {
+                origin,
+                direction,
+                half_angle,
+                range,
+            } => {
+                let direction = glm::normalize(&direction);
+                let (u, v) = basis(&direction);
+                // the rim where the cone reaches its range
+                let rim_center = origin + direction * range * half_angle.cos();
+                let rim_radius = range * half_angle.sin();
+                let mut lines = arc(&rim_center, &u, &v, rim_radius, 0.0, 2.0 * PI);
+                for side in [u, -u, v, -v] {
+                    lines.push((origin, rim_center + side * rim_radius));
+                }
+                lines
+            }
+            DebugVolume::Sphere { center, radius } => sphere_lines(&center, radius),
+            DebugVolume::CapsuleSweep {
+                origin,
+                direction,
+                distance,
+                radius,
+            } => DebugOutline::Capsule {
+                a: origin,
+                b: origin + glm::normalize(&direction) * distance,
+                radius,
+            }
+            .lines(),
+        }
+    }
+}
+
+/// The 12 edges of a box, corner i is at the max of x if i & 1, of y if i & 2 and of z if i & 4
+pub fn box_lines(corners: [Vec3; 8]) -> Vec<DebugLine> {
+    let mut lines = Vec::new();
+    for i in 0..8 {
+        for bit in [1, 2, 4] {
+            if i & bit == 0 {
+                lines.push((corners[i], corners[i | bit]));
+            }
+        }
+    }
+    lines
+}
+
+fn sphere_lines(center: &Vec3, radius: f32) -> Vec<DebugLine> {
+    let (x, y, z) = (Vec3::x(), Vec3::y(), Vec3::z());
+    [(x, y), (y, z), (z, x)]
+        .iter()
+        .flat_map(|(u, v)| arc(center, u, v, radius, 0.0, 2.0 * PI))
+        .collect()
+}
+
+/// Part of the circle of the radius around center, in the plane of u and v
+fn arc(center: &Vec3, u: &Vec3, v: &Vec3, radius: f32, from: f32, to: f32) -> Vec<DebugLine> {
+    let segments = ((to - from) / (2.0 * PI) * CIRCLE_SEGMENTS as f32).ceil() as usize;
+    let point = |i: usize| {
+        let angle = from + (to - from) * i as f32 / segments as f32;
+        center + (u * angle.cos() + v * angle.sin()) * radius
+    };
+    (0..segments).map(|i| (point(i), point(i + 1))).collect()
+}
+
+/// Two unit vectors orthogonal to the axis and to each other
+fn basis(axis: &Vec3) -> (Vec3, Vec3) {
+    let other = if axis.y.abs() < 0.9 {
+        Vec3::y()
+    } else {
+        Vec3::x()
+    };
+    let u = glm::normalize(&glm::cross(axis, &other));
+    (u, glm::cross(axis, &u))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let aabb = DebugOutline::Aabb {
+            mins: Vec3::new(-1.0, -1.0, -1.0),
+            maxs: Vec3::new(1.0, 1.0, 1.0),
+        };
+        let lines = aabb.lines();
+        assert_eq!(lines.len(), 12);
+        // every edge runs along one axis and is as long as the box
+        assert!(lines
+            .iter()
+            .all(|(a, b)| (glm::distance(a, b) - 2.0).abs() < 1e-5));
+
+        let capsule = DebugOutline::Capsule {
+            a: Vec3::zeros(),
+            b: Vec3::new(0.0, 2.0, 0.0),
+            radius: 0.5,
+        };
+        // every point of the outline is at the radius from the segment
+        for (point, _) in capsule.lines() {
+            let on_axis = Vec3::new(0.0, point.y.clamp(0.0, 2.0), 0.0);
+            assert!((glm::distance(&point, &on_axis) - 0.5).abs() < 1e-4);
+        }
+
+        let cone = DebugVolume::Cone {
+            origin: Vec3::zeros(),
+            direction: Vec3::new(0.0, 0.0, 2.0),
+            half_angle: 0.5,
+            range: 3.0,
+        };
+        for (_, rim) in cone.lines().iter().skip(CIRCLE_SEGMENTS) {
+            assert!((glm::length(rim) - 3.0).abs() < 1e-4);
+            assert!((glm::angle(rim, &Vec3::z()) - 0.5).abs() < 1e-4);
+        }
+    }
+}
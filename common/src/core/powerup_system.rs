@@ -1,51 +1,66 @@
-use phf::phf_map;
-use rand::distributions::Uniform;
-use rand::{
-    distributions::{Distribution, Standard},
-    Rng,
-};
+use crate::core::action_states::ActionState;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
-pub enum PowerUp {
-    Blizzard,
-    WindEnhancement,
-    Dash,
-    Flash,
-    Invisible,
-    TripleJump,
-    Invincible, // maybe
+/// Id of a power-up definition in the game config
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PowerUp(pub String);
+
+/// How a power-up is used once picked up
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerUpActivation {
+    Instant, // effects apply when cast, then the power-up is gone
+    Held,    // active while its status effects last, casting again triggers on_use
 }
 
-impl PowerUp {
-    pub fn value(&self) -> u32 {
-        match *self {
-            PowerUp::Blizzard => 1,
-            PowerUp::WindEnhancement => 2,
-            PowerUp::Dash => 3,
-            PowerUp::Flash => 4,
-            PowerUp::Invisible => 5,
-            PowerUp::TripleJump => 6,
-            PowerUp::Invincible => 7, //to be implemented
-        }
-    }
+/// Effect primitives a power-up definition is made of, directions follow the caster's camera
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PowerUpEffect {
+    Status {
+        effect: StatusEffect,
+        duration: f32,
+        magnitude: f32,
+    },
+    Impulse {
+        strength: f32,
+        blocking_duration: f32, // movement is disabled for this long
+    },
+    Teleport {
+        distance: f32,
+    },
+    AreaDebuff {
+        effects: Vec<StatusEffect>,
+        action_state: Option<ActionState>,
+        duration: f32,
+        max_dist: f32,
+        max_angle: f32,
+    },
+    // launched from the caster, hits the first player or solid thing it touches
+    Projectile {
+        speed: f32,
+        radius: f32,
+        gravity_scale: f32,
+        lifetime: f32, // it vanishes without effect after that long
+        on_hit: Vec<ProjectileHit>,
+    },
 }
 
-impl Distribution<PowerUp> for Standard {
-    // adjusted ratios
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PowerUp {
-        let between = Uniform::from(1..13);
-        match between.sample(rng) {
-            1 => PowerUp::Blizzard,
-            2 | 3 => PowerUp::WindEnhancement,
-            4 | 5 => PowerUp::Dash,
-            6 | 7 => PowerUp::Flash,
-            8 | 9 => PowerUp::Invisible,
-            10 | 11 => PowerUp::TripleJump,
-            _ => PowerUp::Invincible,
-            // _ => PowerUp::Dash,
-        }
-    }
+/// What a projectile does where it hits
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ProjectileHit {
+    // on the player hit, along the flight of the projectile
+    Impulse {
+        strength: f32,
+    },
+    Status {
+        effect: StatusEffect,
+        duration: f32,
+        magnitude: f32,
+    },
+    // on every player in the radius but the owner, away from the hit
+    Burst {
+        radius: f32,
+        strength: f32,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
@@ -74,15 +89,6 @@ pub enum OtherEffects {
     Slippery,
 }
 
-pub static POWER_UP_TO_EFFECT_MAP: phf::Map<u32, StatusEffect> = phf_map! {
-    2u32 => StatusEffect::Power(PowerUpEffects::EnhancedWind),
-    3u32 => StatusEffect::Power(PowerUpEffects::EnabledDash),
-    4u32 => StatusEffect::Power(PowerUpEffects::EnabledFlash),
-    5u32 => StatusEffect::Power(PowerUpEffects::Invisible),
-    6u32 => StatusEffect::Power(PowerUpEffects::TripleJump),
-    7u32 => StatusEffect::Power(PowerUpEffects::Invincible),
-};
-
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PowerUpLocations {
     PowerUp1XYZ,
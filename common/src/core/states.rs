@@ -1,26 +1,94 @@
 use nalgebra_glm as glm;
 use nalgebra_glm::Vec3;
+use rand::Rng;
 use rapier3d::prelude::Vector;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::configs::game_config::ConfigGame;
+use crate::configs::game_config::{ConfigGame, ConfigMatch, ConfigPowerUp, ZoneKind};
+use crate::configs::physics_config::ConfigAttack;
 use crate::core::action_states::ActionState;
 use crate::core::choices::FinalChoices;
 use crate::core::command::Command;
-use crate::core::components::{Physics, Transform};
+use crate::core::components::{Entity, Physics, Transform};
 use crate::core::events::ParticleSpec;
 use crate::core::powerup_system::StatusEffect::Power;
 use crate::core::powerup_system::{
-    PowerUp, PowerUpLocations, PowerUpStatus, StatusEffect, POWER_UP_TO_EFFECT_MAP,
+    OtherEffects, PowerUp, PowerUpLocations, PowerUpStatus, ProjectileHit, StatusEffect,
 };
-use crate::core::weather::Weather;
+use crate::core::stats::{LastHit, MatchSummary, PlayerStats};
+use crate::core::status_effects::{effective_stat, ActiveStatusEffect, Stat};
+use crate::core::weather::{LightningStrike, Weather};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct WorldState {
     pub weather: Option<Weather>,
     pub prev_weather: Option<Weather>,
+    pub lightning_strikes: Vec<LightningStrike>,
+    pub hazards: HashMap<String, HazardState>, // by scene node id
+    pub zones: HashMap<Entity, ZoneState>,     // by entity
+    pub projectiles: HashMap<Entity, ProjectileState>, // by entity
+}
+
+/// A scene node with a behavior, simulated by the server and drawn where it is by the clients
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HazardState {
+    pub entity: Entity,
+    pub origin: Transform,    // in the world, where the scene places it
+    pub transform: Transform, // in the world, where it is now
+    pub phase: HazardPhase,
+    pub next_waypoint: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum HazardPhase {
+    Idle,
+    Recharging(f32), // time left before it can trigger again
+    Crumbling(f32),  // time left before it falls
+    Falling(f32),    // time since it started falling
+}
+
+impl HazardState {
+    pub fn new(entity: Entity, origin: Transform) -> Self {
+        Self {
+            entity,
+            transform: origin.clone(),
+            origin,
+            phase: HazardPhase::Idle,
+            next_waypoint: 0,
+        }
+    }
+}
+
+/// A projectile in flight, simulated by the server and drawn with a trail by the clients
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectileState {
+    pub owner: u32,
+    pub power_up: PowerUp, // the trail takes the color of its active aura
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub radius: f32,
+    pub time_left: f32,
+    pub on_hit: Vec<ProjectileHit>,
+}
+
+/// A zone of the level, who is inside comes from the intersections with its sensor
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ZoneState {
+    pub kind: ZoneKind,
+    pub position: Vec3,
+    pub occupants: BTreeSet<u32>,
+}
+
+impl ZoneState {
+    pub fn new(kind: ZoneKind, position: Vec3) -> Self {
+        Self {
+            kind,
+            position,
+            occupants: BTreeSet::new(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -35,16 +103,19 @@ pub struct GameState {
     pub game_winner: Option<u32>,
     pub game_start_time: Duration,
     pub prev_winner: Option<(u32, FinalChoices)>,
+    pub match_summary: Option<MatchSummary>,
+    pub match_clock: MatchClock,
 }
 
 impl GameState {
     pub fn new() -> Self {
+        // all locations are vacant, they are filled on the first tick from the power-up config
         let mut active_power_ups: HashMap<PowerUpLocations, (f32, Option<PowerUp>)> =
             HashMap::new();
-        active_power_ups.insert(PowerUpLocations::PowerUp1XYZ, (0.0, Some(rand::random())));
-        active_power_ups.insert(PowerUpLocations::PowerUp2XYZ, (0.0, Some(rand::random())));
-        active_power_ups.insert(PowerUpLocations::PowerUp3XYZ, (0.0, Some(rand::random())));
-        active_power_ups.insert(PowerUpLocations::PowerUp4XYZ, (0.0, Some(rand::random())));
+        active_power_ups.insert(PowerUpLocations::PowerUp1XYZ, (0.0, None));
+        active_power_ups.insert(PowerUpLocations::PowerUp2XYZ, (0.0, None));
+        active_power_ups.insert(PowerUpLocations::PowerUp3XYZ, (0.0, None));
+        active_power_ups.insert(PowerUpLocations::PowerUp4XYZ, (0.0, None));
 
         Self {
             active_power_ups,
@@ -53,12 +124,56 @@ impl GameState {
     }
 }
 
+/// Whether a player stands on something, updated every tick from its contacts
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct GroundState {
+    pub on_ground: bool,
+    pub air_time: f32, // since the player left the ground, 0 while on it
+    pub jumped: bool,  // is leaving the ground by jumping rather than walking off
+    pub buffered_jump: Option<f32>, // time left for a jump pressed in the air to happen on landing
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroundTransition {
+    Landed,
+    TookOff,
+}
+
+impl GroundState {
+    /// Moves on by a tick lasting dt, returns whether the player landed or left the ground
+    pub fn update(&mut self, on_ground: bool, dt: f32) -> Option<GroundTransition> {
+        let was_on_ground = self.on_ground;
+        self.on_ground = on_ground;
+        self.buffered_jump = self
+            .buffered_jump
+            .map(|time_left| time_left - dt)
+            .filter(|time_left| *time_left > 0.0);
+
+        match (was_on_ground, on_ground) {
+            (false, true) => {
+                self.air_time = 0.0;
+                Some(GroundTransition::Landed)
+            }
+            (true, false) => {
+                self.air_time = dt;
+                Some(GroundTransition::TookOff)
+            }
+            (false, false) => {
+                self.air_time += dt;
+                None
+            }
+            (true, true) => None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct PlayerState {
     pub id: u32,
     pub transform: Transform,
     pub physics: Physics,
     pub jump_count: u32,
+    pub ground: GroundState,
     pub camera_forward: Vec3,
     pub is_dead: bool,
     pub on_cooldown: HashMap<Command, f32>,
@@ -66,11 +181,14 @@ pub struct PlayerState {
     pub on_flag_time: f32,
     pub spawn_point: Vector<f32>,
     pub power_up: Option<(PowerUp, PowerUpStatus)>,
-    pub status_effects: HashMap<StatusEffect, f32 /* time till status effect expire */>,
+    pub status_effects: HashMap<StatusEffect, ActiveStatusEffect>,
     pub active_action_states: HashSet<(ActionState, Duration)>,
+    pub charge_time: Option<f32>, // seconds the attack has been charged, None if not charging
     pub cheat_keys_enabled: bool,
     pub last_step: u64,
     pub respawn_sec: u32, // b/c seconds are unreliable
+    pub last_hit: Option<LastHit>,
+    pub stats: PlayerStats,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, Copy)]
@@ -91,6 +209,29 @@ impl GameLifeCycleState {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchPhase {
+    #[default]
+    Regular,
+    Overtime,    // time is up but the flag is contested
+    SuddenDeath, // time is up and the leaders are tied, no more respawns
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct MatchClock {
+    pub time_left: Option<f32>, // None if the match has no time limit
+    pub phase: MatchPhase,
+}
+
+impl MatchClock {
+    pub fn new(match_config: &ConfigMatch) -> Self {
+        Self {
+            time_left: match_config.time_limit,
+            phase: MatchPhase::Regular,
+        }
+    }
+}
+
 // Notes to be removed:
 //      1. Update who should get the powerup
 //          - need to update the powerup counter, and the player's state
@@ -106,6 +247,7 @@ impl PlayerState {
         let consume_amount = consume_amount.unwrap_or(1);
         if self.wind_charge >= consume_amount {
             self.wind_charge -= consume_amount;
+            self.stats.wind_spent += consume_amount;
             true
         } else {
             false
@@ -124,19 +266,6 @@ impl PlayerState {
         };
     }
 
-    pub fn is_in_refill_area(&self, game_config: ConfigGame) -> Option<Vec3> {
-        let radius = game_config.refill_radius;
-        let mut closest_point = None;
-
-        for i in &game_config.refill_points {
-            if self.is_in_circular_area((i.x, i.z), radius, (None, None)) {
-                closest_point = Some(*i);
-            }
-        }
-
-        closest_point
-    }
-
     pub fn is_need_refill(&self, game_config: ConfigGame) -> bool {
         if self.wind_charge == game_config.max_wind_charge {
             return false;
@@ -154,40 +283,6 @@ impl PlayerState {
         self.on_cooldown.contains_key(&command)
     }
 
-    pub fn is_in_circular_area(
-        &self,
-        horizontal_center: (f32, f32),
-        radius: f32,
-        vertical_bounds: (Option<f32>, Option<f32>),
-    ) -> bool {
-        let (p_x, p_y, p_z) = (
-            self.transform.translation.x,
-            self.transform.translation.y,
-            self.transform.translation.z,
-        );
-        let (c_x, c_z) = horizontal_center;
-
-        match vertical_bounds {
-            (Some(y1), Some(y2)) => {
-                if p_y > y2 || p_y < y1 {
-                    return false;
-                }
-            }
-            (Some(y1), None) => {
-                if p_y < y1 {
-                    return false;
-                }
-            }
-            (None, Some(y2)) => {
-                if p_y > y2 {
-                    return false;
-                }
-            }
-            (None, None) => {}
-        }
-        (p_x - c_x).powi(2) + (p_z - c_z).powi(2) < radius.powi(2)
-    }
-
     pub fn reset_status_effects(&mut self) {
         self.status_effects.clear();
     }
@@ -208,12 +303,85 @@ impl PlayerState {
         self.active_action_states = updated_action_states;
     }
 
+    pub fn start_charging(&mut self) {
+        self.charge_time = Some(0.0);
+        // lasts until the charge is released or dropped
+        self.add_action_state(ActionState::Charging, Duration::MAX);
+    }
+
+    // returns how long the attack was charged, if it was
+    pub fn stop_charging(&mut self) -> Option<f32> {
+        self.active_action_states
+            .retain(|(action_state, _)| *action_state != ActionState::Charging);
+        self.charge_time.take()
+    }
+
+    // remember who hit this player last, for ring-out attribution
+    pub fn record_hit_by(&mut self, attacker_id: u32, window: f32) {
+        if attacker_id == self.id {
+            return;
+        }
+        self.last_hit = Some(LastHit {
+            attacker_id,
+            time_left: window,
+        });
+    }
+
+    // returns the player to be credited for this player's ring-out, if any
+    pub fn take_ring_out_credit(&mut self) -> Option<u32> {
+        self.last_hit.take().map(|hit| hit.attacker_id)
+    }
+
     pub fn holds_status_effect(&self, effect: StatusEffect) -> bool {
         self.status_effects.contains_key(&effect)
     }
     pub fn holds_status_effect_mut(&mut self, effect: StatusEffect) -> bool {
         self.status_effects.contains_key(&effect)
     }
+
+    /// Applies a status effect following the stacking rule of its definition
+    pub fn apply_status_effect(
+        &mut self,
+        effect: StatusEffect,
+        duration: f32,
+        magnitude: f32,
+        source: Option<u32>,
+        game_config: &ConfigGame,
+    ) {
+        let applied = ActiveStatusEffect::new(duration, magnitude, source);
+        match self.status_effects.get_mut(&effect) {
+            Some(active) => {
+                let definition = game_config.status_effect(effect);
+                active.reapply(
+                    applied,
+                    definition.map_or(Default::default(), |d| d.stacking),
+                    definition.map_or(1, |d| d.max_stacks),
+                );
+            }
+            None => {
+                self.status_effects.insert(effect, applied);
+            }
+        }
+    }
+
+    /// A stat after the modifiers of the status effects the player is under
+    pub fn stat(&self, stat: Stat, base: f32, game_config: &ConfigGame) -> f32 {
+        effective_stat(
+            &self.status_effects,
+            stat,
+            base,
+            &game_config.status_effects,
+        )
+    }
+
+    /// An active power-up lasts as long as the status effects it granted
+    pub fn clear_expired_power_up(&mut self) {
+        if let Some((_, PowerUpStatus::Active)) = self.power_up {
+            if !self.status_effects.keys().any(|e| matches!(e, Power(_))) {
+                self.power_up = None;
+            }
+        }
+    }
 }
 
 impl GameState {
@@ -237,36 +405,174 @@ impl GameState {
         }
     }
 
+    pub fn update_last_hits(&mut self, delta_time: f32) {
+        for (_, player_state) in self.players.iter_mut() {
+            if let Some(hit) = player_state.last_hit.as_mut() {
+                hit.time_left -= delta_time;
+                if hit.time_left <= 0.0 {
+                    player_state.last_hit = None;
+                }
+            }
+        }
+    }
+
+    // charges grow up to the max charge time, or as far as the player's wind charge can pay for
+    pub fn update_charges(&mut self, delta_time: f32, attack_config: &ConfigAttack) {
+        for (_, player_state) in self.players.iter_mut() {
+            let charge_time = match player_state.charge_time {
+                Some(charge_time) => charge_time,
+                None => continue,
+            };
+            // dying or getting stunned drops the charge
+            if player_state.is_dead
+                || player_state.holds_status_effect(StatusEffect::Other(OtherEffects::Stun))
+            {
+                player_state.stop_charging();
+                continue;
+            }
+
+            let new_charge_time = (charge_time + delta_time).min(attack_config.max_charge_time);
+            let cost =
+                attack_config.charged_attack_cost(attack_config.charge_level(new_charge_time));
+            if cost <= player_state.wind_charge {
+                player_state.charge_time = Some(new_charge_time);
+            }
+        }
+    }
+
     pub fn update_action_states(&mut self, delta_time: Duration) {
         for (_, player_state) in self.players.iter_mut() {
             player_state.sweep_action_states(delta_time);
         }
     }
 
-    pub fn has_single_winner(&self, game_config: ConfigGame) -> Option<u32> {
-        let valid_players: HashMap<u32, bool> = self
-            .clone()
-            .players
-            .into_iter()
-            .map(|(id, player_state)| {
-                (
-                    id,
-                    player_state.is_in_circular_area(
-                        game_config.flag_xz,
-                        game_config.flag_radius,
-                        game_config.flag_z_bound,
-                    ),
-                )
-            })
-            .filter(|(_, res)| *res)
-            .collect();
+    pub fn has_single_winner(&self) -> Option<u32> {
+        let valid_players = self.players_on_flag();
         if valid_players.len() != 1 {
             None
         } else {
-            Some(*valid_players.keys().last().unwrap())
+            Some(valid_players[0])
+        }
+    }
+
+    // ids of all players standing in the flag area, in ascending order
+    pub fn players_on_flag(&self) -> Vec<u32> {
+        self.players_in_zones(ZoneKind::Flag)
+    }
+
+    // ids of the living players inside any zone of this kind, in ascending order
+    pub fn players_in_zones(&self, kind: ZoneKind) -> Vec<u32> {
+        let occupants: BTreeSet<u32> = self
+            .world
+            .zones
+            .values()
+            .filter(|zone| zone.kind == kind)
+            .flat_map(|zone| zone.occupants.iter().copied())
+            .collect();
+        occupants
+            .into_iter()
+            .filter(|id| self.player(*id).is_some_and(|player| !player.is_dead))
+            .collect()
+    }
+
+    /// Where the refill zone the player stands in is, if any
+    pub fn refill_point(&self, player_id: u32) -> Option<Vec3> {
+        self.world
+            .zones
+            .values()
+            .find(|zone| zone.kind == ZoneKind::Refill && zone.occupants.contains(&player_id))
+            .map(|zone| zone.position)
+    }
+
+    /// Does nothing unless the entities are a zone and a player
+    pub fn enter_zone(&mut self, zone_entity: Entity, player_entity: Entity) {
+        let Some(player_id) = player_entity.player_id() else {
+            return;
+        };
+        if !self.players.contains_key(&player_id) {
+            return;
+        }
+        if let Some(zone) = self.world.zones.get_mut(&zone_entity) {
+            zone.occupants.insert(player_id);
+        }
+    }
+
+    pub fn leave_zone(&mut self, zone_entity: Entity, player_entity: Entity) {
+        let Some(player_id) = player_entity.player_id() else {
+            return;
+        };
+        if let Some(zone) = self.world.zones.get_mut(&zone_entity) {
+            zone.occupants.remove(&player_id);
         }
     }
 
+    // ids of the players with the most on_flag_time, in ascending order
+    pub fn flag_leaders(&self) -> Vec<u32> {
+        let best = self
+            .players
+            .values()
+            .map(|p| p.on_flag_time)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let mut leaders: Vec<u32> = self
+            .players
+            .values()
+            .filter(|p| p.on_flag_time == best)
+            .map(|p| p.id)
+            .collect();
+        leaders.sort();
+        leaders
+    }
+
+    /// Runs the match clock, returns the winner once time is up and the match can be decided:
+    /// overtime lasts as long as the flag is contested, after which the leader wins.
+    /// Tied leaders go to sudden death if enabled, where the last player standing also wins.
+    pub fn update_match_clock(
+        &mut self,
+        delta_time: f32,
+        flag_contested: bool,
+        match_config: &ConfigMatch,
+    ) -> Option<u32> {
+        let time_left = (self.match_clock.time_left? - delta_time).max(0.0);
+        self.match_clock.time_left = Some(time_left);
+        if time_left > 0.0 {
+            return None;
+        }
+
+        if flag_contested {
+            if self.match_clock.phase == MatchPhase::Regular {
+                self.match_clock.phase = MatchPhase::Overtime;
+            }
+            return None;
+        }
+
+        let leaders = self.flag_leaders();
+        if leaders.len() == 1 {
+            return Some(leaders[0]);
+        }
+        if !match_config.sudden_death {
+            self.match_clock.phase = MatchPhase::Overtime;
+            return None;
+        }
+
+        self.match_clock.phase = MatchPhase::SuddenDeath;
+        let mut alive: Vec<u32> = self
+            .players
+            .values()
+            .filter(|p| !p.is_dead)
+            .map(|p| p.id)
+            .collect();
+        alive.sort();
+        match alive.len() {
+            0 => leaders.first().copied(),
+            1 => Some(alive[0]),
+            _ => None,
+        }
+    }
+
+    pub fn respawns_enabled(&self) -> bool {
+        self.match_clock.phase != MatchPhase::SuddenDeath
+    }
+
     // returns winner if winner is decided
     pub fn update_player_on_flag_times(
         &mut self,
@@ -302,6 +608,7 @@ impl GameState {
         match self.previous_tick_winner {
             None => None,
             Some(id) => {
+                self.player_mut(id).unwrap().stats.flag_time += delta_time;
                 if still_decay {
                     self.player_mut(id).unwrap().on_flag_time +=
                         delta_time * (1.0 + new_decay_rate);
@@ -320,39 +627,34 @@ impl GameState {
     // Process the status_effect map, remove all ones reached time, remove powerups accordingly
     pub fn update_player_status_effect(&mut self, delta_time: f32) {
         for (_, player_state) in self.players.iter_mut() {
-            let (new_status_effects, to_process) = player_state
-                .status_effects
-                .clone()
-                .into_iter()
-                .map(|(key, time_remaining)| (key, time_remaining - delta_time))
-                .partition(|(_, time_remaining)| *time_remaining > 0.0);
-            player_state.status_effects = new_status_effects;
-            for (effect, _) in to_process {
-                if let Power(_) = effect {
-                    if let Some((current_powerup, powerup_status)) = player_state.power_up.clone() {
-                        if (*POWER_UP_TO_EFFECT_MAP
-                            .get(&current_powerup.value())
-                            .unwrap_or(&StatusEffect::None)
-                            == effect)
-                            && powerup_status == PowerUpStatus::Active
-                        {
-                            player_state.power_up = None;
-                        }
-                    }
-                }
+            for active in player_state.status_effects.values_mut() {
+                active.time_left -= delta_time;
             }
+            player_state
+                .status_effects
+                .retain(|_, active| active.time_left > 0.0);
+            player_state.clear_expired_power_up();
         }
     }
 
-    pub fn update_powerup_respawn(&mut self, delta_time: f32) {
-        for (_, (vacancy_time, powerup)) in self.active_power_ups.iter_mut() {
+    pub fn update_powerup_respawn<R: Rng + ?Sized>(
+        &mut self,
+        delta_time: f32,
+        powerup_config: &ConfigPowerUp,
+        rng: &mut R,
+    ) {
+        // in a set order, for the draws to be the same when replaying a snapshot
+        let mut locations = self.active_power_ups.keys().cloned().collect::<Vec<_>>();
+        locations.sort_by_key(|loc_id| loc_id.value());
+        for loc_id in locations {
+            let (vacancy_time, powerup) = self.active_power_ups.get_mut(&loc_id).unwrap();
             if powerup.clone().is_none() {
                 // case where the powerup is empty, we need to refill the powerup for the map
                 *vacancy_time -= delta_time;
                 if *vacancy_time <= 0.0 {
                     // refill
                     *vacancy_time = 0.0;
-                    *powerup = Some(rand::random());
+                    *powerup = powerup_config.random_power_up(rng);
                 }
             }
         }
@@ -361,35 +663,25 @@ impl GameState {
     // check if any players should get a powerup and record powerup
     pub fn check_powerup_pickup(&mut self, game_config: ConfigGame) -> HashSet<u32> {
         let mut res = HashSet::new();
-        let powerup_radius = game_config.powerup_config.power_up_radius;
         let powerup_respawn_cd = game_config.powerup_config.power_up_respawn_cooldown;
-        for (loc_id, (vacancy_time, powerup)) in self.active_power_ups.iter_mut() {
-            if !powerup.clone().is_none() {
-                // check if a player should get the powerup now
-                for (player_id, player_state) in self.players.iter_mut() {
-                    let power_up_location = *game_config
-                        .powerup_config
-                        .power_up_locations
-                        .get(&loc_id.value())
-                        .unwrap();
-                    if player_state.power_up.is_none()
-                        && player_state.is_in_circular_area(
-                            (power_up_location.0, power_up_location.2),
-                            powerup_radius,
-                            (
-                                Some(power_up_location.1 - powerup_radius),
-                                Some(power_up_location.1 + powerup_radius),
-                            ),
-                        )
-                    {
-                        // player should get it, powerup is gone
-                        player_state.power_up =
-                            Some((powerup.clone().unwrap(), PowerUpStatus::Held));
-                        res.insert(*player_id);
-                        *vacancy_time = powerup_respawn_cd;
-                        *powerup = None;
-                    }
-                }
+        let mut locations = self.active_power_ups.keys().cloned().collect::<Vec<_>>();
+        locations.sort_by_key(|loc_id| loc_id.value());
+        for loc_id in locations {
+            // the first player in the zone without a powerup gets it
+            let Some(player_id) = self
+                .players_in_zones(ZoneKind::PowerUp(loc_id.value()))
+                .into_iter()
+                .find(|id| self.players[id].power_up.is_none())
+            else {
+                continue;
+            };
+            let (vacancy_time, powerup) = self.active_power_ups.get_mut(&loc_id).unwrap();
+            if let Some(powerup) = powerup.take() {
+                // player should get it, powerup is gone
+                self.players.get_mut(&player_id).unwrap().power_up =
+                    Some((powerup, PowerUpStatus::Held));
+                res.insert(player_id);
+                *vacancy_time = powerup_respawn_cd;
             }
         }
         return res;
@@ -425,6 +717,16 @@ impl GameState {
         min.map(|(id, _)| id)
     }
 
+    // effects a player applied go away with them
+    pub fn remove_status_effects_from(&mut self, source: u32) {
+        for player_state in self.players.values_mut() {
+            player_state
+                .status_effects
+                .retain(|_, active| active.source != Some(source));
+            player_state.clear_expired_power_up();
+        }
+    }
+
     pub fn get_affected_players(&self, effect: StatusEffect) -> HashSet<u32> {
         let mut res = HashSet::new();
         for (id, player_state) in self.players.iter() {
@@ -435,6 +737,20 @@ impl GameState {
         res
     }
 
+    pub fn match_summary(&self) -> MatchSummary {
+        let elapsed_time =
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap() - self.game_start_time;
+        MatchSummary {
+            winner: self.game_winner,
+            duration_secs: elapsed_time.as_secs(),
+            players: self
+                .players
+                .iter()
+                .map(|(id, player_state)| (*id, player_state.stats.clone()))
+                .collect(),
+        }
+    }
+
     pub fn get_existing_powerups(&self) -> HashSet<u32> {
         let mut res = HashSet::new();
         for (id, (_, powerup)) in self.active_power_ups.iter() {
@@ -475,6 +791,8 @@ mod tests {
             game_winner: None,
             game_start_time: Default::default(),
             prev_winner: None,
+            match_summary: None,
+            match_clock: Default::default(),
         };
         assert_eq!(state.players.len(), 0);
     }
@@ -492,9 +810,212 @@ mod tests {
             game_winner: None,
             game_start_time: Default::default(),
             prev_winner: None,
+            match_summary: None,
+            match_clock: Default::default(),
         };
         let serialized = bincode::serialize(&state).unwrap();
         let deserialized: GameState = bincode::deserialize(&serialized[..]).unwrap();
         assert_eq!(state.players.len(), deserialized.players.len());
     }
+
+    #[test]
+    fn test_ring_out_attribution() {
+        use super::*;
+        let mut state = GameState::default();
+        for id in 1..=2 {
+            state.players.insert(
+                id,
+                PlayerState {
+                    id,
+                    ..Default::default()
+                },
+            );
+        }
+
+        // hitting yourself does not count
+        state.player_mut(1).unwrap().record_hit_by(1, 5.0);
+        assert_eq!(state.player(1).unwrap().last_hit, None);
+
+        state.player_mut(1).unwrap().record_hit_by(2, 5.0);
+        state.update_last_hits(4.0);
+        assert_eq!(state.player_mut(1).unwrap().take_ring_out_credit(), Some(2));
+        assert_eq!(state.player_mut(1).unwrap().take_ring_out_credit(), None);
+
+        // hit expires after the window
+        state.player_mut(1).unwrap().record_hit_by(2, 5.0);
+        state.update_last_hits(5.0);
+        assert_eq!(state.player_mut(1).unwrap().take_ring_out_credit(), None);
+    }
+
+    #[test]
+    fn test_match_clock() {
+        use super::*;
+        let match_config = ConfigMatch {
+            time_limit: Some(10.0),
+            sudden_death: true,
+        };
+        let mut state = GameState {
+            match_clock: MatchClock::new(&match_config),
+            ..Default::default()
+        };
+        for id in 1..=3 {
+            state.players.insert(
+                id,
+                PlayerState {
+                    id,
+                    ..Default::default()
+                },
+            );
+        }
+        state.player_mut(1).unwrap().on_flag_time = 4.0;
+        state.player_mut(2).unwrap().on_flag_time = 4.0;
+
+        assert_eq!(state.update_match_clock(5.0, false, &match_config), None);
+        assert_eq!(state.match_clock.time_left, Some(5.0));
+
+        // contested flag when time is up
+        assert_eq!(state.update_match_clock(6.0, true, &match_config), None);
+        assert_eq!(state.match_clock.phase, MatchPhase::Overtime);
+
+        // tied leaders
+        assert_eq!(state.update_match_clock(0.1, false, &match_config), None);
+        assert_eq!(state.match_clock.phase, MatchPhase::SuddenDeath);
+        assert!(!state.respawns_enabled());
+
+        // last one standing
+        state.player_mut(2).unwrap().is_dead = true;
+        state.player_mut(3).unwrap().is_dead = true;
+        assert_eq!(state.update_match_clock(0.1, false, &match_config), Some(1));
+
+        // leader by flag time
+        state.player_mut(2).unwrap().on_flag_time = 5.0;
+        assert_eq!(state.update_match_clock(0.1, false, &match_config), Some(2));
+    }
+
+    #[test]
+    fn test_active_power_up_lasts_with_its_status() {
+        use super::*;
+        use crate::core::powerup_system::PowerUpEffects;
+        let mut state = GameState::default();
+        let mut player = PlayerState {
+            id: 1,
+            power_up: Some((PowerUp("wind".to_owned()), PowerUpStatus::Active)),
+            ..Default::default()
+        };
+        player.status_effects.insert(
+            StatusEffect::Power(PowerUpEffects::EnhancedWind),
+            ActiveStatusEffect::new(2.0, 1.5, None),
+        );
+        state.players.insert(1, player);
+
+        state.update_player_status_effect(1.0);
+        assert!(state.player(1).unwrap().power_up.is_some());
+        state.update_player_status_effect(1.0);
+        assert!(state.player(1).unwrap().status_effects.is_empty());
+        assert!(state.player(1).unwrap().power_up.is_none());
+    }
+
+    #[test]
+    fn test_charge_grows_while_affordable() {
+        use super::*;
+        let attack_config = ConfigAttack {
+            attack_cost: 1,
+            max_charge_time: 2.0,
+            max_charge_impulse_scale: 2.0,
+            max_charge_range_scale: 1.5,
+            max_charge_cost_scale: 3.0,
+            ..Default::default()
+        };
+        let mut state = GameState::default();
+        state.players.insert(
+            1,
+            PlayerState {
+                id: 1,
+                wind_charge: 2,
+                ..Default::default()
+            },
+        );
+        state.player_mut(1).unwrap().start_charging();
+
+        state.update_charges(0.5, &attack_config);
+        assert_eq!(state.player(1).unwrap().charge_time, Some(0.5));
+        // charging past 1.25s would cost 3, more than the player has
+        for _ in 0..6 {
+            state.update_charges(0.25, &attack_config);
+        }
+        assert_eq!(state.player(1).unwrap().charge_time, Some(1.25));
+        assert_eq!(attack_config.charged_attack_cost(0.75), 3);
+        assert_eq!(attack_config.charge_scales(0.5), (1.5, 1.25));
+
+        // a stun drops the charge
+        state.player_mut(1).unwrap().status_effects.insert(
+            StatusEffect::Other(OtherEffects::Stun),
+            ActiveStatusEffect::new(1.0, 1.0, None),
+        );
+        state.update_charges(0.1, &attack_config);
+        let player = state.player(1).unwrap();
+        assert_eq!(player.charge_time, None);
+        assert!(player.active_action_states.is_empty());
+    }
+
+    #[test]
+    fn test_zone_membership() {
+        use super::*;
+        let mut state = GameState::default();
+        for id in 1..=3 {
+            state.players.insert(
+                id,
+                PlayerState {
+                    id,
+                    ..Default::default()
+                },
+            );
+        }
+        let (flag, refill) = (Entity::from_raw(300), Entity::from_raw(301));
+        state
+            .world
+            .zones
+            .insert(flag, ZoneState::new(ZoneKind::Flag, Vec3::zeros()));
+        let refill_point = glm::vec3(16.5, -6.5, 16.5);
+        state
+            .world
+            .zones
+            .insert(refill, ZoneState::new(ZoneKind::Refill, refill_point));
+
+        state.enter_zone(flag, Entity::player(2));
+        assert_eq!(state.has_single_winner(), Some(2));
+        state.enter_zone(flag, Entity::player(1));
+        assert_eq!(state.players_on_flag(), vec![1, 2]);
+        assert_eq!(state.has_single_winner(), None);
+
+        // only zones and players count, the dead are out
+        state.enter_zone(Entity::from_raw(302), Entity::player(3));
+        state.enter_zone(flag, Entity::player(4));
+        state.enter_zone(flag, refill);
+        state.player_mut(1).unwrap().is_dead = true;
+        assert_eq!(state.players_on_flag(), vec![2]);
+
+        state.enter_zone(refill, Entity::player(3));
+        assert_eq!(state.refill_point(3), Some(refill_point));
+        state.leave_zone(refill, Entity::player(3));
+        assert_eq!(state.refill_point(3), None);
+    }
+
+    #[test]
+    fn test_ground_transitions() {
+        use super::*;
+        let mut ground = GroundState::default();
+        assert_eq!(ground.update(true, 0.1), Some(GroundTransition::Landed));
+        assert_eq!(ground.update(true, 0.1), None);
+
+        ground.buffered_jump = Some(0.15);
+        assert_eq!(ground.update(false, 0.1), Some(GroundTransition::TookOff));
+        assert_eq!(ground.update(false, 0.1), None);
+        assert!((ground.air_time - 0.2).abs() < 1e-6);
+        // the buffered jump ran out before landing
+        assert_eq!(ground.buffered_jump, None);
+
+        assert_eq!(ground.update(true, 0.1), Some(GroundTransition::Landed));
+        assert_eq!(ground.air_time, 0.0);
+    }
 }
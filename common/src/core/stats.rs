@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Per-match statistics of a single player
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PlayerStats {
+    pub ring_outs: u32,
+    pub deaths: u32,
+    pub attacks_landed: u32,
+    pub wind_spent: u32,
+    pub power_ups_used: u32,
+    pub flag_time: f32,
+}
+
+/// The last player that hit a player, kept until the attribution window runs out
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LastHit {
+    pub attacker_id: u32,
+    pub time_left: f32, // time till the hit no longer counts for a ring-out
+}
+
+/// Summary of a finished match, sent to clients with the Ended state and written to disk
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MatchSummary {
+    pub winner: Option<u32>,
+    pub duration_secs: u64,
+    pub players: HashMap<u32, PlayerStats>,
+}
+
+impl MatchSummary {
+    /// player ids ordered by ring-outs, then by flag time
+    pub fn ranked_players(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.players.keys().copied().collect();
+        ids.sort_by(|a, b| {
+            let (sa, sb) = (&self.players[a], &self.players[b]);
+            sb.ring_outs
+                .cmp(&sa.ring_outs)
+                .then(sb.flag_time.total_cmp(&sa.flag_time))
+                .then(a.cmp(b))
+        });
+        ids
+    }
+}
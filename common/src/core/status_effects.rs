@@ -0,0 +1,234 @@
+use crate::configs::game_config::ConfigStatusEffect;
+use crate::core::powerup_system::StatusEffect;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A status effect a player is under
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ActiveStatusEffect {
+    pub time_left: f32,
+    pub duration: f32, // of the latest application
+    pub stacks: u32,
+    pub magnitude: f32,
+    pub source: Option<u32>, // player that applied it, None for the environment
+}
+
+/// Player stats that status effects can modify
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stat {
+    MoveSpeed,
+    Friction,
+    LinearDamping,
+    KnockbackTaken,
+    JumpCount,
+    AttackRange,
+    AttackStrength,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierOp {
+    Add,
+    Multiply,
+    Set, // the lowest set value wins
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub op: ModifierOp,
+    pub value: Option<f32>, // the magnitude of the effect if None
+}
+
+/// What happens when an effect is applied to a player already under it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StackingRule {
+    #[default]
+    Refresh, // the new application replaces the old one
+    Stack, // adds a stack up to max_stacks and restarts the timer
+    Max,   // keeps the longest time left and the strongest magnitude
+}
+
+impl ActiveStatusEffect {
+    pub fn new(duration: f32, magnitude: f32, source: Option<u32>) -> Self {
+        Self {
+            time_left: duration,
+            duration,
+            stacks: 1,
+            magnitude,
+            source,
+        }
+    }
+
+    pub fn reapply(
+        &mut self,
+        applied: ActiveStatusEffect,
+        stacking: StackingRule,
+        max_stacks: u32,
+    ) {
+        match stacking {
+            StackingRule::Refresh => *self = applied,
+            StackingRule::Stack => {
+                *self = ActiveStatusEffect {
+                    stacks: (self.stacks + 1).min(max_stacks.max(1)),
+                    ..applied
+                }
+            }
+            StackingRule::Max => {
+                if applied.time_left > self.time_left {
+                    self.time_left = applied.time_left;
+                    self.duration = applied.duration;
+                    self.source = applied.source;
+                }
+                self.magnitude = self.magnitude.max(applied.magnitude);
+            }
+        }
+    }
+}
+
+/// Value of a stat after the modifiers of all status effects, (base + adds) * multipliers
+/// unless an effect sets it. Modifiers never take a stat below zero, or below a negative base
+pub fn effective_stat(
+    status_effects: &HashMap<StatusEffect, ActiveStatusEffect>,
+    stat: Stat,
+    base: f32,
+    definitions: &[ConfigStatusEffect],
+) -> f32 {
+    let mut add = 0.0;
+    let mut multiplier = 1.0;
+    let mut set: Option<f32> = None;
+
+    for (effect, active) in status_effects.iter() {
+        let definition = match definitions.iter().find(|d| d.effect == *effect) {
+            Some(definition) => definition,
+            None => continue,
+        };
+        for modifier in definition.modifiers.iter().filter(|m| m.stat == stat) {
+            let value = modifier.value.unwrap_or(active.magnitude);
+            match modifier.op {
+                ModifierOp::Add => add += value * active.stacks as f32,
+                ModifierOp::Multiply => multiplier *= value.powi(active.stacks as i32),
+                ModifierOp::Set => set = Some(set.map_or(value, |s: f32| s.min(value))),
+            }
+        }
+    }
+
+    set.unwrap_or((base + add) * multiplier).max(base.min(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::powerup_system::{OtherEffects, PowerUpEffects};
+
+    fn definitions() -> Vec<ConfigStatusEffect> {
+        vec![
+            ConfigStatusEffect {
+                effect: StatusEffect::Other(OtherEffects::Slippery),
+                stacking: StackingRule::Stack,
+                max_stacks: 2,
+                modifiers: vec![StatModifier {
+                    stat: Stat::Friction,
+                    op: ModifierOp::Add,
+                    value: Some(-0.4),
+                }],
+            },
+            ConfigStatusEffect {
+                effect: StatusEffect::Power(PowerUpEffects::EnhancedWind),
+                stacking: StackingRule::Refresh,
+                max_stacks: 1,
+                modifiers: vec![StatModifier {
+                    stat: Stat::AttackRange,
+                    op: ModifierOp::Multiply,
+                    value: None,
+                }],
+            },
+            ConfigStatusEffect {
+                effect: StatusEffect::Other(OtherEffects::Stun),
+                stacking: StackingRule::Max,
+                max_stacks: 1,
+                modifiers: vec![StatModifier {
+                    stat: Stat::MoveSpeed,
+                    op: ModifierOp::Set,
+                    value: Some(0.0),
+                }],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_stacking_rules() {
+        let mut stack = ActiveStatusEffect::new(2.0, 1.0, None);
+        for _ in 0..3 {
+            stack.reapply(
+                ActiveStatusEffect::new(1.0, 1.0, Some(2)),
+                StackingRule::Stack,
+                2,
+            );
+        }
+        assert_eq!(
+            (stack.stacks, stack.time_left, stack.source),
+            (2, 1.0, Some(2))
+        );
+
+        let mut max = ActiveStatusEffect::new(2.0, 1.0, Some(1));
+        max.reapply(
+            ActiveStatusEffect::new(1.0, 3.0, Some(2)),
+            StackingRule::Max,
+            1,
+        );
+        assert_eq!(
+            (max.time_left, max.magnitude, max.source),
+            (2.0, 3.0, Some(1))
+        );
+
+        let mut refresh = ActiveStatusEffect::new(2.0, 1.0, Some(1));
+        refresh.reapply(
+            ActiveStatusEffect::new(1.0, 3.0, Some(2)),
+            StackingRule::Refresh,
+            1,
+        );
+        assert_eq!(refresh, ActiveStatusEffect::new(1.0, 3.0, Some(2)));
+    }
+
+    #[test]
+    fn test_effective_stats() {
+        let definitions = definitions();
+        let mut effects = HashMap::new();
+        assert_eq!(
+            effective_stat(&effects, Stat::Friction, 1.0, &definitions),
+            1.0
+        );
+
+        let mut slippery = ActiveStatusEffect::new(1.0, 1.0, None);
+        slippery.stacks = 2;
+        effects.insert(StatusEffect::Other(OtherEffects::Slippery), slippery);
+        assert!((effective_stat(&effects, Stat::Friction, 1.0, &definitions) - 0.2).abs() < 1e-5);
+        // floored at zero, or at a negative base
+        assert_eq!(
+            effective_stat(&effects, Stat::Friction, 0.5, &definitions),
+            0.0
+        );
+        assert_eq!(
+            effective_stat(&effects, Stat::Friction, -0.2, &definitions),
+            -0.2
+        );
+
+        effects.insert(
+            StatusEffect::Power(PowerUpEffects::EnhancedWind),
+            ActiveStatusEffect::new(1.0, 1.5, None),
+        );
+        assert_eq!(
+            effective_stat(&effects, Stat::AttackRange, 2.0, &definitions),
+            3.0
+        );
+
+        effects.insert(
+            StatusEffect::Other(OtherEffects::Stun),
+            ActiveStatusEffect::new(1.0, 1.0, None),
+        );
+        assert_eq!(
+            effective_stat(&effects, Stat::MoveSpeed, 1.0, &definitions),
+            0.0
+        );
+    }
+}
@@ -4,5 +4,37 @@ use serde::{Deserialize, Serialize};
 
 pub enum Weather {
     Rainy,
-    Windy(Vector),
+    Windy(Vector), // blows along the vector, its length is the force
+    Snowy,
+    Foggy,
+    Stormy,
+}
+
+/// A weather without its parameters, as it is named in the config
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeatherKind {
+    Rainy,
+    Windy,
+    Snowy,
+    Foggy,
+    Stormy,
+}
+
+impl Weather {
+    pub fn kind(&self) -> WeatherKind {
+        match self {
+            Weather::Rainy => WeatherKind::Rainy,
+            Weather::Windy(_) => WeatherKind::Windy,
+            Weather::Snowy => WeatherKind::Snowy,
+            Weather::Foggy => WeatherKind::Foggy,
+            Weather::Stormy => WeatherKind::Stormy,
+        }
+    }
+}
+
+/// A lightning strike telegraphed to the players before it hits
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LightningStrike {
+    pub position: Vector,
+    pub time_left: f32,
 }
@@ -1,23 +1,37 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use bincode::{deserialize, serialize};
+use log::warn;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::HashMap;
-use std::fs::{File, OpenOptions};
+use std::fs::{self, File, OpenOptions};
 use std::hash::Hash;
 use std::io::{BufWriter, Read, Write};
 use std::marker::PhantomData;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
+
+// bump when the layout of the cache file changes, older files are discarded
+const CACHE_FORMAT_VERSION: u32 = 1;
+// inserts written to the file at once, see FileCache::flush
+const DEFAULT_FLUSH_EVERY: usize = 8;
 
 pub trait Cache<K, V> {
     fn get(&self, key: &K) -> Option<V>;
     fn insert(&self, key: K, value: V) -> Option<V>;
 }
 
+/// A map saved to a file, read once when created.
+/// Inserts are written in batches, and whatever is left when the cache is flushed or dropped.
 #[derive(Debug)]
-pub struct FileCache<K, V> {
+pub struct FileCache<K, V>
+where
+    K: Serialize + DeserializeOwned + Eq + Hash + Clone,
+    V: Serialize + DeserializeOwned + Clone,
+{
     file_path: String,
     cache: RwLock<HashMap<K, V>>,
+    pending: Mutex<usize>, // inserts not written to the file yet
+    flush_every: usize,
     _marker: PhantomData<(K, V)>,
 }
 
@@ -27,10 +41,19 @@ where
     V: Serialize + DeserializeOwned + Clone,
 {
     pub fn new(file_path: &str) -> Self {
-        let cache = FileCache::load_from_file(file_path).unwrap_or(HashMap::new());
+        Self::with_flush_every(file_path, DEFAULT_FLUSH_EVERY)
+    }
+
+    pub fn with_flush_every(file_path: &str, flush_every: usize) -> Self {
+        let cache = FileCache::load_from_file(file_path).unwrap_or_else(|e| {
+            warn!("Discarding the cache file {}: {:#}", file_path, e);
+            HashMap::new()
+        });
         FileCache {
             file_path: file_path.to_string(),
             cache: RwLock::new(cache),
+            pending: Mutex::new(0),
+            flush_every: flush_every.max(1),
             _marker: PhantomData,
         }
     }
@@ -43,20 +66,40 @@ where
 
         let mut contents = Vec::new();
         file.read_to_end(&mut contents)?;
-        let map: HashMap<K, V> =
+        let (version, map): (u32, HashMap<K, V>) =
             deserialize(&contents[..]).with_context(|| "Failed to deserialize the cache")?;
+        if version != CACHE_FORMAT_VERSION {
+            bail!(
+                "Cache format {} instead of {}",
+                version,
+                CACHE_FORMAT_VERSION
+            );
+        }
 
         Ok(map)
     }
 
-    fn save_to_file(&self) -> Result<()> {
-        let data = serialize(&self.cache).context("Failed to serialize the cache")?;
-        let file = File::create(&self.file_path).context("Failed to create the cache file")?;
+    /// Writes the pending inserts, to a temporary file renamed over the cache file
+    pub fn flush(&self) -> Result<()> {
+        // held while writing, so that concurrent flushes don't share the temporary file
+        let mut pending = self.pending.lock().unwrap();
+        if *pending == 0 {
+            return Ok(());
+        }
+
+        let data = serialize(&(CACHE_FORMAT_VERSION, &*self.cache.read().unwrap()))
+            .context("Failed to serialize the cache")?;
+        let tmp_path = format!("{}.{}.tmp", self.file_path, std::process::id());
+        let file = File::create(&tmp_path).context("Failed to create the cache file")?;
         let mut writer = BufWriter::new(file);
         writer
             .write_all(&data)
             .context("Failed to write the cache file")?;
         writer.flush().context("Failed to flush the cache file")?;
+        drop(writer);
+        fs::rename(&tmp_path, &self.file_path).context("Failed to replace the cache file")?;
+
+        *pending = 0;
         Ok(())
     }
 }
@@ -74,18 +117,38 @@ where
         let mut cache = self.cache.write().unwrap();
         let result = cache.insert(key, value);
         drop(cache); // Explicitly drop the lock before saving to the file
-        if self.save_to_file().is_err() {
-            eprintln!("Error saving cache to file");
+
+        let mut pending = self.pending.lock().unwrap();
+        *pending += 1;
+        let batch_full = *pending >= self.flush_every;
+        drop(pending);
+        if batch_full {
+            if let Err(e) = self.flush() {
+                warn!("Error saving cache to file: {:#}", e);
+            }
         }
         result
     }
 }
 
+impl<K, V> Drop for FileCache<K, V>
+where
+    K: Serialize + DeserializeOwned + Eq + Hash + Clone,
+    V: Serialize + DeserializeOwned + Clone,
+{
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("Error saving cache to file: {:#}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::utils::file_cache::{Cache, FileCache};
     use serde::{Deserialize, Serialize};
     use std::fs::remove_file;
+    use std::path::Path;
     use std::sync::{Arc, Barrier};
     use std::thread;
 
@@ -110,7 +173,8 @@ mod tests {
         assert!(cached_value.is_some());
         assert_eq!(cached_value.unwrap(), value);
 
-        // Cleanup
+        // Cleanup, once the cache is done writing
+        drop(cache);
         let _ = remove_file(file_path);
     }
 
@@ -180,7 +244,53 @@ mod tests {
             handle.join().unwrap();
         }
 
+        // Cleanup, once the cache is done writing
+        drop(cache);
+        let _ = remove_file(file_path);
+    }
+
+    #[test]
+    fn test_corrupt_file_is_discarded() {
+        let file_path = "test_cache_corrupt.bin";
+        std::fs::write(file_path, b"not a cache").unwrap();
+
+        let cache = FileCache::<Key, Value>::new(file_path);
+        assert!(cache.get(&Key("key".to_string())).is_none());
+        cache.insert(Key("key".to_string()), Value(42));
+        drop(cache);
+
+        // and replaced on the next flush
+        let cache = FileCache::<Key, Value>::new(file_path);
+        assert_eq!(cache.get(&Key("key".to_string())), Some(Value(42)));
+
+        // Cleanup
+        drop(cache);
+        let _ = remove_file(file_path);
+    }
+
+    #[test]
+    fn test_batched_flush() {
+        let file_path = "test_cache_batched.bin";
+        let _ = remove_file(file_path);
+        let cache = FileCache::<Key, Value>::with_flush_every(file_path, 3);
+
+        cache.insert(Key("a".to_string()), Value(1));
+        cache.insert(Key("b".to_string()), Value(2));
+        assert!(!Path::new(file_path).exists());
+        cache.insert(Key("c".to_string()), Value(3));
+        assert!(Path::new(file_path).exists());
+
+        cache.insert(Key("d".to_string()), Value(4));
+        cache.flush().unwrap();
+        let reloaded = FileCache::<Key, Value>::new(file_path);
+        assert_eq!(reloaded.get(&Key("d".to_string())), Some(Value(4)));
+        // nothing is left behind by the atomic writes
+        let tmp_path = format!("{}.{}.tmp", file_path, std::process::id());
+        assert!(!Path::new(&tmp_path).exists());
+
         // Cleanup
+        drop(cache);
+        drop(reloaded);
         let _ = remove_file(file_path);
     }
 }
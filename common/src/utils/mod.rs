@@ -1 +1,2 @@
 pub mod file_cache;
+pub mod stable_hash;
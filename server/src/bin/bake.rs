@@ -0,0 +1,79 @@
+//! Bakes the colliders of scene.json into one file the server loads on startup,
+//! run it from the root of the repository after changing the scene or the models:
+//! cargo run --release --bin bake
+
+use std::time::Instant;
+
+use clap::Parser;
+use common::configs::ConfigurationManager;
+use server::executor::command_handlers::prelude::StartupCommandHandler;
+use server::simulation::baked_level::{BakedLevel, BakedNode, BAKED_LEVEL_PATH};
+use server::simulation::obj_collider::{collider_cache_hits, flush_collider_cache};
+
+#[derive(Parser)]
+#[command(about = "Computes the colliders of the scene ahead of time")]
+struct Args {
+    /// Where to write the baked level
+    #[arg(short, long, default_value = BAKED_LEVEL_PATH)]
+    output: String,
+}
+
+fn main() {
+    env_logger::init();
+    let args = Args::parse();
+
+    let config = ConfigurationManager::get_configuration();
+    let source_hash = BakedLevel::source_hash(&config.models, &config.scene)
+        .unwrap_or_else(|e| panic!("Failed to read the level: {}", e));
+    let handler = StartupCommandHandler::new(
+        config.models.clone(),
+        config.scene.clone(),
+        config.game.clone(),
+        None,
+    );
+
+    let start = Instant::now();
+    let mut nodes = Vec::new();
+    println!(
+        "{:<32} {:>10} {:>10} {:>6} {:>7}",
+        "node", "time (ms)", "triangles", "hulls", "cached"
+    );
+    for (node, position) in handler.scene_nodes() {
+        let node_start = Instant::now();
+        // a cached collider takes no time to build, so its time says nothing about the mesh
+        let cache_hits = collider_cache_hits();
+        let collider = handler
+            .node_collider(&node)
+            .unwrap_or_else(|e| panic!("Failed to bake {}: {}", node.id, e));
+        let baked_node = BakedNode {
+            id: node.id,
+            position,
+            collider,
+        };
+        let cached = collider_cache_hits() > cache_hits;
+        let (triangles, hulls) = baked_node.counts();
+        println!(
+            "{:<32} {:>10.1} {:>10} {:>6} {:>7}",
+            baked_node.id,
+            node_start.elapsed().as_secs_f64() * 1000.0,
+            triangles,
+            hulls,
+            if cached { "yes" } else { "no" }
+        );
+        nodes.push(baked_node);
+    }
+
+    // the next bake and the server reuse what was built
+    flush_collider_cache();
+
+    let node_count = nodes.len();
+    BakedLevel::new(source_hash, nodes)
+        .save(&args.output)
+        .unwrap_or_else(|e| panic!("Failed to write {}: {}", args.output, e));
+    println!(
+        "Baked {} nodes into {} in {:.2}s",
+        node_count,
+        args.output,
+        start.elapsed().as_secs_f64()
+    );
+}
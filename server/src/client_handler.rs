@@ -2,6 +2,8 @@ use crate::{CLIENT_ID_ASSIGNER, SESSION_ID};
 use bus::{Bus, BusReader};
 use common::communication::commons::Protocol;
 use common::communication::message::{HostRole, Message, Payload};
+use common::configs::ConfigurationManager;
+use common::core::command::Command;
 use common::core::states::GameState;
 use log::{debug, error, info, warn};
 use server::game_loop::ClientCommand;
@@ -11,6 +13,10 @@ use std::ops::Deref;
 use std::sync::atomic::Ordering;
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// how often the latency to a client is measured, the client echoes the server's pings
+const PING_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct ClientHandler {
     protocol: Protocol,
@@ -71,11 +77,24 @@ impl ClientHandler {
                         )),
                     ))
                     .expect("send message fails");
+                // the client plays by the rules of the server, not by its own files
+                let gameplay = ConfigurationManager::get_configuration().gameplay();
+                write_protocol
+                    .send_message(&Message::new(
+                        HostRole::Server,
+                        Payload::ConfigGameplay(Box::new(gameplay)),
+                    ))
+                    .expect("send message fails");
             } else {
                 error!("Unexpected message before connection init: {:?}", msg);
             }
         }
 
+        let client_id = self.client_id.unwrap();
+        let tx = self.tx.clone();
+        tx.send(ClientCommand::new(client_id.into(), Command::Join))
+            .unwrap();
+
         let read_handler = thread::spawn(move || {
             let mut read_resources = (read_protocol, self.tx);
             Self::read_messages(&mut read_resources);
@@ -97,6 +116,8 @@ impl ClientHandler {
         //TODO: add code to remove disconnected clients
 
         warn!("Client disconnected");
+        tx.send(ClientCommand::new(client_id.into(), Command::Leave))
+            .unwrap();
     }
 
     fn read_messages(resources: &mut (Protocol, mpsc::Sender<ClientCommand>)) {
@@ -104,19 +125,31 @@ impl ClientHandler {
         while let Ok(msg) = protocol.read_message::<Message>() {
             if let Message {
                 host_role: HostRole::Client(client_id),
+                timestamp,
                 payload,
-                ..
             } = msg
             {
                 match payload {
+                    // joining, leaving and latencies are only issued by the server
+                    Payload::Command(Command::Join | Command::Leave | Command::Latency(_)) => {
+                        warn!("Client {} sent a server-issued command", client_id);
+                    }
                     Payload::Command(command) => {
                         tx.send(ClientCommand::new(client_id.into(), command))
                             .unwrap();
                     }
+                    // an echo of our ping, which carries the time it was sent
                     Payload::Ping => {
-                        protocol
-                            .send_message(&Message::new(HostRole::Server, Payload::Ping))
-                            .unwrap();
+                        let now = SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap()
+                            .as_millis() as u64;
+                        let round_trip = now.saturating_sub(timestamp) as u32;
+                        tx.send(ClientCommand::new(
+                            client_id.into(),
+                            Command::Latency(round_trip),
+                        ))
+                        .unwrap();
                     }
                     _ => {}
                 }
@@ -133,7 +166,17 @@ impl ClientHandler {
         ),
     ) {
         let (client_id, protocol, rx, game_state) = resources;
+        let mut last_ping = Instant::now();
         while let Ok(outgoing_request) = rx.recv() {
+            if last_ping.elapsed() >= PING_INTERVAL {
+                last_ping = Instant::now();
+                if let Err(e) =
+                    protocol.send_message(&Message::new(HostRole::Server, Payload::Ping))
+                {
+                    warn!("Error while pinging client: {:?}", e);
+                }
+            }
+
             if !outgoing_request.recipients().matches(*client_id) {
                 // this message is not for this client
                 continue;
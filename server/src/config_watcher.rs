@@ -0,0 +1,48 @@
+use common::configs::{ConfigTuning, TUNING_CONFIG_PATHS};
+use log::{info, warn};
+use std::fs;
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, sleep};
+use std::time::{Duration, SystemTime};
+
+// how often the files of the tuning are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Watches the files of the tuning, reloads it when they change and hands it to the game loop,
+/// which switches to it between two ticks.
+/// An invalid tuning is left out, the previous one stays until the files are fixed.
+pub fn watch_configs() -> Receiver<ConfigTuning> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut modified = modified_times();
+        loop {
+            sleep(POLL_INTERVAL);
+            let now = modified_times();
+            if now == modified {
+                continue;
+            }
+            modified = now;
+            match ConfigTuning::load() {
+                Ok(tuning) => {
+                    info!("Reloaded the tuning configs");
+                    if tx.send(tuning).is_err() {
+                        return; // the game loop is gone
+                    }
+                }
+                Err(e) => warn!("Keeping the previous tuning configs, {}", e),
+            }
+        }
+    });
+    rx
+}
+
+fn modified_times() -> Vec<Option<SystemTime>> {
+    TUNING_CONFIG_PATHS
+        .iter()
+        .map(|path| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect()
+}
@@ -3,17 +3,20 @@ extern crate nalgebra_glm as glm;
 use common::configs::game_config::ConfigGame;
 use derive_more::Constructor;
 use rapier3d::prelude as rapier;
-use rapier3d::{geometry, pipeline};
 use std::time::Duration;
 
 use common::configs::physics_config::ConfigPhysics;
 use common::core::action_states::ActionState;
 use common::core::command::Command;
 use common::core::events::{GameEvent, ParticleSpec, ParticleType, SoundSpec};
-use common::core::powerup_system::{OtherEffects, PowerUpEffects, StatusEffect};
+use common::core::powerup_system::{OtherEffects, StatusEffect};
+use common::core::status_effects::Stat;
 use common::core::states::GameState;
 
+use crate::executor::command_handlers::weather::weather_attack_range;
+use crate::simulation::entity::Entity;
 use crate::simulation::physics_state::PhysicsState;
+use crate::simulation::targeting::TargetQuery;
 use crate::Recipients;
 
 use super::{CommandHandler, GameEventCollector, HandlerError, HandlerResult};
@@ -23,6 +26,7 @@ pub struct AreaAttackCommandHandler {
     player_id: u32,
     physics_config: ConfigPhysics,
     game_config: ConfigGame,
+    rewind: f32, // in seconds, to where the attacker saw the other players
 }
 
 impl CommandHandler for AreaAttackCommandHandler {
@@ -61,6 +65,10 @@ impl CommandHandler for AreaAttackCommandHandler {
             }
         }
 
+        // fog shortens every attack
+        let base_attack_range =
+            weather_attack_range(&game_state.world.weather, &self.game_config.weather_config);
+
         let player_state = game_state
             .player_mut(self.player_id)
             .ok_or_else(|| HandlerError::new(format!("Player {} not found", self.player_id)))?;
@@ -86,29 +94,12 @@ impl CommandHandler for AreaAttackCommandHandler {
         // when attacking, remove invisibility
         super::remove_invisibility(player_state);
 
-        let wind_enhanced = player_state
-            .status_effects
-            .contains_key(&StatusEffect::Power(PowerUpEffects::EnhancedWind));
-        let scalar = if wind_enhanced {
-            self.game_config.powerup_config.wind_enhancement_scalar
-        } else {
-            1.0
-        };
+        let range_scalar =
+            player_state.stat(Stat::AttackRange, base_attack_range, &self.game_config);
+        let strength_scalar = player_state.stat(Stat::AttackStrength, 1.0, &self.game_config);
 
         let player_pos = player_state.transform.translation;
 
-        let player_collider_handle = physics_state
-            .get_entity_handles(self.player_id)
-            .ok_or(HandlerError::new(format!(
-                "Player {} not found",
-                self.player_id
-            )))?
-            .collider
-            .ok_or(HandlerError::new(format!(
-                "Player {} does not have a collider",
-                self.player_id
-            )))?;
-
         player_state.insert_cooldown(
             Command::AreaAttack,
             self.physics_config.attack_config.area_attack_cooldown,
@@ -154,89 +145,69 @@ impl CommandHandler for AreaAttackCommandHandler {
             Duration::from_secs_f32(self.physics_config.attack_config.area_attack_cooldown),
         ));
 
-        // loop over all other players
-        for (other_player_id, other_player_state) in game_state.players.iter_mut() {
-            if &self.player_id == other_player_id {
-                continue;
-            }
-
-            // other player not affected if invincible
-            if other_player_state
-                .holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible))
-            {
-                continue;
-            }
-
-            // get direction from this player to other player
-            let other_player_pos = other_player_state.transform.translation;
-            let vec_to_other = glm::normalize(&(other_player_pos - player_pos));
-
-            // send ray to other player (may need multiple later)
-            let solid = true;
-            let filter = pipeline::QueryFilter::default().exclude_collider(player_collider_handle);
+        let attack_config = &self.physics_config.attack_config;
+        let targets = super::attack_targets(game_state, self.player_id);
+        let hits = physics_state.query_targets_rewound(
+            &TargetQuery::Sphere {
+                center: player_pos,
+                radius: attack_config.max_area_attack_dist * range_scalar,
+            },
+            Some(Entity::player(self.player_id)),
+            &targets,
+            self.rewind,
+        );
+        let attacks_landed = hits.len() as u32;
+
+        for hit in hits {
+            // the targets are all players
+            let target_id = hit.entity.player_id().unwrap();
+            let other_player_state = game_state.player_mut(target_id).unwrap();
+
+            // targets partly behind cover are pushed less
+            let attack_strength = strength_scalar
+                * hit.exposure
+                * (attack_config.area_attack_impulse
+                    - (attack_config.area_attack_coeff * hit.distance));
+
+            let impulse_vec = hit.direction
+                * attack_strength
+                * other_player_state.stat(Stat::KnockbackTaken, 1.0, &self.game_config);
+
+            // clear velocity of target before applying impulse
+            physics_state.set_character_linvel(target_id, rapier::vector![0.0, 0.0, 0.0]);
+
+            // apply_stun
+            // super::apply_stun(
+            //     other_player_state,
+            //     attack_strength / self.physics_config.attack_config.area_attack_impulse
+            //         * self.physics_config.attack_config.max_attack_stun_duration,
+            // );
+
+            // TODO:
+            other_player_state.apply_status_effect(
+                StatusEffect::Other(OtherEffects::MovementDisabled),
+                attack_strength / attack_config.area_attack_impulse
+                    * attack_config.max_attack_stun_duration,
+                1.0,
+                Some(self.player_id),
+                &self.game_config,
+            );
 
-            let ray = geometry::Ray::new(
-                rapier::point![player_pos.x, player_pos.y, player_pos.z],
-                rapier::vector![vec_to_other.x, vec_to_other.y, vec_to_other.z],
+            // apply attack impulse
+            physics_state.apply_character_impulse(
+                target_id,
+                rapier::vector![impulse_vec.x, impulse_vec.y, impulse_vec.z],
             );
-            if let Some((handle, toi)) = physics_state.query_pipeline.cast_ray(
-                &physics_state.bodies,
-                &physics_state.colliders,
-                &ray,
-                self.physics_config.attack_config.max_area_attack_dist * scalar,
-                solid,
-                filter,
-            ) {
-                let other_player_collider_handle = physics_state
-                    .get_entity_handles(*other_player_id)
-                    .ok_or(HandlerError::new(format!(
-                        "Player {} not found",
-                        self.player_id
-                    )))?
-                    .collider
-                    .ok_or(HandlerError::new(format!(
-                        "Player {} does not have a collider",
-                        self.player_id
-                    )))?;
-
-                // if ray hit the correct target (the other player), apply force
-                if handle == other_player_collider_handle {
-                    let other_player_rigid_body = physics_state
-                        .get_entity_rigid_body_mut(*other_player_id)
-                        .unwrap();
-
-                    let attack_strength = scalar
-                        * (self.physics_config.attack_config.area_attack_impulse
-                            - (self.physics_config.attack_config.area_attack_coeff * toi));
-
-                    let impulse_vec = vec_to_other * attack_strength;
-
-                    // clear velocity of target before applying impulse
-                    other_player_rigid_body.set_linvel(rapier::vector![0.0, 0.0, 0.0], true);
-
-                    // apply_stun
-                    // super::apply_stun(
-                    //     other_player_state,
-                    //     attack_strength / self.physics_config.attack_config.area_attack_impulse
-                    //         * self.physics_config.attack_config.max_attack_stun_duration,
-                    // );
-
-                    // TODO:
-                    other_player_state.status_effects.insert(
-                        StatusEffect::Other(OtherEffects::MovementDisabled),
-                        attack_strength / self.physics_config.attack_config.area_attack_impulse
-                            * self.physics_config.attack_config.max_attack_stun_duration,
-                    );
-
-                    // apply attack impulse
-                    other_player_rigid_body.apply_impulse(
-                        rapier::vector![impulse_vec.x, impulse_vec.y, impulse_vec.z],
-                        true,
-                    );
-                }
-            }
+
+            other_player_state.record_hit_by(self.player_id, self.game_config.ring_out_window);
         }
 
+        game_state
+            .player_mut(self.player_id)
+            .unwrap()
+            .stats
+            .attacks_landed += attacks_landed;
+
         Ok(())
     }
 }
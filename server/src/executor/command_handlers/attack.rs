@@ -1,15 +1,18 @@
+use crate::executor::command_handlers::weather::weather_attack_range;
+use crate::simulation::entity::Entity;
 use crate::simulation::physics_state::PhysicsState;
+use crate::simulation::targeting::TargetQuery;
 use crate::Recipients;
 use common::core::action_states::ActionState;
 use common::core::choices::FinalChoices;
 use common::core::command::Command;
 use common::core::events::{GameEvent, ParticleSpec, ParticleType, SoundSpec};
-use common::core::powerup_system::{OtherEffects, PowerUpEffects, StatusEffect};
+use common::core::powerup_system::{OtherEffects, StatusEffect};
 use common::core::states::GameState;
+use common::core::status_effects::Stat;
 use derive_more::Constructor;
 use nalgebra::UnitQuaternion;
 use nalgebra_glm::Vec3;
-use rapier3d::{geometry, pipeline};
 use std::time::Duration;
 
 extern crate nalgebra_glm as glm;
@@ -25,6 +28,8 @@ pub struct AttackCommandHandler {
     player_id: u32,
     physics_config: ConfigPhysics,
     game_config: ConfigGame,
+    charge_level: f32, // 0 for a regular attack, up to 1 for a fully charged one
+    rewind: f32,       // in seconds, to where the attacker saw the other players
 }
 
 impl CommandHandler for AttackCommandHandler {
@@ -62,7 +67,9 @@ impl CommandHandler for AttackCommandHandler {
             }
         }
 
-        super::handle_invincible_players(game_state, physics_state, self.player_id);
+        // fog shortens every attack
+        let base_attack_range =
+            weather_attack_range(&game_state.world.weather, &self.game_config.weather_config);
 
         let player_state = game_state
             .player_mut(self.player_id)
@@ -79,9 +86,10 @@ impl CommandHandler for AttackCommandHandler {
         }
 
         // if attack on cooldown, or cannot consume charge, do nothing for now
+        let attack_config = &self.physics_config.attack_config;
         if player_state.command_on_cooldown(Command::Attack)
             || !player_state
-                .try_consume_wind_charge(Some(self.physics_config.attack_config.attack_cost))
+                .try_consume_wind_charge(Some(attack_config.charged_attack_cost(self.charge_level)))
         {
             return Ok(());
         }
@@ -91,20 +99,8 @@ impl CommandHandler for AttackCommandHandler {
 
         let player_pos = player_state.transform.translation;
 
-        let player_collider_handle = physics_state
-            .get_entity_handles(self.player_id)
-            .ok_or(HandlerError::new(format!(
-                "Player {} not found",
-                self.player_id
-            )))?
-            .collider
-            .ok_or(HandlerError::new(format!(
-                "Player {} does not have a collider",
-                self.player_id
-            )))?;
-
         let player_rigid_body = physics_state
-            .get_entity_rigid_body_mut(self.player_id)
+            .get_entity_rigid_body_mut(Entity::player(self.player_id))
             .unwrap();
 
         let camera_forward = player_state.camera_forward;
@@ -149,109 +145,81 @@ impl CommandHandler for AttackCommandHandler {
             Recipients::All,
         );
 
-        let wind_enhanced = player_state
-            .status_effects
-            .contains_key(&StatusEffect::Power(PowerUpEffects::EnhancedWind));
-        let scalar = if wind_enhanced {
-            self.game_config.powerup_config.wind_enhancement_scalar
-        } else {
-            1.0
-        };
+        let (charge_impulse_scale, charge_range_scale) =
+            attack_config.charge_scales(self.charge_level);
+        let range_scalar =
+            player_state.stat(Stat::AttackRange, base_attack_range, &self.game_config)
+                * charge_range_scale;
+        let strength_scalar =
+            player_state.stat(Stat::AttackStrength, 1.0, &self.game_config) * charge_impulse_scale;
 
         player_state.active_action_states.insert((
             ActionState::Attacking,
             Duration::from_secs_f32(self.physics_config.attack_config.attack_cooldown),
         ));
 
-        // loop over all other players
-        for (other_player_id, other_player_state) in game_state.players.iter_mut() {
-            if &self.player_id == other_player_id {
-                continue;
-            }
-
-            // other player not affected if invincible
-            if other_player_state
-                .holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible))
-            {
-                continue;
-            }
-
-            // get direction from this player to other player
-            let other_player_pos = other_player_state.transform.translation;
-            let vec_to_other = glm::normalize(&(other_player_pos - player_pos));
-
-            // check dot product between direction to other player and attack direction
-            let angle = glm::angle(&horizontal_camera_forward, &vec_to_other);
-
-            // if object in attack range
-            if angle <= self.physics_config.attack_config.max_attack_angle * scalar {
-                // send ray to other player (may need multiple later)
-                let solid = true;
-                let filter =
-                    pipeline::QueryFilter::default().exclude_collider(player_collider_handle);
-
-                let ray = geometry::Ray::new(
-                    rapier::point![player_pos.x, player_pos.y, player_pos.z],
-                    rapier::vector![vec_to_other.x, vec_to_other.y, vec_to_other.z],
-                );
-                if let Some((handle, toi)) = physics_state.query_pipeline.cast_ray(
-                    &physics_state.bodies,
-                    &physics_state.colliders,
-                    &ray,
-                    self.physics_config.attack_config.max_attack_dist * scalar,
-                    solid,
-                    filter,
-                ) {
-                    let other_player_collider_handle = physics_state
-                        .get_entity_handles(*other_player_id)
-                        .ok_or(HandlerError::new(format!(
-                            "Player {} not found",
-                            self.player_id
-                        )))?
-                        .collider
-                        .ok_or(HandlerError::new(format!(
-                            "Player {} does not have a collider",
-                            self.player_id
-                        )))?;
-
-                    // if ray hit the correct target (the other player), apply force
-                    if handle == other_player_collider_handle {
-                        let other_player_rigid_body = physics_state
-                            .get_entity_rigid_body_mut(*other_player_id)
-                            .unwrap();
-
-                        let attack_strength = scalar
-                            * (self.physics_config.attack_config.attack_impulse
-                                - (self.physics_config.attack_config.attack_coeff * toi));
-                        let impulse_vec = vec_to_other * attack_strength;
-
-                        // clear velocity of target before applying attack
-                        other_player_rigid_body.set_linvel(rapier::vector![0.0, 0.0, 0.0], true);
-
-                        // apply_stun
-                        // super::apply_stun(
-                        //     other_player_state,
-                        //     attack_strength / self.physics_config.attack_config.attack_impulse
-                        //         * self.physics_config.attack_config.max_attack_stun_duration,
-                        // );
-
-                        // TODO:
-                        other_player_state.status_effects.insert(
-                            StatusEffect::Other(OtherEffects::MovementDisabled),
-                            attack_strength / self.physics_config.attack_config.attack_impulse
-                                * self.physics_config.attack_config.max_attack_stun_duration,
-                        );
-
-                        // apply attack impulse
-                        other_player_rigid_body.apply_impulse(
-                            rapier::vector![impulse_vec.x, impulse_vec.y, impulse_vec.z],
-                            true,
-                        );
-                    }
-                }
-            }
+        let targets = super::attack_targets(game_state, self.player_id);
+        let hits = physics_state.query_targets_rewound(
+            &TargetQuery::Cone {
+                origin: player_pos,
+                direction: horizontal_camera_forward,
+                half_angle: attack_config.max_attack_angle * range_scalar,
+                range: attack_config.max_attack_dist * range_scalar,
+            },
+            Some(Entity::player(self.player_id)),
+            &targets,
+            self.rewind,
+        );
+        let attacks_landed = hits.len() as u32;
+
+        for hit in hits {
+            // the targets are all players
+            let target_id = hit.entity.player_id().unwrap();
+            let other_player_state = game_state.player_mut(target_id).unwrap();
+
+            // targets partly behind cover are pushed less
+            let attack_strength = strength_scalar
+                * hit.exposure
+                * (attack_config.attack_impulse - (attack_config.attack_coeff * hit.distance));
+            let impulse_vec = hit.direction
+                * attack_strength
+                * other_player_state.stat(Stat::KnockbackTaken, 1.0, &self.game_config);
+
+            // clear velocity of target before applying attack
+            physics_state.set_character_linvel(target_id, rapier::vector![0.0, 0.0, 0.0]);
+
+            // apply_stun
+            // super::apply_stun(
+            //     other_player_state,
+            //     attack_strength / self.physics_config.attack_config.attack_impulse
+            //         * self.physics_config.attack_config.max_attack_stun_duration,
+            // );
+
+            // TODO:
+            other_player_state.apply_status_effect(
+                StatusEffect::Other(OtherEffects::MovementDisabled),
+                attack_strength / attack_config.attack_impulse
+                    * attack_config.max_attack_stun_duration,
+                1.0,
+                Some(self.player_id),
+                &self.game_config,
+            );
+
+            // apply attack impulse
+            physics_state.apply_character_impulse(
+                target_id,
+                rapier::vector![impulse_vec.x, impulse_vec.y, impulse_vec.z],
+            );
+
+            other_player_state.record_hit_by(self.player_id, self.game_config.ring_out_window);
         }
 
+        game_state
+            .player_mut(self.player_id)
+            .unwrap()
+            .stats
+            .attacks_landed += attacks_landed;
+
         Ok(())
     }
 }
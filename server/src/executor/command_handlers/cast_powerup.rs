@@ -1,30 +1,29 @@
 extern crate nalgebra_glm as glm;
 
 use super::{CommandHandler, GameEventCollector, HandlerError, HandlerResult};
+use crate::simulation::entity::Entity;
 use crate::simulation::physics_state::PhysicsState;
+use crate::simulation::targeting::TargetQuery;
 use crate::Recipients;
-use common::configs::game_config::ConfigGame;
+use common::configs::game_config::{ConfigGame, ConfigPowerUpDefinition};
 use common::core::action_states::ActionState;
 use common::core::command::Command;
-use common::core::events::{GameEvent, ParticleSpec, ParticleType, SoundSpec};
-use common::core::powerup_system::OtherEffects::{Slippery, Stun};
+use common::core::events::{GameEvent, ParticleSpec, SoundSpec};
+use common::core::powerup_system::OtherEffects::{MovementDisabled, Stun};
 use common::core::powerup_system::PowerUpEffects::Invincible;
-use common::core::powerup_system::{
-    OtherEffects, PowerUp, PowerUpEffects, PowerUpStatus, StatusEffect, POWER_UP_TO_EFFECT_MAP,
-};
-use common::core::states::GameState;
+use common::core::powerup_system::{PowerUpActivation, PowerUpEffect, PowerUpStatus, StatusEffect};
+use common::core::states::{GameState, ProjectileState};
 use derive_more::Constructor;
-use nalgebra::{zero, UnitQuaternion};
+use nalgebra::UnitQuaternion;
 use nalgebra_glm::Vec3;
-use rapier3d::math::Isometry;
 use rapier3d::prelude as rapier;
-use rapier3d::{geometry, pipeline};
 use std::time::Duration;
 
 #[derive(Constructor)]
 pub struct CastPowerUpCommandHandler {
     player_id: u32,
     game_config: ConfigGame,
+    rewind: f32, // in seconds, to where the caster saw the other players
 }
 
 impl CommandHandler for CastPowerUpCommandHandler {
@@ -34,8 +33,6 @@ impl CommandHandler for CastPowerUpCommandHandler {
         physics_state: &mut PhysicsState,
         game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        super::handle_invincible_players(game_state, physics_state, self.player_id);
-
         let player_state = game_state
             .player(self.player_id)
             .ok_or_else(|| HandlerError::new(format!("Player {} not found", self.player_id)))?
@@ -53,163 +50,83 @@ impl CommandHandler for CastPowerUpCommandHandler {
             return Ok(());
         } // Maybe Add Cleanse?
 
-        // if powerup is on cooldown, or does not have a powerup, return
-        if player_state.power_up.is_none() {
-            return Ok(());
-        }
+        // if player does not have a powerup, return
+        let (power_up, power_up_status) = match player_state.power_up.clone() {
+            Some(power_up) => power_up,
+            None => return Ok(()),
+        };
+        let definition = self
+            .game_config
+            .powerup_config
+            .definition(&power_up)
+            .ok_or_else(|| HandlerError::new(format!("Power-up {} not found", power_up.0)))?;
 
-        if let Some((x, PowerUpStatus::Active)) = player_state.power_up.clone() {
-            {
-                let player_state = game_state.player_mut(self.player_id).unwrap();
-                // when dashing or flashing, remove invisibility
-                super::remove_invisibility(player_state);
-            }
-            return match x {
-                PowerUp::Flash => flash(
-                    game_state,
-                    self.player_id,
-                    self.game_config.clone(),
-                    physics_state,
-                    game_events,
-                ),
-                PowerUp::Dash => dash(
-                    game_state,
-                    self.player_id,
-                    self.game_config.clone(),
-                    physics_state,
-                    game_events,
-                ),
-                _ => Ok(()),
-            };
+        {
+            let player_state = game_state.player_mut(self.player_id).unwrap();
+            // when using a powerup, remove invisibility
+            super::remove_invisibility(player_state);
         }
 
-        let mut other_player_status_changes: Vec<(u32, StatusEffect, f32)> = vec![];
-
-        if let Some((x, PowerUpStatus::Held)) = player_state.power_up.clone() {
-            {
-                let player_state = game_state.player_mut(self.player_id).unwrap();
-                // when using a powerup, remove invisibility
-                super::remove_invisibility(player_state);
-                player_state
-                    .active_action_states
-                    .insert((ActionState::CastingPowerUp, Duration::from_secs_f32(1.666)));
-            }
-            match x {
-                PowerUp::Blizzard => {
-                    let player_pos = player_state.transform.translation;
-
-                    let player_rigid_body = physics_state
-                        .get_entity_rigid_body_mut(self.player_id)
-                        .unwrap();
+        if power_up_status == PowerUpStatus::Active {
+            return self.use_power_up(definition, game_state, physics_state, game_events);
+        }
 
-                    let camera_forward = player_state.camera_forward;
-                    let horizontal_camera_forward = Vec3::new(
-                        player_state.camera_forward.x,
-                        0.0,
-                        player_state.camera_forward.z,
-                    );
-
-                    // turn player towards attack direction (camera_forward)
-                    let rotation =
-                        UnitQuaternion::face_towards(&horizontal_camera_forward, &Vec3::y());
-                    player_rigid_body.set_rotation(rotation, true);
-
-                    // send game events for attack sound/particles
-                    game_events.add(
-                        GameEvent::ParticleEvent(ParticleSpec::new(
-                            ParticleType::BLIZZARD,
-                            player_pos,
-                            horizontal_camera_forward,
-                            //TODO: placeholder for player color
-                            glm::vec3(0.0, 1.0, 0.0),
-                            glm::vec4(1.0, 1.0, 1.0, 1.0),
-                            format!("Blizzard from player {}", self.player_id),
-                        )),
-                        Recipients::All,
-                    );
-
-                    game_events.add(
-                        GameEvent::SoundEvent(SoundSpec::new(
-                            player_pos,
-                            "ice".to_string(),
-                            (self.player_id, false),
-                            (false, false, false),
-                            player_state.camera_forward,
-                        )),
-                        Recipients::All,
-                    );
-
-                    // loop over all other players
-                    for (other_player_id, other_player_state) in game_state.players.iter_mut() {
-                        if &self.player_id == other_player_id {
-                            continue;
-                        }
-
-                        // other player not affected if invincible
-                        if other_player_state
-                            .holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible))
-                        {
-                            continue;
-                        }
-
-                        // get direction from this player to other player
-                        let other_player_pos = other_player_state.transform.translation;
-                        let vec_to_other = glm::normalize(&(other_player_pos - player_pos));
-
-                        // check dot product between direction to other player and attack direction
-                        let angle = glm::angle(&horizontal_camera_forward, &vec_to_other);
-
-                        let dist = glm::length(&(other_player_pos - player_pos));
-                        // if object in attack range
-                        if angle <= self.game_config.powerup_config.blizzard_max_attack_angle
-                            && dist <= self.game_config.powerup_config.blizzard_max_attack_dist
-                        {
-                            other_player_status_changes.push((
-                                *other_player_id,
-                                StatusEffect::Other(Stun),
-                                self.game_config.powerup_config.power_up_debuff_duration,
-                            ));
-                            other_player_status_changes.push((
-                                *other_player_id,
-                                StatusEffect::Other(Slippery),
-                                self.game_config.powerup_config.power_up_debuff_duration,
-                            ));
-
-                            other_player_state.active_action_states.insert((
-                                ActionState::Frozen,
-                                Duration::from_secs_f32(
-                                    self.game_config.powerup_config.power_up_debuff_duration,
-                                ),
-                            ));
-                        }
-                    }
+        {
+            let player_state = game_state.player_mut(self.player_id).unwrap();
+            player_state
+                .active_action_states
+                .insert((ActionState::CastingPowerUp, Duration::from_secs_f32(1.666)));
+            player_state.stats.power_ups_used += 1;
+        }
+        game_events.add(
+            GameEvent::PowerUpCast {
+                player: self.player_id,
+                power_up,
+            },
+            Recipients::All,
+        );
+
+        let player_pos = player_state.transform.translation;
+        if let Some(particle) = &definition.particle {
+            game_events.add(
+                GameEvent::ParticleEvent(ParticleSpec::new(
+                    particle.clone(),
+                    player_pos,
+                    horizontal(&player_state.camera_forward),
+                    //TODO: placeholder for player color
+                    glm::vec3(0.0, 1.0, 0.0),
+                    glm::vec4(1.0, 1.0, 1.0, 1.0),
+                    format!("{} from player {}", definition.name, self.player_id),
+                )),
+                Recipients::All,
+            );
+        }
+        if let Some(sound) = &definition.sound {
+            game_events.add(
+                GameEvent::SoundEvent(SoundSpec::new(
+                    player_pos,
+                    sound.clone(),
+                    (self.player_id, false),
+                    (false, false, false),
+                    player_state.camera_forward,
+                )),
+                Recipients::All,
+            );
+        }
 
-                    let player_state = game_state.player_mut(self.player_id).unwrap();
-                    // Clear blizzard power up after use
-                    player_state.power_up = None;
-                }
-                x => {
-                    if x == PowerUp::Invincible {
-                        super::reset_weather(physics_state, self.player_id);
-                    }
-                    let player_state = game_state.player_mut(self.player_id).unwrap();
-                    player_state.status_effects.insert(
-                        *POWER_UP_TO_EFFECT_MAP.get(&(x.value())).unwrap(),
-                        self.game_config.powerup_config.power_up_buff_duration,
-                    );
-                    // by now the player should have casted the powerup successfully, change powerup status
-                    player_state.power_up = Some((
-                        player_state.power_up.clone().unwrap().0,
-                        PowerUpStatus::Active,
-                    ));
-                }
-            }
-        };
+        for effect in definition.effects.iter() {
+            self.apply_effect(effect, game_state, physics_state);
+        }
 
-        // TODO: replace this example with actual implementation, with sound_id powerups etc.
-        if let Some((x, PowerUpStatus::Held)) = player_state.power_up.clone() {
-            if x != PowerUp::Blizzard {
-                let player_pos = player_state.transform.translation;
+        let player_state = game_state.player_mut(self.player_id).unwrap();
+        match definition.activation {
+            PowerUpActivation::Instant => player_state.power_up = None,
+            PowerUpActivation::Held => {
+                // by now the player should have casted the powerup successfully, change powerup status
+                player_state.power_up = Some((
+                    player_state.power_up.clone().unwrap().0,
+                    PowerUpStatus::Active,
+                ));
                 game_events.add(
                     GameEvent::SoundEvent(SoundSpec::new(
                         player_pos,
@@ -218,188 +135,281 @@ impl CommandHandler for CastPowerUpCommandHandler {
                         (false, false, false),
                         player_state.camera_forward,
                     )),
-                    Recipients::All // One(self.player_id as u8),
+                    Recipients::All, // One(self.player_id as u8),
                 );
             }
         }
-        // End of TODO
-
-        // apply effects to other players
-        for (id, effect, duration) in other_player_status_changes.iter() {
-            let other_player_state = game_state.player_mut(*id).unwrap();
-            if !other_player_state
-                .status_effects
-                .contains_key(&StatusEffect::Power(Invincible))
-            {
-                other_player_state.status_effects.insert(*effect, *duration);
-            }
-        }
 
         Ok(())
     }
 }
 
-fn flash(
-    game_state: &mut GameState,
-    player_id: u32,
-    game_config: ConfigGame,
-    physics_state: &mut PhysicsState,
-    game_events: &mut dyn GameEventCollector,
-) -> HandlerResult {
-    let player_state = game_state
-        .player_mut(player_id)
-        .ok_or_else(|| HandlerError::new(format!("Player {} not found", player_id)))?;
-    // if player is stunned
-    if player_state.holds_status_effect_mut(StatusEffect::Other(OtherEffects::Stun)) {
-        return Ok(());
+impl CastPowerUpCommandHandler {
+    // casting an active power-up again triggers its on_use effects, e.g. dash or flash
+    fn use_power_up(
+        &self,
+        definition: &ConfigPowerUpDefinition,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        game_events: &mut dyn GameEventCollector,
+    ) -> HandlerResult {
+        let player_state = game_state
+            .player_mut(self.player_id)
+            .ok_or_else(|| HandlerError::new(format!("Player {} not found", self.player_id)))?;
+
+        // if on cooldown, or nothing to use, do nothing for now
+        if definition.on_use.is_empty() || player_state.command_on_cooldown(Command::CastPowerUp) {
+            return Ok(());
+        }
+        player_state.insert_cooldown(Command::CastPowerUp, definition.cooldown);
+
+        if let Some(sound) = &definition.use_sound {
+            game_events.add(
+                GameEvent::SoundEvent(SoundSpec::new(
+                    player_state.transform.translation,
+                    sound.clone(),
+                    (self.player_id, true),
+                    (false, false, false),
+                    player_state.camera_forward,
+                )),
+                Recipients::All,
+            );
+        }
+
+        for effect in definition.on_use.iter() {
+            self.apply_effect(effect, game_state, physics_state);
+        }
+        Ok(())
     }
 
-    // if flash on cooldown, or should not be able to dash, do nothing for now
-    if player_state.command_on_cooldown(Command::Flash)
-        || !player_state.holds_status_effect_mut(StatusEffect::Power(PowerUpEffects::EnabledFlash))
-    {
-        return Ok(());
+    fn apply_effect(
+        &self,
+        effect: &PowerUpEffect,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+    ) {
+        let player_state = game_state.player_mut(self.player_id).unwrap();
+        let player_pos = player_state.transform.translation;
+        let camera_forward = player_state.camera_forward;
+
+        match effect {
+            PowerUpEffect::Status {
+                effect,
+                duration,
+                magnitude,
+            } => {
+                if *effect == StatusEffect::Power(Invincible) {
+                    super::reset_weather(physics_state, self.player_id);
+                }
+                player_state.apply_status_effect(
+                    *effect,
+                    *duration,
+                    *magnitude,
+                    Some(self.player_id),
+                    &self.game_config,
+                );
+            }
+            PowerUpEffect::Impulse {
+                strength,
+                blocking_duration,
+            } => {
+                player_state.apply_status_effect(
+                    StatusEffect::Other(MovementDisabled),
+                    *blocking_duration,
+                    1.0,
+                    Some(self.player_id),
+                    &self.game_config,
+                );
+
+                let player_rigid_body = physics_state
+                    .get_entity_rigid_body_mut(Entity::player(self.player_id))
+                    .unwrap();
+                face_towards(player_rigid_body, &camera_forward);
+
+                // clear velocity of the player before applying the impulse
+                physics_state.set_character_linvel(self.player_id, rapier::vector![0.0, 0.0, 0.0]);
+                physics_state.apply_character_impulse(
+                    self.player_id,
+                    rapier::vector![
+                        camera_forward.x * strength,
+                        0.0,
+                        camera_forward.z * strength
+                    ],
+                );
+            }
+            PowerUpEffect::Teleport { distance } => {
+                let player_rigid_body = physics_state
+                    .get_entity_rigid_body_mut(Entity::player(self.player_id))
+                    .unwrap();
+                face_towards(player_rigid_body, &camera_forward);
+
+                let mut new_coordinates = player_pos;
+                new_coordinates.x += distance * camera_forward.x;
+                new_coordinates.z += distance * camera_forward.z;
+                player_rigid_body.set_translation(new_coordinates, true);
+            }
+            PowerUpEffect::AreaDebuff {
+                effects,
+                action_state,
+                duration,
+                max_dist,
+                max_angle,
+            } => {
+                let player_rigid_body = physics_state
+                    .get_entity_rigid_body_mut(Entity::player(self.player_id))
+                    .unwrap();
+                face_towards(player_rigid_body, &camera_forward);
+
+                let targets = super::attack_targets(game_state, self.player_id);
+                let hits = physics_state.query_targets_rewound(
+                    &TargetQuery::Cone {
+                        origin: player_pos,
+                        direction: horizontal(&camera_forward),
+                        half_angle: *max_angle,
+                        range: *max_dist,
+                    },
+                    Some(Entity::player(self.player_id)),
+                    &targets,
+                    self.rewind,
+                );
+                for hit in hits {
+                    // the targets are all players
+                    let target_id = hit.entity.player_id().unwrap();
+                    let other_player_state = game_state.player_mut(target_id).unwrap();
+                    for effect in effects.iter() {
+                        other_player_state.apply_status_effect(
+                            *effect,
+                            *duration,
+                            1.0,
+                            Some(self.player_id),
+                            &self.game_config,
+                        );
+                    }
+                    if let Some(action_state) = action_state {
+                        other_player_state
+                            .active_action_states
+                            .insert((*action_state, Duration::from_secs_f32(*duration)));
+                    }
+                    other_player_state
+                        .record_hit_by(self.player_id, self.game_config.ring_out_window);
+                }
+            }
+            PowerUpEffect::Projectile {
+                speed,
+                radius,
+                gravity_scale,
+                lifetime,
+                on_hit,
+            } => {
+                let Some((power_up, _)) = player_state.power_up.clone() else {
+                    return;
+                };
+                // a player that hasn't sent its camera yet shoots where it faces
+                let Some(direction) = camera_forward.try_normalize(f32::EPSILON).or_else(|| {
+                    physics_state
+                        .get_entity_rigid_body(Entity::player(self.player_id))
+                        .map(|body| body.rotation() * Vec3::z())
+                }) else {
+                    return;
+                };
+                // clear of the capsule of the caster, though it never hits its owner
+                let origin = player_pos + direction * (0.5 + radius);
+                let velocity = direction * *speed;
+                let entity =
+                    physics_state.launch_projectile(origin, velocity, *radius, *gravity_scale);
+                game_state.world.projectiles.insert(
+                    entity,
+                    ProjectileState {
+                        owner: self.player_id,
+                        power_up,
+                        position: origin,
+                        velocity,
+                        radius: *radius,
+                        time_left: *lifetime,
+                        on_hit: on_hit.clone(),
+                    },
+                );
+            }
+        }
     }
+}
 
-    let player_pos = player_state.transform.translation;
-
-    game_events.add(
-        GameEvent::SoundEvent(SoundSpec::new(
-            player_pos,
-            "flash".to_string(),
-            (player_id, true),
-            (false, false, false),
-            player_state.camera_forward,
-        )),
-        Recipients::All,
-    );
-
-    let player_rigid_body = physics_state.get_entity_rigid_body_mut(player_id).unwrap();
-
-    let camera_forward = Vec3::new(
-        player_state.camera_forward.x,
-        0.0,
-        player_state.camera_forward.z,
-    );
-
-    // turn player towards attack direction (camera_forward)
-    let rotation = UnitQuaternion::face_towards(&camera_forward, &Vec3::y());
-    player_rigid_body.set_rotation(rotation, true);
+fn horizontal(camera_forward: &Vec3) -> Vec3 {
+    Vec3::new(camera_forward.x, 0.0, camera_forward.z)
+}
 
-    player_state.insert_cooldown(Command::Flash, game_config.powerup_config.flash_cooldown);
-
-    // TODO::
-    // Flashy particle effect would be cool here
-    // game_events.add(
-    //     GameEvent::ParticleEvent(ParticleSpec::new(
-    //         ParticleType::ATTACK,
-    //         player_pos.clone(),
-    //         camera_forward.clone(),
-    //         glm::vec3(0.0, 1.0, 0.0),
-    //         glm::vec4(0.4, 0.9, 0.7, 1.0),
-    //         format!("Attack from player {}", self.player_id),
-    //     )),
-    //     Recipients::All,
-    // );
-
-    let x_dir = player_state.camera_forward.x;
-    let z_dir = player_state.camera_forward.z;
-
-    let mut new_coordinates = game_state
-        .player_mut(player_id)
-        .unwrap()
-        .transform
-        .translation;
-
-    new_coordinates.x += game_config.powerup_config.flash_distance_scalar * x_dir;
-    new_coordinates.z += game_config.powerup_config.flash_distance_scalar * z_dir;
-
-    let new_position = Isometry::new(new_coordinates, zero());
-    player_rigid_body.set_position(new_position, true);
-    Ok(())
+// turn player towards the direction of the effect (camera_forward)
+fn face_towards(player_rigid_body: &mut rapier::RigidBody, camera_forward: &Vec3) {
+    // no direction to face without a camera
+    if horizontal(camera_forward).norm() <= f32::EPSILON {
+        return;
+    }
+    let rotation = UnitQuaternion::face_towards(&horizontal(camera_forward), &Vec3::y());
+    player_rigid_body.set_rotation(rotation, true);
 }
 
-fn dash(
-    game_state: &mut GameState,
-    player_id: u32,
-    game_config: ConfigGame,
-    physics_state: &mut PhysicsState,
-    game_events: &mut dyn GameEventCollector,
-) -> HandlerResult {
-    let player_state = game_state
-        .player_mut(player_id)
-        .ok_or_else(|| HandlerError::new(format!("Player {} not found", player_id)))?;
-
-    // if player is stunned
-    if player_state.holds_status_effect_mut(StatusEffect::Other(OtherEffects::Stun)) {
-        return Ok(());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::configs::from_file;
+    use common::core::powerup_system::PowerUp;
+    use common::core::states::PlayerState;
+    use rapier::{ColliderBuilder, RigidBodyBuilder};
+    use std::cell::RefCell;
+
+    const PLAYER: u32 = 1;
+
+    fn setup(
+        power_up: (PowerUp, PowerUpStatus),
+        camera_forward: Vec3,
+    ) -> (GameState, PhysicsState) {
+        let mut game_state = GameState::new();
+        let mut physics_state = PhysicsState::new();
+        let body = RigidBodyBuilder::dynamic().build();
+        let collider = ColliderBuilder::capsule_y(0.5, 0.5).build();
+        physics_state.insert_entity(Entity::player(PLAYER), Some(collider), Some(body));
+        game_state.players.insert(
+            PLAYER,
+            PlayerState {
+                id: PLAYER,
+                camera_forward,
+                power_up: Some(power_up),
+                ..Default::default()
+            },
+        );
+        (game_state, physics_state)
     }
 
-    // if dash on cooldown, or should not be able to dash, do nothing for now
-    if player_state.command_on_cooldown(Command::Dash)
-        || !player_state.holds_status_effect_mut(StatusEffect::Power(PowerUpEffects::EnabledDash))
-    {
-        return Ok(());
+    fn cast(game_state: &mut GameState, physics_state: &mut PhysicsState) {
+        let game_config: ConfigGame = from_file("../game.json").unwrap();
+        let game_events = RefCell::new(Vec::new());
+        CastPowerUpCommandHandler::new(PLAYER, game_config, 0.0)
+            .handle(game_state, physics_state, &mut game_events.borrow_mut())
+            .unwrap();
     }
 
-    player_state.status_effects.insert(
-        StatusEffect::Other(OtherEffects::MovementDisabled),
-        game_config.powerup_config.dash_blocking_duration,
-    );
-
-    let player_pos = player_state.transform.translation;
-
-    game_events.add(
-        GameEvent::SoundEvent(SoundSpec::new(
-            player_pos,
-            "dash".to_string(),
-            (player_id, true),
-            (false, false, false),
-            player_state.camera_forward,
-        )),
-        Recipients::All,
-    );
-
-    let player_rigid_body = physics_state.get_entity_rigid_body_mut(player_id).unwrap();
-
-    let camera_forward = Vec3::new(
-        player_state.camera_forward.x,
-        0.0,
-        player_state.camera_forward.z,
-    );
-
-    // turn player towards dash direction (camera_forward)
-    let rotation = UnitQuaternion::face_towards(&camera_forward, &Vec3::y());
-    player_rigid_body.set_rotation(rotation, true);
+    #[test]
+    fn test_projectile_without_camera_flies_where_the_caster_faces() {
+        let gust_ball = (PowerUp("gust_ball".to_string()), PowerUpStatus::Held);
+        let (mut game_state, mut physics_state) = setup(gust_ball, Vec3::zeros());
+        cast(&mut game_state, &mut physics_state);
+
+        let projectile = game_state.world.projectiles.values().next().unwrap();
+        let direction = projectile.velocity.normalize();
+        assert!((direction - Vec3::z()).norm() < 1e-5);
+        assert!(projectile.position.iter().all(|x| x.is_finite()));
+    }
 
-    player_state.insert_cooldown(Command::Dash, game_config.powerup_config.dash_cooldown);
-
-    // TODO::
-    // some particle at the end would be cool, but probably different
-    // game_events.add(
-    //     GameEvent::ParticleEvent(ParticleSpec::new(
-    //         ParticleType::ATTACK,
-    //         player_pos.clone(),
-    //         camera_forward.clone(),
-    //         glm::vec3(0.0, 1.0, 0.0),
-    //         glm::vec4(0.4, 0.9, 0.7, 1.0),
-    //         format!("Attack from player {}", self.player_id),
-    //     )),
-    //     Recipients::All,
-    // );
-
-    // clear velocity of target before applying attack
-    player_rigid_body.set_linvel(rapier::vector![0.0, 0.0, 0.0], true);
-
-    player_rigid_body.apply_impulse(
-        rapier::vector![
-            player_state.camera_forward.x * game_config.powerup_config.dash_impulse,
-            0.0,
-            player_state.camera_forward.z * game_config.powerup_config.dash_impulse
-        ],
-        true,
-    );
-    super::handle_invincible_players(game_state, physics_state, player_id);
-
-    Ok(())
+    #[test]
+    fn test_teleport_keeps_facing() {
+        let flash = (PowerUp("flash".to_string()), PowerUpStatus::Active);
+        let (mut game_state, mut physics_state) = setup(flash, Vec3::x());
+        cast(&mut game_state, &mut physics_state);
+
+        let body = physics_state
+            .get_entity_rigid_body(Entity::player(PLAYER))
+            .unwrap();
+        assert!(body.translation().x > 1.0);
+        assert!((body.rotation() * Vec3::z() - Vec3::x()).norm() < 1e-5);
+    }
 }
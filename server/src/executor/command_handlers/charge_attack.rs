@@ -0,0 +1,62 @@
+use super::attack::AttackCommandHandler;
+use super::{CommandHandler, GameEventCollector, HandlerError, HandlerResult};
+use crate::simulation::physics_state::PhysicsState;
+use common::configs::game_config::ConfigGame;
+use common::configs::physics_config::ConfigPhysics;
+use common::core::command::{ChargePhase, Command};
+use common::core::powerup_system::{OtherEffects, StatusEffect};
+use common::core::states::GameState;
+use derive_more::Constructor;
+
+/// Hold-to-charge attack, the charge itself grows with the game state tick
+#[derive(Constructor)]
+pub struct ChargeAttackCommandHandler {
+    player_id: u32,
+    phase: ChargePhase,
+    physics_config: ConfigPhysics,
+    game_config: ConfigGame,
+    rewind: f32,
+}
+
+impl CommandHandler for ChargeAttackCommandHandler {
+    fn handle(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        game_events: &mut dyn GameEventCollector,
+    ) -> HandlerResult {
+        let player_state = game_state
+            .player_mut(self.player_id)
+            .ok_or_else(|| HandlerError::new(format!("Player {} not found", self.player_id)))?;
+
+        match self.phase {
+            ChargePhase::Begin => {
+                // same conditions as a regular attack, but nothing is consumed until release
+                if player_state.is_dead
+                    || player_state.charge_time.is_some()
+                    || player_state.holds_status_effect(StatusEffect::Other(OtherEffects::Stun))
+                    || player_state.command_on_cooldown(Command::Attack)
+                    || player_state.wind_charge < self.physics_config.attack_config.attack_cost
+                {
+                    return Ok(());
+                }
+                player_state.start_charging();
+                Ok(())
+            }
+            ChargePhase::Release => {
+                // a release without a charge (e.g. a quick tap) is a regular attack
+                let charge_level = player_state.stop_charging().map_or(0.0, |charge_time| {
+                    self.physics_config.attack_config.charge_level(charge_time)
+                });
+                AttackCommandHandler::new(
+                    self.player_id,
+                    self.physics_config.clone(),
+                    self.game_config.clone(),
+                    charge_level,
+                    self.rewind,
+                )
+                .handle(game_state, physics_state, game_events)
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use derive_more::Constructor;
 
+use common::configs::game_config::ConfigGame;
 use common::core::powerup_system::{PowerUp, PowerUpStatus};
 use common::core::states::GameState;
 
@@ -12,6 +13,7 @@ use crate::simulation::physics_state::PhysicsState;
 pub struct CheatCodeCommandHandler {
     player_id: u32,
     powerup: PowerUp,
+    game_config: ConfigGame,
 }
 
 impl CommandHandler for CheatCodeCommandHandler {
@@ -21,6 +23,17 @@ impl CommandHandler for CheatCodeCommandHandler {
         _: &mut PhysicsState,
         _: &mut dyn GameEventCollector,
     ) -> HandlerResult {
+        if self
+            .game_config
+            .powerup_config
+            .definition(&self.powerup)
+            .is_none()
+        {
+            return Err(HandlerError::new(format!(
+                "Power-up {} not found",
+                self.powerup.0
+            )));
+        }
         let mut player_state = game_state
             .player_mut(self.player_id)
             .ok_or_else(|| HandlerError::new(format!("Player {} not found", self.player_id)))?;
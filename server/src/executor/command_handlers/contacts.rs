@@ -0,0 +1,366 @@
+use super::{CommandHandler, GameEventCollector, HandlerResult};
+use crate::simulation::entity::{ContactEvent, Entity};
+use crate::simulation::physics_state::PhysicsState;
+use common::configs::game_config::ConfigGame;
+use common::core::powerup_system::{PowerUpEffects, ProjectileHit, StatusEffect};
+use common::core::states::{GameState, PlayerState};
+use common::core::status_effects::Stat;
+use derive_more::Constructor;
+use nalgebra_glm as glm;
+
+/// Gameplay reacting to the contacts of the last physics step, bumps, projectile hits and zones
+#[derive(Constructor)]
+pub struct ContactCommandHandler {
+    events: Vec<ContactEvent>,
+    game_config: ConfigGame,
+}
+
+impl CommandHandler for ContactCommandHandler {
+    fn handle(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        _: &mut dyn GameEventCollector,
+    ) -> HandlerResult {
+        for event in self.events.iter() {
+            match *event {
+                ContactEvent::Started {
+                    entities: (a, b),
+                    sensor: false,
+                } => {
+                    self.bump(game_state, physics_state, a, b);
+                    self.bump(game_state, physics_state, b, a);
+                }
+                // projectile hits and zone membership, sensors of other kinds are ignored
+                ContactEvent::Started {
+                    entities: (a, b),
+                    sensor: true,
+                } => {
+                    self.projectile_hit(game_state, physics_state, a, b);
+                    self.projectile_hit(game_state, physics_state, b, a);
+                    game_state.enter_zone(a, b);
+                    game_state.enter_zone(b, a);
+                }
+                ContactEvent::Stopped {
+                    entities: (a, b),
+                    sensor: true,
+                } => {
+                    game_state.leave_zone(a, b);
+                    game_state.leave_zone(b, a);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ContactCommandHandler {
+    /// An invincible player launches the players it runs into
+    fn bump(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        attacker_entity: Entity,
+        victim_entity: Entity,
+    ) {
+        let (Some(attacker_id), Some(victim_id)) =
+            (attacker_entity.player_id(), victim_entity.player_id())
+        else {
+            return;
+        };
+        let invincible = StatusEffect::Power(PowerUpEffects::Invincible);
+        let (Some(attacker), Some(victim)) =
+            (game_state.player(attacker_id), game_state.player(victim_id))
+        else {
+            return;
+        };
+        if !attacker.holds_status_effect(invincible)
+            || victim.holds_status_effect(invincible)
+            || victim.is_dead
+        {
+            return;
+        }
+
+        let direction =
+            glm::normalize(&(victim.transform.translation - attacker.transform.translation));
+        let impulse = direction
+            * self.game_config.powerup_config.invincible_effective_impulse
+            * victim.stat(Stat::KnockbackTaken, 1.0, &self.game_config);
+        physics_state.apply_character_impulse(victim_id, impulse);
+
+        // a bump from an invincible player counts as a hit for ring-out attribution
+        if let Some(victim) = game_state.player_mut(victim_id) {
+            victim.record_hit_by(attacker_id, self.game_config.ring_out_window);
+        }
+    }
+
+    /// A projectile hits the first player or solid thing it touches other than its owner,
+    /// then vanishes
+    fn projectile_hit(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        projectile_entity: Entity,
+        other_entity: Entity,
+    ) {
+        let Some(projectile) = game_state.world.projectiles.get(&projectile_entity) else {
+            return;
+        };
+        let solid = physics_state
+            .get_entity_collider(other_entity)
+            .is_some_and(|collider| !collider.is_sensor());
+        if other_entity.player_id() == Some(projectile.owner) || !solid {
+            return;
+        }
+        let projectile = game_state
+            .world
+            .projectiles
+            .remove(&projectile_entity)
+            .unwrap();
+        // where it is now, the synced position is a step behind
+        let position = physics_state
+            .get_entity_rigid_body(projectile_entity)
+            .map_or(projectile.position, |body| *body.translation());
+        physics_state.remove_entity(projectile_entity);
+
+        let direction = projectile
+            .velocity
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(glm::Vec3::zeros);
+        for hit in projectile.on_hit.iter() {
+            match *hit {
+                ProjectileHit::Impulse { strength } => self.knock(
+                    game_state,
+                    physics_state,
+                    projectile.owner,
+                    other_entity.player_id(),
+                    direction * strength,
+                ),
+                ProjectileHit::Status {
+                    effect,
+                    duration,
+                    magnitude,
+                } => {
+                    let Some(victim) = other_entity
+                        .player_id()
+                        .and_then(|victim_id| game_state.player_mut(victim_id))
+                    else {
+                        continue;
+                    };
+                    if !hittable(victim) {
+                        continue;
+                    }
+                    victim.apply_status_effect(
+                        effect,
+                        duration,
+                        magnitude,
+                        Some(projectile.owner),
+                        &self.game_config,
+                    );
+                    victim.record_hit_by(projectile.owner, self.game_config.ring_out_window);
+                }
+                ProjectileHit::Burst { radius, strength } => {
+                    let in_reach = game_state
+                        .players
+                        .values()
+                        .filter(|player| player.id != projectile.owner)
+                        .filter(|player| {
+                            glm::distance(&player.transform.translation, &position) <= radius
+                        })
+                        .map(|player| (player.id, player.transform.translation - position))
+                        .collect::<Vec<_>>();
+                    for (player_id, away) in in_reach {
+                        let away = away.try_normalize(f32::EPSILON).unwrap_or(direction);
+                        self.knock(
+                            game_state,
+                            physics_state,
+                            projectile.owner,
+                            Some(player_id),
+                            away * strength,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pushes a player hit by someone else's projectile
+    fn knock(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        attacker_id: u32,
+        victim_id: Option<u32>,
+        impulse: glm::Vec3,
+    ) {
+        let Some(victim_id) = victim_id else {
+            return;
+        };
+        let Some(victim) = game_state.player_mut(victim_id) else {
+            return;
+        };
+        if !hittable(victim) {
+            return;
+        }
+        let impulse = impulse * victim.stat(Stat::KnockbackTaken, 1.0, &self.game_config);
+        physics_state.apply_character_impulse(victim_id, impulse);
+        victim.record_hit_by(attacker_id, self.game_config.ring_out_window);
+    }
+}
+
+// as the attacks, projectiles leave the dead and the invincible alone
+fn hittable(player: &PlayerState) -> bool {
+    !player.is_dead && !player.holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::configs::from_file;
+    use common::core::powerup_system::PowerUp;
+    use common::core::states::ProjectileState;
+    use rapier3d::prelude::*;
+    use std::cell::RefCell;
+
+    const OWNER: u32 = 1;
+    const WALL: Entity = Entity::from_raw(300);
+    const ZONE: Entity = Entity::from_raw(301);
+
+    fn add_player(game_state: &mut GameState, physics_state: &mut PhysicsState, id: u32, x: f32) {
+        let position = glm::vec3(x, 0.0, 0.0);
+        let body = RigidBodyBuilder::dynamic().translation(position).build();
+        physics_state.insert_entity(
+            Entity::player(id),
+            Some(ColliderBuilder::ball(0.5).build()),
+            Some(body),
+        );
+        let mut player = PlayerState {
+            id,
+            ..Default::default()
+        };
+        player.transform.translation = position;
+        game_state.players.insert(id, player);
+    }
+
+    // a gust ball flying along +x from the owner
+    fn setup() -> (ContactCommandHandler, GameState, PhysicsState, Entity) {
+        let game_config: ConfigGame = from_file("../game.json").unwrap();
+        let mut game_state = GameState::new();
+        let mut physics_state = PhysicsState::new();
+        add_player(&mut game_state, &mut physics_state, OWNER, 0.0);
+        let wall = ColliderBuilder::cuboid(0.1, 5.0, 5.0).build();
+        physics_state.insert_entity(WALL, Some(wall), None);
+        let zone = ColliderBuilder::ball(5.0).sensor(true).build();
+        physics_state.insert_entity(ZONE, Some(zone), None);
+
+        let position = glm::vec3(1.0, 0.0, 0.0);
+        let velocity = glm::vec3(8.0, 0.0, 0.0);
+        let projectile = physics_state.launch_projectile(position, velocity, 0.4, 0.0);
+        game_state.world.projectiles.insert(
+            projectile,
+            ProjectileState {
+                owner: OWNER,
+                power_up: PowerUp("gust_ball".to_string()),
+                position,
+                velocity,
+                radius: 0.4,
+                time_left: 4.0,
+                on_hit: vec![
+                    ProjectileHit::Impulse { strength: 200.0 },
+                    ProjectileHit::Burst {
+                        radius: 3.0,
+                        strength: 80.0,
+                    },
+                ],
+            },
+        );
+        let handler = ContactCommandHandler::new(Vec::new(), game_config);
+        (handler, game_state, physics_state, projectile)
+    }
+
+    fn touch(
+        handler: &mut ContactCommandHandler,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        entities: (Entity, Entity),
+    ) {
+        handler.events = vec![ContactEvent::Started {
+            entities,
+            sensor: true,
+        }];
+        let game_events = RefCell::new(Vec::new());
+        handler
+            .handle(game_state, physics_state, &mut game_events.borrow_mut())
+            .unwrap();
+    }
+
+    fn hit_by(game_state: &GameState, id: u32) -> Option<u32> {
+        game_state.players[&id].last_hit.map(|hit| hit.attacker_id)
+    }
+
+    #[test]
+    fn test_projectile_passes_owner_and_sensors() {
+        let (mut handler, mut game_state, mut physics_state, projectile) = setup();
+        add_player(&mut game_state, &mut physics_state, 2, 2.0);
+
+        touch(
+            &mut handler,
+            &mut game_state,
+            &mut physics_state,
+            (projectile, Entity::player(OWNER)),
+        );
+        touch(
+            &mut handler,
+            &mut game_state,
+            &mut physics_state,
+            (ZONE, projectile),
+        );
+        assert!(game_state.world.projectiles.contains_key(&projectile));
+        assert_eq!(hit_by(&game_state, OWNER), None);
+
+        touch(
+            &mut handler,
+            &mut game_state,
+            &mut physics_state,
+            (Entity::player(2), projectile),
+        );
+        assert!(!game_state.world.projectiles.contains_key(&projectile));
+        assert!(physics_state.get_entity_handles(projectile).is_none());
+        assert_eq!(hit_by(&game_state, 2), Some(OWNER));
+        assert!(physics_state.character_linvel(2).x > 0.0);
+        assert_eq!(hit_by(&game_state, OWNER), None);
+    }
+
+    #[test]
+    fn test_projectile_burst_reaches_hittable_players() {
+        let (mut handler, mut game_state, mut physics_state, projectile) = setup();
+        // around where it hits, only the last is out of reach
+        for (id, x) in [(2, 2.5), (3, 2.0), (4, 0.5), (5, 6.0)] {
+            add_player(&mut game_state, &mut physics_state, id, x);
+        }
+        game_state.player_mut(3).unwrap().apply_status_effect(
+            StatusEffect::Power(PowerUpEffects::Invincible),
+            10.0,
+            1.0,
+            None,
+            &handler.game_config,
+        );
+        game_state.player_mut(4).unwrap().is_dead = true;
+
+        touch(
+            &mut handler,
+            &mut game_state,
+            &mut physics_state,
+            (projectile, WALL),
+        );
+        assert!(!game_state.world.projectiles.contains_key(&projectile));
+        // pushed away from where it hit
+        assert_eq!(hit_by(&game_state, 2), Some(OWNER));
+        assert!(physics_state.character_linvel(2).x > 0.0);
+        for id in [OWNER, 3, 4, 5] {
+            assert_eq!(hit_by(&game_state, id), None);
+            assert_eq!(physics_state.character_linvel(id), glm::Vec3::zeros());
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use super::{CommandHandler, GameEventCollector, HandlerError, HandlerResult};
 extern crate nalgebra_glm as glm;
 use crate::Recipients;
+use crate::simulation::entity::Entity;
 use crate::simulation::physics_state::PhysicsState;
 use common::core::events::GameEvent;
 use common::{configs::game_config::ConfigGame, core::events::SoundSpec};
 use common::core::command::Command;
-use common::core::states::GameState;
+use common::core::states::{GameState, GroundState};
 use derive_more::Constructor;
 use nalgebra::zero;
 use rapier3d::math::Isometry;
@@ -51,22 +52,40 @@ impl CommandHandler for DieCommandHandler {
 
         player_state.reset_status_effects();
         player_state.power_up = None;
+        player_state.stats.deaths += 1;
+        let ring_out_by = player_state.take_ring_out_credit();
 
         let spawn_position = player_state.spawn_point;
 
         // Teleport the player back to their spawn position and disable physics.
         let new_position = Isometry::new(spawn_position, zero());
-        if let Some(player_rigid_body) = physics_state.get_entity_rigid_body_mut(self.player_id) {
+        if let Some(player_rigid_body) =
+            physics_state.get_entity_rigid_body_mut(Entity::player(self.player_id))
+        {
             player_rigid_body.set_position(new_position, true);
-            player_rigid_body.set_linvel(rapier::vector![0.0, 0.0, 0.0], true);
             player_rigid_body.set_enabled(false);
         }
+        physics_state.set_character_linvel(self.player_id, rapier::vector![0.0, 0.0, 0.0]);
 
         player_state.is_dead = true;
         player_state.jump_count = 1;
+        player_state.ground = GroundState::default();
         player_state.respawn_sec = 3;
         player_state.insert_cooldown(Command::Spawn, new_spawn_cooldown);
 
+        // credit the last player who hit this one within the attribution window
+        if let Some(attacker) = ring_out_by.and_then(|id| game_state.player_mut(id)) {
+            attacker.stats.ring_outs += 1;
+        }
+
+        game_events.add(
+            GameEvent::PlayerKnockedOut {
+                victim: self.player_id,
+                by: ring_out_by,
+            },
+            Recipients::All,
+        );
+
         Ok(())
     }
 }
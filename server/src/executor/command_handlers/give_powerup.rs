@@ -29,6 +29,16 @@ impl CommandHandler for GivePowerUpCommandHandler {
         let player_pos = player_state.transform.translation;
         // TODO: maybe add sound for picking up powerup?
 
+        if let Some((power_up, _)) = player_state.power_up.clone() {
+            game_events.add(
+                GameEvent::PowerUpPickedUp {
+                    player: self.player_id,
+                    power_up,
+                },
+                Recipients::All,
+            );
+        }
+
         game_events.add(
             GameEvent::ParticleEvent(ParticleSpec::new(
                 ParticleType::POWERUP,
@@ -0,0 +1,136 @@
+use super::jump::JumpCommandHandler;
+use super::{CommandHandler, GameEventCollector, HandlerResult};
+use crate::simulation::entity::Entity;
+use crate::simulation::physics_state::PhysicsState;
+use crate::Recipients;
+use common::configs::game_config::ConfigGame;
+use common::configs::physics_config::ConfigPhysics;
+use common::core::command::Command;
+use common::core::events::{GameEvent, SoundSpec};
+use common::core::powerup_system::{OtherEffects, StatusEffect};
+use common::core::states::{GameState, GroundTransition, PlayerState};
+use derive_more::Constructor;
+use itertools::Itertools;
+use nalgebra::Vector3;
+use std::f32::consts::PI;
+
+// steepest contact normal a player can stand on
+const MAX_GROUND_ANGLE: f32 = PI / 3.;
+
+/// Tracks whether players stand on something, every tick.
+/// Resets jumps on the ground, takes the ground jump away once the coyote time is over,
+/// and does the jumps buffered in the air on landing.
+#[derive(Constructor)]
+pub struct GroundCommandHandler {
+    physics_config: ConfigPhysics,
+    game_config: ConfigGame,
+}
+
+impl CommandHandler for GroundCommandHandler {
+    fn handle(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        game_events: &mut dyn GameEventCollector,
+    ) -> HandlerResult {
+        let dt = physics_state.dt();
+        let movement_config = &self.physics_config.movement_config;
+        let mut buffered_jumps = Vec::new();
+
+        for player_id in game_state.players.keys().copied().sorted().collect_vec() {
+            let on_ground = is_on_ground(physics_state, player_id);
+            let player_state = game_state.player_mut(player_id).unwrap();
+            if player_state.is_dead {
+                continue;
+            }
+
+            let air_time = player_state.ground.air_time;
+            match player_state.ground.update(on_ground, dt) {
+                Some(GroundTransition::Landed)
+                    if player_state.ground.jumped || air_time > movement_config.coyote_time =>
+                {
+                    add_sound(game_events, player_state, "land");
+                }
+                Some(GroundTransition::TookOff) if player_state.ground.jumped => {
+                    add_sound(game_events, player_state, "jump");
+                }
+                _ => {}
+            }
+
+            if !on_ground {
+                // walked off a ledge too long ago, only the air jumps are left
+                if player_state.ground.air_time > movement_config.coyote_time
+                    && player_state.jump_count == 0
+                {
+                    player_state.jump_count = 1;
+                }
+                continue;
+            }
+
+            // still on the ground right after jumping, the jump hasn't taken off yet
+            if player_state.on_cooldown.contains_key(&Command::Jump)
+                || player_state.holds_status_effect(StatusEffect::Other(OtherEffects::Stun))
+            {
+                continue;
+            }
+            player_state.jump_count = 0;
+            player_state.ground.jumped = false;
+            if player_state.ground.buffered_jump.take().is_some() {
+                buffered_jumps.push(player_id);
+            }
+        }
+
+        for player_id in buffered_jumps {
+            JumpCommandHandler::new(
+                player_id,
+                self.physics_config.clone(),
+                self.game_config.clone(),
+            )
+            .handle(game_state, physics_state, game_events)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the player touches something it can stand on, going by the contact normals
+fn is_on_ground(physics_state: &PhysicsState, player_id: u32) -> bool {
+    // the controller moving kinematic players knows best
+    if let Some(grounded) = physics_state.character_grounded(player_id) {
+        return grounded;
+    }
+    let Some(collider_handle) = physics_state
+        .get_entity_handles(Entity::player(player_id))
+        .and_then(|handles| handles.collider)
+    else {
+        return false;
+    };
+
+    physics_state
+        .narrow_phase
+        .contacts_with(collider_handle)
+        .any(|contact_pair| {
+            let Some((manifold, _)) = contact_pair.find_deepest_contact() else {
+                return false;
+            };
+            // the normal points from the first collider to the second one
+            let up_from_ground = if contact_pair.collider1 == collider_handle {
+                -manifold.data.normal
+            } else {
+                manifold.data.normal
+            };
+            nalgebra_glm::angle(&up_from_ground, &Vector3::y()) < MAX_GROUND_ANGLE
+        })
+}
+
+fn add_sound(game_events: &mut dyn GameEventCollector, player_state: &PlayerState, name: &str) {
+    game_events.add(
+        GameEvent::SoundEvent(SoundSpec::new(
+            player_state.transform.translation,
+            name.to_string(),
+            (player_state.id, true),
+            (false, false, false),
+            player_state.camera_forward,
+        )),
+        Recipients::All,
+    );
+}
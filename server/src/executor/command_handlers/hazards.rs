@@ -0,0 +1,260 @@
+use super::die::DieCommandHandler;
+use super::{CommandHandler, GameEventCollector, HandlerError, HandlerResult};
+use crate::simulation::entity::Entity;
+use crate::simulation::physics_state::PhysicsState;
+use crate::Recipients;
+use common::configs::game_config::ConfigGame;
+use common::configs::scene_config::ConfigBehavior;
+use common::core::events::{GameEvent, SoundSpec};
+use common::core::powerup_system::{PowerUpEffects, StatusEffect};
+use common::core::states::{GameState, HazardPhase, HazardState, PlayerState};
+use derive_more::Constructor;
+use nalgebra::{Isometry3, Translation3, UnitQuaternion};
+use nalgebra_glm as glm;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Simulates the scene nodes that have a behavior, see ConfigBehavior
+#[derive(Constructor)]
+pub struct HazardCommandHandler {
+    behaviors: Arc<HashMap<String, ConfigBehavior>>, // by scene node id
+    game_config: ConfigGame,
+}
+
+impl CommandHandler for HazardCommandHandler {
+    fn handle(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        game_events: &mut dyn GameEventCollector,
+    ) -> HandlerResult {
+        let dt = physics_state.dt();
+        let mut players_to_kill = Vec::new();
+
+        for (node_id, hazard) in game_state.world.hazards.iter_mut() {
+            let behavior = self
+                .behaviors
+                .get(node_id)
+                .ok_or_else(|| HandlerError::new(format!("Hazard {} has no behavior", node_id)))?;
+            let touching = touching_players(physics_state, &game_state.players, hazard.entity);
+
+            match *behavior {
+                ConfigBehavior::MovingPlatform {
+                    ref waypoints,
+                    speed,
+                } => {
+                    if waypoints.is_empty() {
+                        continue;
+                    }
+                    let target = hazard.origin.translation + waypoints[hazard.next_waypoint];
+                    let to_target = target - hazard.transform.translation;
+                    let next = if to_target.norm() <= speed * dt {
+                        hazard.next_waypoint = (hazard.next_waypoint + 1) % waypoints.len();
+                        target
+                    } else {
+                        hazard.transform.translation + to_target.normalize() * speed * dt
+                    };
+                    move_hazard(physics_state, hazard, next);
+                }
+                ConfigBehavior::BouncePad { impulse, cooldown } => {
+                    if let HazardPhase::Recharging(time_left) = hazard.phase {
+                        hazard.phase = if time_left > dt {
+                            HazardPhase::Recharging(time_left - dt)
+                        } else {
+                            HazardPhase::Idle
+                        };
+                        continue;
+                    }
+                    if touching.is_empty() {
+                        continue;
+                    }
+                    let up = glm::quat_rotate_vec3(&hazard.origin.rotation, &glm::Vec3::y());
+                    for &player_id in touching.iter() {
+                        // every bounce is as high, whatever the speed at landing
+                        let velocity = physics_state.character_linvel(player_id);
+                        physics_state
+                            .set_character_linvel(player_id, velocity - up * velocity.dot(&up));
+                        physics_state.apply_character_impulse(player_id, up * impulse);
+
+                        let player_state = &game_state.players[&player_id];
+                        game_events.add(
+                            GameEvent::SoundEvent(SoundSpec::new(
+                                player_state.transform.translation,
+                                "jump".to_string(),
+                                (player_id, true),
+                                (false, false, false),
+                                player_state.camera_forward,
+                            )),
+                            Recipients::All,
+                        );
+                    }
+                    hazard.phase = HazardPhase::Recharging(cooldown);
+                }
+                ConfigBehavior::WindTunnel { force, .. } => {
+                    // the force is in the frame of the node
+                    let force = glm::quat_rotate_vec3(&hazard.origin.rotation, &force);
+                    for &player_id in touching.iter() {
+                        if game_state.players[&player_id]
+                            .holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible))
+                        {
+                            continue;
+                        }
+                        physics_state.apply_character_impulse(player_id, force * dt);
+                    }
+                }
+                ConfigBehavior::KillVolume { .. } => {
+                    players_to_kill.extend(touching);
+                }
+                ConfigBehavior::CrumblingPlatform {
+                    crumble_delay,
+                    respawn_delay,
+                } => match hazard.phase {
+                    HazardPhase::Crumbling(time_left) if time_left > dt => {
+                        hazard.phase = HazardPhase::Crumbling(time_left - dt);
+                    }
+                    HazardPhase::Crumbling(_) => {
+                        hazard.phase = HazardPhase::Falling(0.0);
+                    }
+                    HazardPhase::Falling(time) if time + dt < respawn_delay => {
+                        let time = time + dt;
+                        hazard.phase = HazardPhase::Falling(time);
+                        let next =
+                            hazard.origin.translation + physics_state.gravity * (0.5 * time * time);
+                        move_hazard(physics_state, hazard, next);
+                    }
+                    HazardPhase::Falling(_) => {
+                        hazard.phase = HazardPhase::Idle;
+                        let origin = hazard.origin.translation;
+                        move_hazard(physics_state, hazard, origin);
+                    }
+                    _ if !touching.is_empty() => {
+                        hazard.phase = HazardPhase::Crumbling(crumble_delay);
+                    }
+                    _ => {}
+                },
+            }
+        }
+
+        for player_id in players_to_kill {
+            DieCommandHandler::new(player_id, self.game_config.clone()).handle(
+                game_state,
+                physics_state,
+                game_events,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Living players touching the entity's collider, or inside it if it is a sensor
+fn touching_players(
+    physics_state: &PhysicsState,
+    players: &HashMap<u32, PlayerState>,
+    entity: Entity,
+) -> Vec<u32> {
+    let Some(hazard_collider) = physics_state
+        .get_entity_handles(entity)
+        .and_then(|handles| handles.collider)
+    else {
+        return Vec::new();
+    };
+    let is_sensor = physics_state
+        .colliders
+        .get(hazard_collider)
+        .is_some_and(|collider| collider.is_sensor());
+
+    players
+        .values()
+        .filter(|player| !player.is_dead)
+        .filter(|player| {
+            let Some(player_collider) = physics_state
+                .get_entity_handles(Entity::player(player.id))
+                .and_then(|handles| handles.collider)
+            else {
+                return false;
+            };
+            if is_sensor {
+                physics_state
+                    .narrow_phase
+                    .intersection_pair(hazard_collider, player_collider)
+                    == Some(true)
+            } else {
+                physics_state
+                    .narrow_phase
+                    .contact_pair(hazard_collider, player_collider)
+                    .is_some_and(|pair| pair.has_any_active_contact)
+            }
+        })
+        .map(|player| player.id)
+        .collect()
+}
+
+/// Kinematic hazards reach the translation at the next physics step
+fn move_hazard(physics_state: &mut PhysicsState, hazard: &HazardState, translation: glm::Vec3) {
+    if let Some(body) = physics_state.get_entity_rigid_body_mut(hazard.entity) {
+        body.set_next_kinematic_position(Isometry3::from_parts(
+            Translation3::from(translation),
+            UnitQuaternion::from_quaternion(hazard.origin.rotation),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::entity::EntityKind;
+    use common::configs::from_file;
+    use common::core::components::Transform;
+    use rapier3d::prelude::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_moving_platform_follows_waypoints() {
+        let behavior = ConfigBehavior::MovingPlatform {
+            waypoints: vec![glm::vec3(4.0, 0.0, 0.0)],
+            speed: 2.0,
+        };
+        let handler = HazardCommandHandler::new(
+            Arc::new(HashMap::from([("platform".to_string(), behavior)])),
+            from_file("../game.json").unwrap(),
+        );
+
+        let mut game_state = GameState::new();
+        let mut physics_state = PhysicsState::new();
+        let body = RigidBodyBuilder::kinematic_position_based().build();
+        let collider = ColliderBuilder::cuboid(1.0, 0.1, 1.0).build();
+        let platform =
+            physics_state.spawn_entity(EntityKind::Hazard, None, Some(collider), Some(body));
+        game_state.world.hazards.insert(
+            "platform".to_string(),
+            HazardState::new(platform, Transform::default()),
+        );
+        let game_events = RefCell::new(Vec::new());
+
+        let ticks = 10;
+        for _ in 0..ticks {
+            handler
+                .handle(
+                    &mut game_state,
+                    &mut physics_state,
+                    &mut game_events.borrow_mut(),
+                )
+                .unwrap();
+            physics_state.step();
+            // as the executor syncs it after the step
+            let hazard = game_state.world.hazards.get_mut("platform").unwrap();
+            let position = physics_state
+                .get_entity_rigid_body(platform)
+                .unwrap()
+                .position();
+            hazard.transform.translation = position.translation.vector;
+        }
+
+        // moves toward the waypoint at its speed
+        let translation = game_state.world.hazards["platform"].transform.translation;
+        let expected = 2.0 * physics_state.dt() * ticks as f32;
+        assert!((translation.x - expected).abs() < 1e-3);
+        assert!(translation.y.abs() < 1e-3 && translation.z.abs() < 1e-3);
+    }
+}
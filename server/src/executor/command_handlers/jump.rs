@@ -3,27 +3,25 @@ use crate::Recipients;
 use crate::simulation::physics_state::PhysicsState;
 use common::core::action_states::ActionState;
 use common::core::events::{GameEvent, SoundSpec};
-use common::core::powerup_system::{
-    OtherEffects, PowerUp, PowerUpEffects, PowerUpStatus, StatusEffect,
-};
+use common::core::powerup_system::{OtherEffects, PowerUp, PowerUpStatus, StatusEffect};
 use common::core::states::GameState;
 use derive_more::Constructor;
-use itertools::Itertools;
-use nalgebra::Vector3;
-use std::f32::consts::PI;
 use std::time::Duration;
 
 extern crate nalgebra_glm as glm;
 
 use crate::game_loop::{ClientCommand, TICK_RATE};
+use common::configs::game_config::ConfigGame;
 use common::configs::physics_config::ConfigPhysics;
 use common::core::command::Command;
+use common::core::status_effects::Stat;
 use rapier3d::prelude as rapier;
 
 #[derive(Constructor)]
 pub struct JumpCommandHandler {
     player_id: u32,
     physics_config: ConfigPhysics,
+    game_config: ConfigGame,
 }
 
 impl CommandHandler for JumpCommandHandler {
@@ -33,13 +31,6 @@ impl CommandHandler for JumpCommandHandler {
         physics_state: &mut PhysicsState,
         game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        // reset jump
-        JumpResetCommandHandler::new(self.player_id).handle(
-            game_state,
-            physics_state,
-            game_events,
-        )?;
-
         let mut player_state = game_state
             .player_mut(self.player_id)
             .ok_or_else(|| HandlerError::new(format!("Player {} not found", self.player_id)))?;
@@ -58,42 +49,47 @@ impl CommandHandler for JumpCommandHandler {
             return Ok(());
         }
 
-        let jump_limit = if player_state
-            .holds_status_effect_mut(StatusEffect::Power(PowerUpEffects::TripleJump))
-        {
-            self.physics_config.movement_config.max_jump_count + 1
-        } else {
-            self.physics_config.movement_config.max_jump_count
-        };
+        let jump_limit = player_state.stat(
+            Stat::JumpCount,
+            self.physics_config.movement_config.max_jump_count as f32,
+            &self.game_config,
+        ) as u32;
 
         if player_state.jump_count >= jump_limit {
+            // out of air jumps, jump as soon as the player lands
+            if !player_state.ground.on_ground {
+                player_state.ground.buffered_jump =
+                    Some(self.physics_config.movement_config.jump_buffer);
+            }
             return Ok(());
         }
 
         player_state.jump_count += 1;
 
-        game_events.add(
-            GameEvent::SoundEvent(SoundSpec::new(
-                player_state.transform.translation,
-                "jump".to_string(),
-                (self.player_id, true),
-                (false, false, false),
-                player_state.camera_forward,
-            )),
-            Recipients::All, // One(self.player_id as u8),
-        );
+        if player_state.ground.on_ground {
+            // the sound plays when the player leaves the ground, see GroundCommandHandler
+            player_state.ground.jumped = true;
+        } else {
+            game_events.add(
+                GameEvent::SoundEvent(SoundSpec::new(
+                    player_state.transform.translation,
+                    "jump".to_string(),
+                    (self.player_id, true),
+                    (false, false, false),
+                    player_state.camera_forward,
+                )),
+                Recipients::All, // One(self.player_id as u8),
+            );
+        }
 
         if player_state.jump_count > 1 {
             // when multi-jumping, remove invisibility
             super::remove_invisibility(player_state);
         }
 
-        let player_rigid_body = physics_state
-            .get_entity_rigid_body_mut(self.player_id)
-            .unwrap();
-        player_rigid_body.apply_impulse(
+        physics_state.apply_character_impulse(
+            self.player_id,
             rapier::vector![0.0, self.physics_config.movement_config.jump_impulse, 0.0],
-            true,
         );
 
         player_state.active_action_states.insert((
@@ -110,80 +106,6 @@ impl CommandHandler for JumpCommandHandler {
             self.physics_config.movement_config.jump_cooldown,
         );
 
-        super::handle_invincible_players(game_state, physics_state, self.player_id);
-
-        Ok(())
-    }
-}
-
-#[derive(Constructor)]
-pub(crate) struct JumpResetCommandHandler {
-    player_id: u32,
-}
-
-impl CommandHandler for JumpResetCommandHandler {
-    fn handle(
-        &self,
-        game_state: &mut GameState,
-        physics_state: &mut PhysicsState,
-        game_events: &mut dyn GameEventCollector,
-    ) -> HandlerResult {
-        let mut player_state = game_state
-            .player_mut(self.player_id)
-            .ok_or_else(|| HandlerError::new(format!("Player {} not found", self.player_id)))?;
-
-        if player_state.on_cooldown.contains_key(&Command::Jump) {
-            return Ok(());
-        }
-
-        let player_collider_handle = physics_state
-            .get_entity_handles(self.player_id)
-            .ok_or(HandlerError::new(format!(
-                "Handlers for player {} not found",
-                self.player_id
-            )))?
-            .collider
-            .ok_or(HandlerError::new(format!(
-                "Collider for player {} not found",
-                self.player_id
-            )))?;
-
-        let contact_pairs = physics_state
-            .narrow_phase
-            .contacts_with(player_collider_handle)
-            .collect_vec();
-
-        let mut should_reset_jump = false;
-        for contact_pair in contact_pairs {
-            if let Some((manifold, _)) = contact_pair.find_deepest_contact() {
-                // see if player is above another collider by testing the normal angle
-                if nalgebra_glm::angle(&manifold.data.normal, &Vector3::y()) < PI / 3. {
-                    should_reset_jump = true;
-                    // if player_state.jump_count > 0 {
-                    //     game_events.add(
-                    //         GameEvent::SoundEvent(SoundSpec::new(
-                    //             player_state.transform.translation,
-                    //             "land".to_string(),
-                    //             (self.player_id, true),
-                    //             (false, false, false),
-                    //             player_state.camera_forward,
-                    //         )),
-                    //         Recipients::All, // One(self.player_id as u8),
-                    //     );
-                    // }
-                    // break;
-                }
-            }
-        }
-
-        // if player is stunned
-        if player_state.holds_status_effect_mut(StatusEffect::Other(OtherEffects::Stun)) {
-            return Ok(());
-        }
-
-        if should_reset_jump {
-            player_state.jump_count = 0;
-        }
         Ok(())
     }
 }
@@ -1,13 +1,15 @@
 use super::{CommandHandler, GameEventCollector, HandlerError, HandlerResult};
-use crate::executor::command_handlers::jump::JumpResetCommandHandler;
+use crate::executor::command_handlers::weather::weather_move_speed;
 use crate::simulation::physics_state::PhysicsState;
 use crate::Recipients;
+use common::configs::game_config::ConfigGame;
 use common::configs::physics_config::ConfigPhysics;
 use common::core::action_states::ActionState;
 use common::core::command::MoveDirection;
 use common::core::events::{GameEvent, SoundSpec};
-use common::core::powerup_system::{OtherEffects, StatusEffect};
+use common::core::powerup_system::{PowerUpEffects, StatusEffect};
 use common::core::states::GameState;
+use common::core::status_effects::Stat;
 use derive_more::Constructor;
 use nalgebra::UnitQuaternion;
 use nalgebra_glm::Vec3;
@@ -18,6 +20,7 @@ pub struct MoveCommandHandler {
     player_id: u32,
     direction: MoveDirection,
     physics_config: ConfigPhysics,
+    game_config: ConfigGame,
 }
 
 impl CommandHandler for MoveCommandHandler {
@@ -27,8 +30,6 @@ impl CommandHandler for MoveCommandHandler {
         physics_state: &mut PhysicsState,
         game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        super::handle_invincible_players(game_state, physics_state, self.player_id);
-
         // Physics state
         if self.direction.eq(&MoveDirection::zeros()) {
             return Ok(());
@@ -37,6 +38,7 @@ impl CommandHandler for MoveCommandHandler {
         // normalize the direction vector
         let dir_vec = self.direction.normalize();
         let gs_clone = game_state.clone();
+        let weather = game_state.world.weather;
         let player_state = game_state
             .player_mut(self.player_id)
             .ok_or_else(|| HandlerError::new(format!("Player {} not found", self.player_id)))?;
@@ -46,66 +48,53 @@ impl CommandHandler for MoveCommandHandler {
             return Ok(());
         }
 
-        // if player is stunned
-        if player_state.holds_status_effect_mut(StatusEffect::Other(OtherEffects::Stun))
-            || player_state
-                .holds_status_effect_mut(StatusEffect::Other(OtherEffects::MovementDisabled))
+        // if player is stunned or can't move, invincible players are not slowed by the weather
+        let base_move_speed = if player_state
+            .holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible))
         {
+            1.0
+        } else {
+            weather_move_speed(&weather, &self.game_config.weather_config)
+        };
+        let move_speed = player_state.stat(Stat::MoveSpeed, base_move_speed, &self.game_config);
+        if move_speed <= 0.0 {
             return Ok(());
         }
 
         // TODO: Need to figure out how invincibility would fit in here
 
         // rotate the direction vector to face the camera (only take the x and z components)
-        let dt = physics_state.dt();
         let camera_forward = Vec3::new(
             player_state.camera_forward.x,
             0.0,
             player_state.camera_forward.z,
         );
 
-        let player_rigid_body = physics_state
-            .get_entity_rigid_body_mut(self.player_id)
-            .unwrap();
-
         let rotation = UnitQuaternion::face_towards(&camera_forward, &Vec3::y());
         let dir_rotation = UnitQuaternion::face_towards(&dir_vec, &Vec3::y());
 
         let player_rotation = rotation * dir_rotation;
 
-        // apply the rotation to the direction vector
-
         // rotate by just setting the rotation
         // player_rigid_body.set_rotation(rotation, true);
 
-        // rotate by applying a torque impulse
-        // (does not guarantee the rotation will be reached, but it will eventually converge to the desired rotation)
-
-        // Step 1: Calculate the angular displacement required to reach the desired rotation
-        let rotation_difference = player_rotation * player_rigid_body.rotation().inverse();
-
-        // Step 2: Divide the angular displacement by dt to get the desired angular velocity
-        let desired_angular_velocity = rotation_difference.scaled_axis() / dt;
-
-        // Step 3: Calculate the difference between the current and desired angular velocities
-
-        player_rigid_body.set_angular_damping(self.physics_config.movement_config.damping);
-        let current_angular_velocity = player_rigid_body.angvel();
-        let angular_velocity_difference = desired_angular_velocity - current_angular_velocity;
-
-        // Step 4: Calculate the required torque using the gain factor
-        let required_torque =
-            angular_velocity_difference * self.physics_config.movement_config.gain;
-
-        // Step 5: Apply the torque to the player's rigid body
-        player_rigid_body.apply_torque_impulse(required_torque, true);
-
-        let dir_vec = rotation * dir_vec;
-        physics_state.move_character_with_velocity(
+        physics_state.rotate_character(
             self.player_id,
-            dir_vec * self.physics_config.movement_config.step_size,
+            player_rotation,
+            self.physics_config.movement_config.gain,
+            self.physics_config.movement_config.damping,
         );
 
+        let dir_vec = rotation * dir_vec;
+        let step_size = if player_state.ground.on_ground {
+            self.physics_config.movement_config.step_size
+        } else {
+            self.physics_config.movement_config.step_size
+                * self.physics_config.movement_config.air_control
+        };
+        physics_state
+            .move_character_with_velocity(self.player_id, dir_vec * step_size * move_speed);
+
         let action_state = player_state
                 .active_action_states
                 .iter()
@@ -135,13 +124,6 @@ impl CommandHandler for MoveCommandHandler {
             Duration::from_secs_f32(self.physics_config.movement_config.walking_cooldown),
         ));
 
-        // reset jump if player is on the ground
-        JumpResetCommandHandler::new(self.player_id).handle(
-            game_state,
-            physics_state,
-            game_events,
-        )?;
-
         Ok(())
     }
 }
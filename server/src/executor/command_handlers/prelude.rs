@@ -1,12 +1,17 @@
 pub use super::area_attack::AreaAttackCommandHandler;
 pub use super::attack::AttackCommandHandler;
 pub use super::cast_powerup::CastPowerUpCommandHandler;
+pub use super::charge_attack::ChargeAttackCommandHandler;
 pub use super::cheat_code::CheatCodeCommandHandler;
 pub use super::cheat_code_control::CheatCodeControlCommandHandler;
+pub use super::contacts::ContactCommandHandler;
 pub use super::die::DieCommandHandler;
 pub use super::give_powerup::GivePowerUpCommandHandler;
+pub use super::ground::GroundCommandHandler;
+pub use super::hazards::HazardCommandHandler;
 pub use super::jump::JumpCommandHandler;
 pub use super::movement::MoveCommandHandler;
+pub use super::projectiles::ProjectileCommandHandler;
 pub use super::refill::RefillCommandHandler;
 pub use super::spawn::SpawnCommandHandler;
 pub use super::startup::StartupCommandHandler;
@@ -0,0 +1,29 @@
+use super::{CommandHandler, GameEventCollector, HandlerResult};
+use crate::simulation::physics_state::PhysicsState;
+use common::core::states::GameState;
+use derive_more::Constructor;
+
+/// Counts down the lifetime of the projectiles, the ones that hit nothing in time vanish.
+/// Their hits are handled with the contacts, see ContactCommandHandler.
+#[derive(Constructor)]
+pub struct ProjectileCommandHandler;
+
+impl CommandHandler for ProjectileCommandHandler {
+    fn handle(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        _: &mut dyn GameEventCollector,
+    ) -> HandlerResult {
+        let dt = physics_state.dt();
+        game_state.world.projectiles.retain(|&entity, projectile| {
+            projectile.time_left -= dt;
+            if projectile.time_left > 0.0 {
+                return true;
+            }
+            physics_state.remove_entity(entity);
+            false
+        });
+        Ok(())
+    }
+}
@@ -20,11 +20,10 @@ impl CommandHandler for RefillCommandHandler {
     fn handle(
         &self,
         game_state: &mut GameState,
-        physics_state: &mut PhysicsState,
+        _: &mut PhysicsState,
         game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        super::handle_invincible_players(game_state, physics_state, self.player_id);
-
+        let refill_point_result = game_state.refill_point(self.player_id);
         let player_state = game_state.player_mut(self.player_id).unwrap();
         // if player is dead, don't do anything
         if player_state.is_dead {
@@ -40,8 +39,6 @@ impl CommandHandler for RefillCommandHandler {
             return Ok(());
         }
 
-        let refill_point_result = player_state.is_in_refill_area(self.game_config.clone());
-
         if refill_point_result == None || player_state.command_on_cooldown(Command::Refill) {
             // signal player that he/she is not in refill area
             return Ok(());
@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use super::{CommandHandler, GameEventCollector, HandlerResult};
 use crate::Recipients;
+use crate::simulation::entity::Entity;
 use crate::simulation::physics_state::PhysicsState;
 use common::configs::game_config::ConfigGame;
 use common::core::command::Command;
@@ -52,12 +53,12 @@ impl CommandHandler for SpawnCommandHandler {
                 );
 
                 if let Some(player_rigid_body) =
-                    physics_state.get_entity_rigid_body_mut(self.player_id)
+                    physics_state.get_entity_rigid_body_mut(Entity::player(self.player_id))
                 {
                     player_rigid_body.set_enabled(true);
-                    // clear velocity of spawned player
-                    player_rigid_body.set_linvel(rapier::vector![0.0, 0.0, 0.0], true);
                 }
+                // clear velocity of spawned player
+                physics_state.set_character_linvel(self.player_id, rapier::vector![0.0, 0.0, 0.0]);
 
                 player.is_dead = false;
                 player.refill_wind_charge(
@@ -85,6 +86,7 @@ impl CommandHandler for SpawnCommandHandler {
         } else {
             let collider = geometry::ColliderBuilder::capsule_y(0.5, 0.25)
                 .mass(0.0)
+                .active_events(rapier3d::prelude::ActiveEvents::COLLISION_EVENTS)
                 .build();
 
             let rigid_body = rapier3d::prelude::RigidBodyBuilder::dynamic()
@@ -98,7 +100,7 @@ impl CommandHandler for SpawnCommandHandler {
                 ))
                 .build();
 
-            physics_state.insert_entity(self.player_id, Some(collider), Some(rigid_body));
+            physics_state.insert_character(self.player_id, collider, rigid_body);
 
             // Game state (needed because syncing is only for the physical properties of entities)
             game_state.players.insert(
@@ -115,6 +117,13 @@ impl CommandHandler for SpawnCommandHandler {
                     ..Default::default()
                 },
             );
+            // a player joining mid-weather hasn't heard it start
+            super::weather::play_ambient_sound(
+                game_events,
+                self.player_id,
+                &game_state.world.weather,
+                &game_state.players[&self.player_id],
+            );
         }
 
         // give just spawned player some invincibility
@@ -122,10 +131,12 @@ impl CommandHandler for SpawnCommandHandler {
         game_state
             .player_mut(self.player_id)
             .unwrap()
-            .status_effects
-            .insert(
+            .apply_status_effect(
                 StatusEffect::Power(PowerUpEffects::Invincible),
                 self.game_config.powerup_config.spawn_invincible_duration,
+                1.0,
+                None,
+                &self.game_config,
             );
         Ok(())
     }
@@ -1,13 +1,18 @@
 use super::{CommandHandler, GameEventCollector, HandlerError, HandlerResult};
-use crate::simulation::obj_collider::FromObject;
+use crate::simulation::baked_level::BakedLevel;
+use crate::simulation::entity::EntityKind;
+use crate::simulation::obj_collider::{flush_collider_cache, FromObject};
 use crate::simulation::physics_state::PhysicsState;
+use common::configs::game_config::{ConfigGame, ConfigZone, ConfigZoneShape};
 use common::configs::model_config::ConfigModels;
-use common::configs::scene_config::ConfigSceneGraph;
-use common::core::states::GameState;
+use common::configs::scene_config::{ConfigBehavior, ConfigNode, ConfigSceneGraph};
+use common::core::components::Transform;
+use common::core::states::{GameState, HazardState, ZoneState};
 use derive_more::Constructor;
 use itertools::Itertools;
 use nalgebra::{Isometry3, UnitQuaternion};
-use rapier3d::math::Isometry;
+use rapier3d::math::{Isometry, Real};
+use rapier3d::prelude::{ActiveEvents, Collider};
 use rapier3d::{dynamics, geometry};
 
 #[derive(Constructor)]
@@ -15,17 +20,89 @@ use rapier3d::{dynamics, geometry};
 pub struct StartupCommandHandler {
     config_models: ConfigModels,
     config_scene_graph: ConfigSceneGraph,
+    game_config: ConfigGame,
+    baked_level: Option<BakedLevel>,
 }
 
 impl CommandHandler for StartupCommandHandler {
     fn handle(
         &self,
-        _: &mut GameState,
+        game_state: &mut GameState,
         physics_state: &mut PhysicsState,
         _game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        let mut scene_entity_id = 0xBEEF; // TODO: set up a better convention for scene entities
+        for (index, (node, world_transform)) in self.scene_nodes().into_iter().enumerate() {
+            // moving nodes are driven by the hazard handler
+            let body = match node.behavior {
+                Some(ConfigBehavior::MovingPlatform { .. })
+                | Some(ConfigBehavior::CrumblingPlatform { .. }) => {
+                    dynamics::RigidBodyBuilder::kinematic_position_based()
+                }
+                _ => dynamics::RigidBodyBuilder::fixed(),
+            }
+            .position(world_transform)
+            .build();
+
+            // the baked level has the colliders ready, see the bake binary
+            let collider = match self
+                .baked_level
+                .as_ref()
+                .and_then(|baked| baked.node(index, &node.id))
+            {
+                Some(baked_node) => baked_node.collider.clone(),
+                None => self.node_collider(&node)?,
+            };
 
+            let kind = match node.behavior {
+                Some(_) => EntityKind::Hazard,
+                None => EntityKind::SceneNode,
+            };
+            // insert the collider into the physics world
+            let entity =
+                physics_state.spawn_entity(kind, Some(node.id.clone()), collider, Some(body));
+
+            if node.behavior.is_some() {
+                game_state.world.hazards.insert(
+                    node.id.clone(),
+                    HazardState::new(
+                        entity,
+                        Transform {
+                            translation: world_transform.translation.vector,
+                            rotation: world_transform.rotation.coords.into(),
+                        },
+                    ),
+                );
+            }
+        }
+
+        // zones are sensors, who is inside comes from their intersection events
+        for zone in self.game_config.zones.iter() {
+            let collider = self
+                .zone_collider(zone)?
+                .sensor(true)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .build();
+            let body = dynamics::RigidBodyBuilder::fixed()
+                .translation(zone.position)
+                .build();
+            let entity =
+                physics_state.spawn_entity(EntityKind::Zone, None, Some(collider), Some(body));
+            game_state
+                .world
+                .zones
+                .insert(entity, ZoneState::new(zone.kind, zone.position));
+        }
+
+        // the colliders built from scratch are kept for the next start
+        flush_collider_cache();
+        Ok(())
+    }
+}
+
+impl StartupCommandHandler {
+    /// The scene nodes with their world transforms, in the order they are inserted
+    pub fn scene_nodes(&self) -> Vec<(ConfigNode, Isometry<Real>)> {
+        let mut scene_nodes = Vec::new();
         let mut nodes = self
             .config_scene_graph
             .nodes
@@ -35,17 +112,6 @@ impl CommandHandler for StartupCommandHandler {
 
         while !nodes.is_empty() {
             let (node, parent_transform) = nodes.pop().unwrap();
-            let model = node.model.clone().ok_or(HandlerError::new(
-                "Config node not attaching model".to_string(),
-            ))?;
-
-            let model_config = self
-                .config_models
-                .model(model)
-                .ok_or(HandlerError::new("Model not declared".to_string()))?;
-
-            let (models, _) = tobj::load_obj(model_config.path.clone(), &tobj::GPU_LOAD_OPTIONS)
-                .map_err(|e| HandlerError::new(format!("Error loading model {:?}", e)))?;
 
             let local_transform = Isometry3::from_parts(
                 node.transform.position.into(),
@@ -54,21 +120,6 @@ impl CommandHandler for StartupCommandHandler {
 
             let world_transform = parent_transform * local_transform;
 
-            let body = dynamics::RigidBodyBuilder::fixed()
-                .position(world_transform)
-                .build();
-
-            let decompose = node.decompose.unwrap_or(false);
-
-            let collider = if !model_config.phantom.unwrap_or(false) {
-                Some(geometry::ColliderBuilder::from_object_models(models, decompose).build())
-            } else {
-                None
-            };
-
-            physics_state.insert_entity(scene_entity_id, collider, Some(body)); // insert the collider into the physics world
-            scene_entity_id += 1;
-
             // add children to nodes
             if let Some(children) = node.children.clone() {
                 nodes.extend(
@@ -77,8 +128,73 @@ impl CommandHandler for StartupCommandHandler {
                         .map(|child| (child.clone(), world_transform)),
                 );
             }
+            scene_nodes.push((node, world_transform));
         }
+        scene_nodes
+    }
 
-        Ok(())
+    /// The collider of a scene node, placed by its rigid body
+    pub fn node_collider(&self, node: &ConfigNode) -> Result<Option<Collider>, HandlerError> {
+        match node.behavior {
+            // volumes are not solid, their model (if any) is only for show
+            Some(ConfigBehavior::WindTunnel { half_extents, .. })
+            | Some(ConfigBehavior::KillVolume { half_extents }) => Ok(Some(
+                geometry::ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z)
+                    .sensor(true)
+                    .build(),
+            )),
+            _ => self.model_collider(node),
+        }
+    }
+
+    fn zone_collider(&self, zone: &ConfigZone) -> Result<geometry::ColliderBuilder, HandlerError> {
+        Ok(match zone.shape {
+            ConfigZoneShape::Cylinder {
+                radius,
+                half_height,
+            } => geometry::ColliderBuilder::cylinder(half_height, radius),
+            ConfigZoneShape::Ball { radius } => geometry::ColliderBuilder::ball(radius),
+            ConfigZoneShape::Cuboid { half_extents } => {
+                geometry::ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z)
+            }
+            ConfigZoneShape::Model { ref model } => {
+                let model_config =
+                    self.config_models
+                        .model(model.clone())
+                        .ok_or(HandlerError::new(format!(
+                            "Zone model {} not declared",
+                            model
+                        )))?;
+                let (models, _) =
+                    tobj::load_obj(model_config.path.clone(), &tobj::GPU_LOAD_OPTIONS)
+                        .map_err(|e| HandlerError::new(format!("Error loading model {:?}", e)))?;
+                // a mesh only intersects what crosses its surface, its convex parts have insides
+                geometry::ColliderBuilder::from_object_models(models, true)
+            }
+        })
+    }
+
+    fn model_collider(&self, node: &ConfigNode) -> Result<Option<Collider>, HandlerError> {
+        let model = node.model.clone().ok_or(HandlerError::new(
+            "Config node not attaching model".to_string(),
+        ))?;
+
+        let model_config = self
+            .config_models
+            .model(model)
+            .ok_or(HandlerError::new("Model not declared".to_string()))?;
+
+        if model_config.phantom.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let (models, _) = tobj::load_obj(model_config.path.clone(), &tobj::GPU_LOAD_OPTIONS)
+            .map_err(|e| HandlerError::new(format!("Error loading model {:?}", e)))?;
+
+        let decompose = node.decompose.unwrap_or(false);
+
+        Ok(Some(
+            geometry::ColliderBuilder::from_object_models(models, decompose).build(),
+        ))
     }
 }
@@ -1,17 +1,18 @@
-use common::core::events::{GameEvent, ParticleSpec, ParticleType, SoundSpec};
-use common::core::powerup_system::{OtherEffects, PowerUpEffects, StatusEffect};
+use common::configs::game_config::ConfigGame;
+use common::core::powerup_system::{PowerUpEffects, StatusEffect};
 use common::core::states::GameState;
+use common::core::status_effects::Stat;
 use derive_more::Constructor;
 
+use crate::executor::command_handlers::weather::weather_surface;
 use crate::executor::command_handlers::{CommandHandler, GameEventCollector, HandlerResult};
+use crate::simulation::entity::Entity;
 use crate::simulation::physics_state::PhysicsState;
 
-extern crate nalgebra_glm as glm;
-
-const SLIPPERY_FRICTION_DECREASE: f32 = 1.2;
-
 #[derive(Constructor)]
-pub struct StatusEffectCommandHandler {}
+pub struct StatusEffectCommandHandler {
+    game_config: ConfigGame,
+}
 
 impl CommandHandler for StatusEffectCommandHandler {
     fn handle(
@@ -20,14 +21,30 @@ impl CommandHandler for StatusEffectCommandHandler {
         physics_state: &mut PhysicsState,
         _: &mut dyn GameEventCollector,
     ) -> HandlerResult {
+        let weather_config = &self.game_config.weather_config;
         for (&player_id, player_state) in game_state.players.iter() {
-            if player_state.holds_status_effect(StatusEffect::Other(OtherEffects::Slippery)) {
-                let body = physics_state.get_entity_rigid_body_mut(player_id).unwrap();
-                body.set_linear_damping(0.);
+            // invincible players are not affected by the weather
+            let (friction, linear_damping) = if player_state
+                .holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible))
+            {
+                weather_surface(&None, weather_config)
+            } else {
+                weather_surface(&game_state.world.weather, weather_config)
+            };
+
+            let body = physics_state
+                .get_entity_rigid_body_mut(Entity::player(player_id))
+                .unwrap();
+            body.set_linear_damping(player_state.stat(
+                Stat::LinearDamping,
+                linear_damping,
+                &self.game_config,
+            ));
 
-                let collider = physics_state.get_entity_collider_mut(player_id).unwrap();
-                collider.set_friction(collider.friction() - SLIPPERY_FRICTION_DECREASE);
-            }
+            let collider = physics_state
+                .get_entity_collider_mut(Entity::player(player_id))
+                .unwrap();
+            collider.set_friction(player_state.stat(Stat::Friction, friction, &self.game_config));
         }
         Ok(())
     }
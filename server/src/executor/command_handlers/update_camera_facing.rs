@@ -15,11 +15,9 @@ impl CommandHandler for UpdateCameraFacingCommandHandler {
     fn handle(
         &self,
         game_state: &mut GameState,
-        physics_state: &mut PhysicsState,
+        _: &mut PhysicsState,
         _: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        super::handle_invincible_players(game_state, physics_state, self.player_id);
-
         // Game state
         let player_state = game_state
             .player_mut(self.player_id)
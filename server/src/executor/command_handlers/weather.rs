@@ -1,11 +1,16 @@
+use common::configs::game_config::{ConfigGame, ConfigWeather, ConfigWeatherState};
+use common::core::action_states::ActionState;
 use common::core::events::{GameEvent, ParticleSpec, ParticleType, SoundSpec};
-use common::core::powerup_system::{PowerUpEffects, StatusEffect};
-use common::core::states::GameState;
-use common::core::weather::Weather;
+use common::core::powerup_system::{OtherEffects, PowerUpEffects, StatusEffect};
+use common::core::states::{GameState, PlayerState};
+use common::core::status_effects::Stat;
+use common::core::weather::{LightningStrike, Weather, WeatherKind};
 use derive_more::Constructor;
+use itertools::Itertools;
 use nalgebra::vector;
 use rand::prelude::*;
 use rapier3d::math::Vector;
+use std::time::Duration;
 
 use crate::executor::command_handlers::{CommandHandler, GameEventCollector, HandlerResult};
 use crate::game_loop::TICK_RATE;
@@ -15,93 +20,154 @@ use crate::Recipients;
 extern crate nalgebra_glm as glm;
 
 pub trait MarkovState<T> {
-    fn next(&self) -> T;
+    fn next<R: Rng + ?Sized>(&self, weather_config: &ConfigWeather, rng: &mut R) -> T;
 }
 
-// Constants for fraction of visits in long term (derived from limiting distribution)
-const RAIN_FRACTION: f64 = 0.3;
-const WIND_FRACTION: f64 = 0.2;
-const NONE_FRACTION: f64 = 0.5;
+// ambient sound of every weather, only the current weather's one keeps playing
+const AMBIENT_SOUNDS: [&str; 5] = [
+    "rain",
+    "wind_weather",
+    "snow_weather",
+    "fog_weather",
+    "storm_weather",
+];
 
-#[allow(clippy::assertions_on_constants)]
-const _: () = assert!(RAIN_FRACTION + WIND_FRACTION + NONE_FRACTION == 1.0);
+const DEFAULT_FRICTION: f32 = 1.0;
+const DEFAULT_LINEAR_DAMPING: f32 = 0.5;
 
-// ticks of the effects
-const RAIN_TICKS: f64 = 20. * TICK_RATE as f64;
-// on average, it will rain every 10 seconds
-const WIND_TICKS: f64 = 10. * TICK_RATE as f64;
+/// Friction and linear damping of a player before status effects, rain makes the ground slippery
+pub fn weather_surface(weather: &Option<Weather>, weather_config: &ConfigWeather) -> (f32, f32) {
+    match weather {
+        Some(Weather::Rainy) => (weather_config.rainy_friction, 0.0),
+        _ => (DEFAULT_FRICTION, DEFAULT_LINEAR_DAMPING),
+    }
+}
 
-const WIND_FORCE_MAGNITUDE: f32 = 128.0;
+/// Move speed of a player before status effects, snow slows everyone down
+pub fn weather_move_speed(weather: &Option<Weather>, weather_config: &ConfigWeather) -> f32 {
+    match weather {
+        Some(Weather::Snowy) => weather_config.snow_move_speed_scale,
+        _ => 1.0,
+    }
+}
+
+/// Attack range of a player before status effects, fog shortens it
+pub fn weather_attack_range(weather: &Option<Weather>, weather_config: &ConfigWeather) -> f32 {
+    match weather {
+        Some(Weather::Foggy) => weather_config.fog_attack_range_scale,
+        _ => 1.0,
+    }
+}
 
-const RAINY_FRICTION: f32 = -0.2;
+/// Builds a weather of the given kind, drawing its parameters from the config
+fn make_weather<R: Rng + ?Sized>(
+    kind: WeatherKind,
+    weather_config: &ConfigWeather,
+    rng: &mut R,
+) -> Weather {
+    match kind {
+        WeatherKind::Rainy => Weather::Rainy,
+        WeatherKind::Windy => Weather::Windy(weather_config.random_wind(rng)),
+        WeatherKind::Snowy => Weather::Snowy,
+        WeatherKind::Foggy => Weather::Foggy,
+        WeatherKind::Stormy => Weather::Stormy,
+    }
+}
 
-/// Modeling weather as a Markov process
+/// Modeling weather as a Markov process, the chain in the config gives the fraction of time
+/// spent in each weather in the long run and how long it lasts on average
 impl MarkovState<Option<Weather>> for Option<Weather> {
-    fn next(&self) -> Option<Weather> {
-        let mut rng = thread_rng();
+    fn next<R: Rng + ?Sized>(
+        &self,
+        weather_config: &ConfigWeather,
+        rng: &mut R,
+    ) -> Option<Weather> {
+        let chain = &weather_config.chain;
         let random_number: f64 = rng.gen(); // Generate a random number between 0 and 1
+        let ticks = |state: &ConfigWeatherState| state.mean_duration * TICK_RATE as f64;
 
         match self {
-            Some(Weather::Rainy) => {
-                if random_number > 1. / RAIN_TICKS {
-                    *self
-                } else {
-                    None
-                }
-            }
-            Some(Weather::Windy(_)) => {
-                if random_number > 1. / WIND_TICKS {
+            Some(weather) => {
+                let Some(state) = chain.states.iter().find(|s| s.weather == weather.kind()) else {
+                    // not in the chain anymore (e.g. set by a cheat key), leave it as is
+                    return *self;
+                };
+                // every weather ends on average after its ticks
+                if random_number > 1. / ticks(state) {
                     *self
                 } else {
                     None
                 }
             }
             None => {
-                const TO_RAINY: f64 = RAIN_FRACTION / (NONE_FRACTION * RAIN_TICKS);
-                const TO_WINDY: f64 = WIND_FRACTION / (NONE_FRACTION * WIND_TICKS);
-
-                if random_number < TO_RAINY {
-                    Some(Weather::Rainy)
-                } else if random_number < TO_RAINY + TO_WINDY {
-                    let wind_dir = thread_rng().gen_range(0.0..2.0 * std::f32::consts::PI);
-                    let wind_dir = vector![wind_dir.cos(), 0.0, wind_dir.sin()];
-
-                    Some(Weather::Windy(wind_dir))
-                } else {
-                    // stay the same
-                    None
+                // leaves no weather at a rate keeping the fractions of the long run
+                let mut threshold = 0.0;
+                for state in chain.states.iter() {
+                    threshold += state.fraction / (chain.no_weather_fraction * ticks(state));
+                    if random_number < threshold {
+                        return Some(make_weather(state.weather, weather_config, rng));
+                    }
                 }
+                // stay the same
+                None
             }
         }
     }
 }
 
-const WEATHER_START_DELAY: u64 = 60 * TICK_RATE;
-
 #[derive(Constructor)]
 /// Handles the command to start the weather
-pub struct UpdateWeatherCommandHandler {}
+pub struct UpdateWeatherCommandHandler {
+    game_config: ConfigGame,
+}
 
 impl CommandHandler for UpdateWeatherCommandHandler {
     fn handle(
         &self,
         game_state: &mut GameState,
-        _: &mut PhysicsState,
-        _: &mut dyn GameEventCollector,
+        physics_state: &mut PhysicsState,
+        game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        // don't do anything for the fist 1 min
-        if game_state.life_cycle_state.unwrap_running() < WEATHER_START_DELAY {
-            return Ok(());
-        }
+        let weather_config = &self.game_config.weather_config;
+        let tick = game_state.life_cycle_state.unwrap_running();
         game_state.world.prev_weather = game_state.world.weather;
-        game_state.world.weather = game_state.world.weather.next();
+
+        if let Some(timeline) = &weather_config.timeline {
+            // the latest entry that started, even if its tick was skipped
+            let entry = timeline
+                .iter()
+                .rev()
+                .find(|entry| (entry.start * TICK_RATE as f32) as u64 <= tick);
+            // scripted weather only changes when an entry of another weather takes over
+            if let Some(entry) = entry {
+                if game_state.world.weather.map(|weather| weather.kind()) != entry.weather {
+                    game_state.world.weather = entry
+                        .weather
+                        .map(|kind| make_weather(kind, weather_config, &mut physics_state.rng));
+                }
+            }
+        } else if tick >= (weather_config.start_delay * TICK_RATE as f32) as u64 {
+            game_state.world.weather = game_state
+                .world
+                .weather
+                .next(weather_config, &mut physics_state.rng);
+        }
+
+        if game_state.world.weather != game_state.world.prev_weather {
+            game_events.add(
+                GameEvent::WeatherChanged(game_state.world.weather),
+                Recipients::All,
+            );
+        }
 
         Ok(())
     }
 }
 
 #[derive(Constructor)]
-pub struct WeatherEffectCommandHandler {}
+pub struct WeatherEffectCommandHandler {
+    game_config: ConfigGame,
+}
 
 impl CommandHandler for WeatherEffectCommandHandler {
     fn handle(
@@ -110,6 +176,23 @@ impl CommandHandler for WeatherEffectCommandHandler {
         physics_state: &mut PhysicsState,
         game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
+        // strikes still to come end with the storm
+        if game_state.world.weather != Some(Weather::Stormy) {
+            game_state.world.lightning_strikes.clear();
+        }
+
+        // the client keeps an ambient sound looping, it only needs to hear about a change
+        if game_state.world.weather != game_state.world.prev_weather {
+            for (&player_id, player_state) in game_state.players.iter() {
+                play_ambient_sound(
+                    game_events,
+                    player_id,
+                    &game_state.world.weather,
+                    player_state,
+                );
+            }
+        }
+
         match game_state.world.weather {
             Some(Weather::Rainy) => {
                 self.handle_rainy_weather(game_state, physics_state, game_events)
@@ -117,6 +200,15 @@ impl CommandHandler for WeatherEffectCommandHandler {
             Some(Weather::Windy(_)) => {
                 self.handle_windy_weather(game_state, physics_state, game_events)
             }
+            Some(Weather::Snowy) => {
+                self.handle_snowy_weather(game_state, physics_state, game_events)
+            }
+            Some(Weather::Foggy) => {
+                self.handle_foggy_weather(game_state, physics_state, game_events)
+            }
+            Some(Weather::Stormy) => {
+                self.handle_stormy_weather(game_state, physics_state, game_events)
+            }
             None => self.handle_reset_weather(game_state, physics_state, game_events),
         }
     }
@@ -129,8 +221,10 @@ impl WeatherEffectCommandHandler {
         physics_state: &mut PhysicsState,
         game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        // reduce friction for every player
+        // friction is reduced by the status effect handler, see weather_surface
         for (&player_id, player_state) in game_state.players.iter() {
+            super::reset_weather(physics_state, player_id);
+
             // add rain particles every one second
             if game_state.life_cycle_state.unwrap_running() % TICK_RATE == 0 {
                 game_events.add(
@@ -145,44 +239,6 @@ impl WeatherEffectCommandHandler {
                     Recipients::One(player_id as u8),
                 )
             }
-
-            // if std::mem::discriminant(&game_state.world.prev_weather) != std::mem::discriminant(&game_state.world.weather) {
-
-                // TODO: change to actual sound event
-                game_events.add(
-                    GameEvent::SoundEvent(SoundSpec::new(
-                        player_state.transform.translation,
-                        "rain".to_string(),
-                        (0, false),
-                        (true, true, true),
-                        player_state.camera_forward,
-                    )),
-                    Recipients::One(player_id as u8),
-                );
-
-                game_events.add(
-                    // to stop rain sound
-                    GameEvent::SoundEvent(SoundSpec::new(
-                        glm::Vec3::new(0.0, 0.0, 0.0),
-                        "wind_weather".to_string(),
-                        (0, false),
-                        (true, false, true),
-                        glm::Vec3::new(0.0,0.0,0.0),
-                    )),
-                    Recipients::One(player_id as u8),
-                );
-            // }
-
-            if player_state.holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible)) {
-                super::reset_weather(physics_state, player_id);
-                continue;
-            }
-
-            let body = physics_state.get_entity_rigid_body_mut(player_id).unwrap();
-            body.set_linear_damping(0.);
-
-            let collider = physics_state.get_entity_collider_mut(player_id).unwrap();
-            collider.set_friction(RAINY_FRICTION);
         }
         Ok(())
     }
@@ -193,10 +249,11 @@ impl WeatherEffectCommandHandler {
         physics_state: &mut PhysicsState,
         game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        let wind_dir = match game_state.world.weather {
-            Some(Weather::Windy(wind_dir)) => wind_dir,
+        let wind = match game_state.world.weather {
+            Some(Weather::Windy(wind)) => wind,
             _ => return Ok(()),
         };
+        let wind_dir = wind.normalize();
         for (&player_id, player_state) in game_state.players.iter() {
             // add wind particles every one second
             if game_state.life_cycle_state.unwrap_running() % TICK_RATE == 0 {
@@ -212,83 +269,433 @@ impl WeatherEffectCommandHandler {
                     Recipients::One(player_id as u8),
                 )
             }
-            
-            // if std::mem::discriminant(&game_state.world.prev_weather) != std::mem::discriminant(&game_state.world.weather) {
-                // TODO: change to actual sound event
-                // reset rain sound
+
+            if player_state.holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible)) {
+                super::reset_weather(physics_state, player_id);
+                continue;
+            }
+
+            // apply a force to the player
+            physics_state.set_character_force(player_id, wind);
+        }
+        Ok(())
+    }
+
+    fn handle_snowy_weather(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        game_events: &mut dyn GameEventCollector,
+    ) -> HandlerResult {
+        // movement is slowed by the movement handler, see weather_move_speed
+        let weather_config = &self.game_config.weather_config;
+        let every_second = game_state
+            .life_cycle_state
+            .unwrap_running()
+            .is_multiple_of(TICK_RATE);
+
+        // in a set order, for the draws to be the same when replaying a snapshot
+        for (&player_id, player_state) in game_state
+            .players
+            .iter_mut()
+            .sorted_by_key(|(&player_id, _)| player_id)
+        {
+            super::reset_weather(physics_state, player_id);
+
+            if every_second {
                 game_events.add(
-                    // to stop rain sound
-                    GameEvent::SoundEvent(SoundSpec::new(
-                        glm::Vec3::new(0.0, 0.0, 0.0),
-                        "rain".to_string(),
-                        (0, false),
-                        (true, false, true),
-                        glm::Vec3::new(0.0,0.0,0.0),
+                    GameEvent::ParticleEvent(ParticleSpec::new(
+                        ParticleType::SNOW,
+                        player_state.transform.translation,
+                        -Vector::y(),
+                        Vector::y(),
+                        glm::vec4(1.0, 1.0, 1.0, 0.9),
+                        "snow".to_string(),
                     )),
                     Recipients::One(player_id as u8),
                 );
+            }
+
+            // players standing still may freeze, checked once a second
+            if !every_second || player_state.is_dead || !is_exposed(player_state) {
+                continue;
+            }
+            let velocity = physics_state.character_linvel(player_id);
+            let horizontal_speed = glm::length(&glm::vec2(velocity.x, velocity.z));
+            if horizontal_speed < weather_config.snow_still_speed
+                && physics_state.rng.gen::<f32>() < weather_config.snow_freeze_chance
+            {
+                player_state.apply_status_effect(
+                    StatusEffect::Other(OtherEffects::Stun),
+                    weather_config.snow_freeze_duration,
+                    1.0,
+                    None,
+                    &self.game_config,
+                );
+                player_state.add_action_state(
+                    ActionState::Frozen,
+                    Duration::from_secs_f32(weather_config.snow_freeze_duration),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_foggy_weather(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        game_events: &mut dyn GameEventCollector,
+    ) -> HandlerResult {
+        // attacks are shortened by the attack handlers, see weather_attack_range
+        for (&player_id, player_state) in game_state.players.iter() {
+            super::reset_weather(physics_state, player_id);
+
+            if game_state
+                .life_cycle_state
+                .unwrap_running()
+                .is_multiple_of(TICK_RATE)
+            {
                 game_events.add(
-                    GameEvent::SoundEvent(SoundSpec::new(
+                    GameEvent::ParticleEvent(ParticleSpec::new(
+                        ParticleType::FOG,
                         player_state.transform.translation,
-                        "wind_weather".to_string(),
-                        (0, false),
-                        (true, true, true),
-                        wind_dir,
+                        player_state.camera_forward,
+                        Vector::y(),
+                        glm::vec4(0.8, 0.82, 0.85, 0.15),
+                        "fog".to_string(),
                     )),
                     Recipients::One(player_id as u8),
                 );
-            // }
-
-            if player_state.holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible)) {
-                super::reset_weather(physics_state, player_id);
-                continue;
             }
-
-            // apply a force to the player
-            let body = physics_state.get_entity_rigid_body_mut(player_id).unwrap();
-            body.reset_forces(false);
-            body.add_force(wind_dir * WIND_FORCE_MAGNITUDE, true);
         }
         Ok(())
     }
 
-    fn handle_reset_weather(
+    fn handle_stormy_weather(
         &self,
         game_state: &mut GameState,
         physics_state: &mut PhysicsState,
         game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
-        // reset friction for every player
-        for (&player_id, _) in game_state.players.iter() {
+        let weather_config = &self.game_config.weather_config;
+        let tick = game_state.life_cycle_state.unwrap_running();
+
+        for (&player_id, player_state) in game_state.players.iter() {
             super::reset_weather(physics_state, player_id);
 
-            // if std::mem::discriminant(&game_state.world.prev_weather) != std::mem::discriminant(&game_state.world.weather) {
-                // TODO: change to actual sound event
-                // reset rain sound
+            if tick.is_multiple_of(TICK_RATE) {
                 game_events.add(
-                    // to stop rain sound
-                    GameEvent::SoundEvent(SoundSpec::new(
-                        glm::Vec3::new(0.0, 0.0, 0.0),
-                        "rain".to_string(),
-                        (0, false),
-                        (true, false, true),
-                        glm::Vec3::new(0.0,0.0,0.0),
+                    GameEvent::ParticleEvent(ParticleSpec::new(
+                        ParticleType::RAIN,
+                        player_state.transform.translation,
+                        -Vector::y(),
+                        Vector::y(),
+                        glm::vec4(0.7, 0.7, 0.8, 0.8),
+                        "storm".to_string(),
                     )),
                     Recipients::One(player_id as u8),
                 );
+            }
+        }
+
+        // telegraph a strike around a random player
+        let interval_ticks = ((weather_config.lightning_interval * TICK_RATE as f32) as u64).max(1);
+        if tick.is_multiple_of(interval_ticks) {
+            let rng = &mut physics_state.rng;
+            let target = game_state
+                .players
+                .values()
+                .filter(|p| !p.is_dead)
+                .sorted_by_key(|p| p.id)
+                .choose(rng)
+                .map(|p| p.transform.translation);
+            if let Some(target) = target {
+                let angle = rng.gen_range(0.0..2.0 * std::f32::consts::PI);
+                // uniform over the disc around the target
+                let dist = weather_config.lightning_scatter * rng.gen::<f32>().sqrt();
+                let position = target + vector![angle.cos() * dist, 0.0, angle.sin() * dist];
+
+                game_state.world.lightning_strikes.push(LightningStrike {
+                    position,
+                    time_left: weather_config.lightning_warning_time,
+                });
+                game_events.add(
+                    GameEvent::ParticleEvent(ParticleSpec::new(
+                        ParticleType::TELEGRAPH,
+                        position,
+                        Vector::y(),
+                        Vector::y(),
+                        glm::vec4(1.0, 0.9, 0.4, 0.8),
+                        "lightning warning".to_string(),
+                    )),
+                    Recipients::All,
+                );
                 game_events.add(
-                    // to stop rain sound
                     GameEvent::SoundEvent(SoundSpec::new(
-                        glm::Vec3::new(0.0, 0.0, 0.0),
-                        "wind_weather".to_string(),
+                        position,
+                        "spawn_beep".to_string(),
                         (0, false),
-                        (true, false, true),
-                        glm::Vec3::new(0.0,0.0,0.0),
+                        (false, false, false),
+                        Vector::zeros(),
                     )),
-                    Recipients::One(player_id as u8),
+                    Recipients::All,
                 );
-            // }
+            }
+        }
+
+        let dt = physics_state.dt();
+        for strike in game_state.world.lightning_strikes.iter_mut() {
+            strike.time_left -= dt;
+        }
+        let (strikes, pending): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut game_state.world.lightning_strikes)
+                .into_iter()
+                .partition(|strike| strike.time_left <= 0.0);
+        game_state.world.lightning_strikes = pending;
+
+        for strike in strikes {
+            self.strike_lightning(strike.position, game_state, physics_state, game_events);
+        }
+        Ok(())
+    }
+
+    // knocks back every exposed player in the strike's radius, harder closer to its center
+    fn strike_lightning(
+        &self,
+        position: glm::Vec3,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        game_events: &mut dyn GameEventCollector,
+    ) {
+        let weather_config = &self.game_config.weather_config;
+
+        for (&player_id, player_state) in game_state.players.iter_mut() {
+            if player_state.is_dead || !is_exposed(player_state) {
+                continue;
+            }
+            let offset = player_state.transform.translation - position;
+            let horizontal_offset = glm::vec3(offset.x, 0.0, offset.z);
+            let dist = glm::length(&horizontal_offset);
+            if dist > weather_config.lightning_radius {
+                continue;
+            }
+
+            let away = if dist > f32::EPSILON {
+                horizontal_offset / dist
+            } else {
+                Vector::zeros()
+            };
+            let strength = weather_config.lightning_impulse
+                * (1.0 - dist / weather_config.lightning_radius)
+                * player_state.stat(Stat::KnockbackTaken, 1.0, &self.game_config);
+            let impulse = glm::normalize(&(away + Vector::y())) * strength;
+
+            player_state.apply_status_effect(
+                StatusEffect::Other(OtherEffects::MovementDisabled),
+                weather_config.lightning_stun_duration,
+                1.0,
+                None,
+                &self.game_config,
+            );
+            physics_state.set_character_linvel(player_id, vector![0.0, 0.0, 0.0]);
+            physics_state.apply_character_impulse(player_id, impulse);
+        }
+
+        game_events.add(
+            GameEvent::ParticleEvent(ParticleSpec::new(
+                ParticleType::LIGHTNING,
+                position,
+                Vector::y(),
+                Vector::y(),
+                glm::vec4(0.9, 0.95, 1.0, 1.0),
+                "lightning".to_string(),
+            )),
+            Recipients::All,
+        );
+        game_events.add(
+            GameEvent::SoundEvent(SoundSpec::new(
+                position,
+                "thunder".to_string(),
+                (0, false),
+                (false, false, false),
+                Vector::zeros(),
+            )),
+            Recipients::All,
+        );
+        game_events.add(GameEvent::LightningStruck(position), Recipients::All);
+    }
+
+    fn handle_reset_weather(
+        &self,
+        game_state: &mut GameState,
+        physics_state: &mut PhysicsState,
+        _game_events: &mut dyn GameEventCollector,
+    ) -> HandlerResult {
+        // reset friction for every player
+        for (&player_id, _) in game_state.players.iter() {
+            super::reset_weather(physics_state, player_id);
         }
         Ok(())
     }
 }
+
+// invincible players are not affected by the weather
+fn is_exposed(player_state: &PlayerState) -> bool {
+    !player_state.holds_status_effect(StatusEffect::Power(PowerUpEffects::Invincible))
+}
+
+/// Starts the ambient sound of the weather for a player and stops all the others
+pub(super) fn play_ambient_sound(
+    game_events: &mut dyn GameEventCollector,
+    player_id: u32,
+    weather: &Option<Weather>,
+    player_state: &PlayerState,
+) {
+    let (playing, direction) = match weather {
+        Some(Weather::Rainy) => (Some("rain"), player_state.camera_forward),
+        Some(Weather::Windy(wind)) => (Some("wind_weather"), wind.normalize()),
+        Some(Weather::Snowy) => (Some("snow_weather"), player_state.camera_forward),
+        Some(Weather::Foggy) => (Some("fog_weather"), player_state.camera_forward),
+        Some(Weather::Stormy) => (Some("storm_weather"), player_state.camera_forward),
+        None => (None, Vector::zeros()),
+    };
+    // TODO: change to actual sound event
+    for sound in AMBIENT_SOUNDS {
+        let event = if playing == Some(sound) {
+            SoundSpec::new(
+                player_state.transform.translation,
+                sound.to_string(),
+                (0, false),
+                (true, true, true),
+                direction,
+            )
+        } else {
+            SoundSpec::new(
+                glm::Vec3::new(0.0, 0.0, 0.0),
+                sound.to_string(),
+                (0, false),
+                (true, false, true),
+                glm::Vec3::new(0.0, 0.0, 0.0),
+            )
+        };
+        game_events.add(
+            GameEvent::SoundEvent(event),
+            Recipients::One(player_id as u8),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::entity::Entity;
+    use common::configs::from_file;
+    use common::configs::game_config::ConfigScriptedWeather;
+    use common::core::states::GameLifeCycleState;
+    use rapier3d::prelude::{ColliderBuilder, RigidBodyBuilder};
+    use std::cell::RefCell;
+
+    const PLAYER: u32 = 1;
+
+    fn setup() -> (ConfigGame, GameState, PhysicsState) {
+        let game_config: ConfigGame = from_file("../game.json").unwrap();
+        let mut game_state = GameState::new();
+        game_state.life_cycle_state = GameLifeCycleState::Running(1);
+        game_state.players.insert(
+            PLAYER,
+            PlayerState {
+                id: PLAYER,
+                ..Default::default()
+            },
+        );
+        let mut physics_state = PhysicsState::new();
+        let body = RigidBodyBuilder::dynamic().build();
+        let collider = ColliderBuilder::capsule_y(0.5, 0.25).build();
+        physics_state.insert_entity(Entity::player(PLAYER), Some(collider), Some(body));
+        (game_config, game_state, physics_state)
+    }
+
+    fn sound_events(game_events: &[(GameEvent, Recipients)]) -> usize {
+        game_events
+            .iter()
+            .filter(|(event, _)| matches!(event, GameEvent::SoundEvent(_)))
+            .count()
+    }
+
+    #[test]
+    fn test_ambient_sound_only_on_weather_change() {
+        let (game_config, mut game_state, mut physics_state) = setup();
+        let handler = WeatherEffectCommandHandler::new(game_config);
+        game_state.world.weather = Some(Weather::Rainy);
+
+        let game_events = RefCell::new(Vec::new());
+        handler
+            .handle(
+                &mut game_state,
+                &mut physics_state,
+                &mut game_events.borrow_mut(),
+            )
+            .unwrap();
+        assert_eq!(sound_events(&game_events.borrow()), AMBIENT_SOUNDS.len());
+
+        game_state.world.prev_weather = game_state.world.weather;
+        let game_events = RefCell::new(Vec::new());
+        handler
+            .handle(
+                &mut game_state,
+                &mut physics_state,
+                &mut game_events.borrow_mut(),
+            )
+            .unwrap();
+        assert_eq!(sound_events(&game_events.borrow()), 0);
+    }
+
+    #[test]
+    fn test_rain_after_wind_stops_the_wind() {
+        let (mut game_config, mut game_state, mut physics_state) = setup();
+        game_config.weather_config.timeline = Some(vec![
+            ConfigScriptedWeather {
+                start: 0.0,
+                weather: Some(WeatherKind::Windy),
+            },
+            ConfigScriptedWeather {
+                start: 1.0,
+                weather: Some(WeatherKind::Rainy),
+            },
+        ]);
+        let update = UpdateWeatherCommandHandler::new(game_config.clone());
+        let effect = WeatherEffectCommandHandler::new(game_config);
+        let force = |physics_state: &PhysicsState| {
+            physics_state
+                .get_entity_rigid_body(Entity::player(PLAYER))
+                .unwrap()
+                .user_force()
+        };
+
+        for tick in [1, TICK_RATE] {
+            game_state.life_cycle_state = GameLifeCycleState::Running(tick);
+            let game_events = RefCell::new(Vec::new());
+            update
+                .handle(
+                    &mut game_state,
+                    &mut physics_state,
+                    &mut game_events.borrow_mut(),
+                )
+                .unwrap();
+            effect
+                .handle(
+                    &mut game_state,
+                    &mut physics_state,
+                    &mut game_events.borrow_mut(),
+                )
+                .unwrap();
+            if tick == 1 {
+                assert!(force(&physics_state).norm() > 0.0);
+            }
+        }
+
+        assert_eq!(game_state.world.weather, Some(Weather::Rainy));
+        assert_eq!(force(&physics_state), Vector::zeros());
+    }
+}
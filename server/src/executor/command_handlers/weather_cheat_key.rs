@@ -2,17 +2,19 @@ use crate::executor::command_handlers::{
     CommandHandler, GameEventCollector, HandlerError, HandlerResult,
 };
 use crate::simulation::physics_state::PhysicsState;
+use crate::Recipients;
+use common::configs::game_config::ConfigGame;
 use common::core::command::{CheatCodeControl, CheatKeyWeather};
+use common::core::events::GameEvent;
 use common::core::states::GameState;
 use common::core::weather::Weather;
 use derive_more::Constructor;
-use rand::{thread_rng, Rng};
-use rapier3d::prelude::vector;
 
 #[derive(Constructor)]
 pub struct WeatherCheatKeyCommandHandler {
     player_id: u32,
     weather: CheatKeyWeather,
+    game_config: ConfigGame,
 }
 
 impl CommandHandler for WeatherCheatKeyCommandHandler {
@@ -20,7 +22,7 @@ impl CommandHandler for WeatherCheatKeyCommandHandler {
         &self,
         game_state: &mut GameState,
         physics_state: &mut PhysicsState,
-        _: &mut dyn GameEventCollector,
+        game_events: &mut dyn GameEventCollector,
     ) -> HandlerResult {
         let player_state = game_state
             .player(self.player_id)
@@ -39,14 +41,29 @@ impl CommandHandler for WeatherCheatKeyCommandHandler {
                 game_state.world.weather = Some(Weather::Rainy);
             }
             CheatKeyWeather::Wind => {
-                let wind_dir = thread_rng().gen_range(0.0..2.0 * std::f32::consts::PI);
-                let wind_dir = vector![wind_dir.cos(), 0.0, wind_dir.sin()];
-                game_state.world.weather = Some(Weather::Windy(wind_dir));
+                let wind = self
+                    .game_config
+                    .weather_config
+                    .random_wind(&mut physics_state.rng);
+                game_state.world.weather = Some(Weather::Windy(wind));
+            }
+            CheatKeyWeather::Snow => {
+                game_state.world.weather = Some(Weather::Snowy);
+            }
+            CheatKeyWeather::Fog => {
+                game_state.world.weather = Some(Weather::Foggy);
+            }
+            CheatKeyWeather::Storm => {
+                game_state.world.weather = Some(Weather::Stormy);
             }
             CheatKeyWeather::Reset => {
                 game_state.world.weather = None;
             }
         }
+        game_events.add(
+            GameEvent::WeatherChanged(game_state.world.weather),
+            Recipients::All,
+        );
         Ok(())
     }
 }
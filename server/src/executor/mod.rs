@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -6,20 +7,28 @@ use itertools::Itertools;
 use log::{debug, error, info, warn};
 
 use command_handlers::prelude::*;
+use common::configs::scene_config::ConfigBehavior;
 use common::configs::*;
 use common::core::command::{Command, MoveDirection, ServerSync};
 use common::core::events::{GameEvent, SoundSpec};
+use common::core::physics_debug::PHYSICS_DEBUG_PERIOD;
 use common::core::states::GameLifeCycleState::{Ended, Running, Waiting};
-use common::core::states::GameState;
+use common::core::states::{GameState, MatchClock};
+use common::core::stats::MatchSummary;
 
 use crate::game_loop::ClientCommand;
+use crate::simulation::baked_level::{BakedLevel, BAKED_LEVEL_PATH};
+use crate::simulation::entity::{Entity, EntityKind};
 use crate::simulation::physics_state::PhysicsState;
 use crate::Recipients;
 
 pub mod command_handlers;
-use crate::executor::command_handlers::jump::JumpResetCommandHandler;
+pub mod snapshot;
 use nalgebra_glm as glm;
 pub const DEFAULT_RESPAWN_LIMIT: f32 = -20.0;
+pub const MATCH_SUMMARY_DIR: &str = "match_summaries";
+// weight of a new latency measurement against the previous ones
+const LATENCY_SMOOTHING: f32 = 0.25;
 
 // 5ms
 /// Executor is a struct that is used to execute a command issued by a client.
@@ -29,32 +38,69 @@ pub struct Executor {
     game_state: Arc<Mutex<GameState>>,
     physics_state: RefCell<PhysicsState>,
     game_events: RefCell<Vec<(GameEvent, Recipients)>>,
-    config_instance: Arc<Config>,
+    config_instance: RefCell<Arc<Config>>, // switched to a reloaded one between two ticks
+    hazard_behaviors: Arc<HashMap<String, ConfigBehavior>>, // the scene is never reloaded
     ready_players: RefCell<Vec<u32>>,
     spawn_command_pushed: RefCell<bool>,
+    flag_contested: RefCell<bool>,
+    latencies: RefCell<HashMap<u32, f32>>, // round trip to each client in seconds
+    debug_subscribers: RefCell<Vec<u32>>,  // clients streamed the physics debug frames
+    debug_timer: RefCell<f32>,             // since the last physics debug frame
 }
 
 impl Executor {
     /// Creates a new Executor with default game state.
     pub fn new(game_state: Arc<Mutex<GameState>>) -> Executor {
+        let config_instance = ConfigurationManager::get_configuration();
+        let mut physics_state = PhysicsState::new();
+        physics_state.configure_characters(&config_instance.physics.character_controller);
         Executor {
             game_state,
-            physics_state: RefCell::new(PhysicsState::new()),
+            physics_state: RefCell::new(physics_state),
             game_events: RefCell::new(Vec::new()),
-            config_instance: ConfigurationManager::get_configuration(),
+            hazard_behaviors: Arc::new(config_instance.scene.behaviors()),
+            config_instance: RefCell::new(config_instance),
             ready_players: RefCell::new(Vec::new()),
             spawn_command_pushed: RefCell::new(false),
+            flag_contested: RefCell::new(false),
+            latencies: RefCell::new(HashMap::new()),
+            debug_subscribers: RefCell::new(Vec::new()),
+            debug_timer: RefCell::new(0.0),
         }
     }
 
+    fn config(&self) -> Arc<Config> {
+        self.config_instance.borrow().clone()
+    }
+
+    /// Switches to a reloaded configuration, see config_watcher.
+    /// The scene and the models are not reloaded, only the tuning.
+    pub fn reload_config(&self, config: Arc<Config>) {
+        self.physics_state
+            .borrow_mut()
+            .configure_characters(&config.physics.character_controller);
+        *self.config_instance.borrow_mut() = config;
+    }
+
     pub fn world_init(&self) {
         let mut game_state = self.game_state.lock().unwrap();
         let mut physics_state = self.physics_state.borrow_mut();
         let mut game_events = self.game_events.borrow_mut();
 
+        let baked_level = BakedLevel::load_current(
+            BAKED_LEVEL_PATH,
+            &self.config().models,
+            &self.config().scene,
+        );
+        if baked_level.is_some() {
+            info!("Loading the colliders from {}", BAKED_LEVEL_PATH);
+        }
+
         let handler = StartupCommandHandler::new(
-            self.config_instance.models.clone(),
-            self.config_instance.scene.clone(),
+            self.config().models.clone(),
+            self.config().scene.clone(),
+            self.config().game.clone(),
+            baked_level,
         );
 
         if let Err(e) = handler.handle(&mut game_state, &mut physics_state, &mut game_events) {
@@ -119,8 +165,60 @@ impl Executor {
         let mut physics_state = self.physics_state.borrow_mut();
         let mut game_events = self.game_events.borrow_mut();
 
-        let game_config = self.config_instance.game.clone();
-        let physics_config = self.config_instance.physics.clone();
+        let game_config = self.config().game.clone();
+        let physics_config = self.config().physics.clone();
+
+        match client_command.command {
+            Command::Join => {
+                game_events.add(
+                    GameEvent::PlayerJoined(client_command.client_id),
+                    Recipients::All,
+                );
+                return;
+            }
+            Command::Leave => {
+                game_state.remove_status_effects_from(client_command.client_id);
+                if game_state.life_cycle_state == Waiting {
+                    self.ready_players
+                        .borrow_mut()
+                        .retain(|id| *id != client_command.client_id);
+                }
+                game_events.add(
+                    GameEvent::PlayerLeft(client_command.client_id),
+                    Recipients::All,
+                );
+                self.latencies.borrow_mut().remove(&client_command.client_id);
+                let mut debug_subscribers = self.debug_subscribers.borrow_mut();
+                debug_subscribers.retain(|id| *id != client_command.client_id);
+                physics_state.set_debug_recording(!debug_subscribers.is_empty());
+                return;
+            }
+            Command::Latency(round_trip) => {
+                let round_trip = round_trip as f32 / 1000.0;
+                self.latencies
+                    .borrow_mut()
+                    .entry(client_command.client_id)
+                    .and_modify(|latency| {
+                        *latency += LATENCY_SMOOTHING * (round_trip - *latency)
+                    })
+                    .or_insert(round_trip);
+                return;
+            }
+            Command::PhysicsDebug => {
+                let mut debug_subscribers = self.debug_subscribers.borrow_mut();
+                if debug_subscribers.contains(&client_command.client_id) {
+                    debug_subscribers.retain(|id| *id != client_command.client_id);
+                } else {
+                    debug_subscribers.push(client_command.client_id);
+                }
+                physics_state.set_debug_recording(!debug_subscribers.is_empty());
+                return;
+            }
+            _ => {}
+        }
+
+        // attacks are resolved where their attacker saw the other players
+        let rewind = self.rewind(client_command.client_id, &physics_config);
 
         #[cfg(not(feature = "debug-ready-sync"))]
         let player_upper_bound = 4;
@@ -149,6 +247,8 @@ impl Executor {
                         // here I just change it to 1 for testing purpose
                         if self.ready_players.borrow().len() == player_upper_bound {
                             game_state.life_cycle_state = Running(0);
+                            game_state.match_clock = MatchClock::new(&game_config.match_config);
+                            game_events.add(GameEvent::MatchStarted, Recipients::All);
                         }
                     } else {
                         warn!("player has already been ready!");
@@ -170,6 +270,7 @@ impl Executor {
                     client_command.client_id,
                     dir,
                     physics_config,
+                    game_config,
                 )),
                 Command::UpdateCamera { forward } => Box::new(
                     UpdateCameraFacingCommandHandler::new(client_command.client_id, forward),
@@ -177,16 +278,27 @@ impl Executor {
                 Command::Jump => Box::new(JumpCommandHandler::new(
                     client_command.client_id,
                     physics_config,
+                    game_config,
                 )),
                 Command::Attack => Box::new(AttackCommandHandler::new(
                     client_command.client_id,
                     physics_config,
                     game_config,
+                    0.0,
+                    rewind,
+                )),
+                Command::ChargeAttack(phase) => Box::new(ChargeAttackCommandHandler::new(
+                    client_command.client_id,
+                    phase,
+                    physics_config,
+                    game_config,
+                    rewind,
                 )),
                 Command::AreaAttack => Box::new(AreaAttackCommandHandler::new(
                     client_command.client_id,
                     physics_config,
                     game_config,
+                    rewind,
                 )),
                 Command::Refill => Box::new(RefillCommandHandler::new(
                     client_command.client_id,
@@ -195,18 +307,33 @@ impl Executor {
                 Command::CastPowerUp => Box::new(CastPowerUpCommandHandler::new(
                     client_command.client_id,
                     game_config,
+                    rewind,
                 )),
                 Command::GivePowerUp => Box::new(GivePowerUpCommandHandler::new(
                     client_command.client_id,
                     game_config,
                 )),
-                Command::StatusEffects => Box::new(StatusEffectCommandHandler::new()),
+                Command::StatusEffects => Box::new(StatusEffectCommandHandler::new(game_config)),
                 // weather systems
-                Command::UpdateWeather => Box::new(UpdateWeatherCommandHandler::new()),
-                Command::WeatherEffects => Box::new(WeatherEffectCommandHandler::new()),
+                Command::UpdateWeather => {
+                    Box::new(UpdateWeatherCommandHandler::new(game_config))
+                }
+                Command::WeatherEffects => {
+                    Box::new(WeatherEffectCommandHandler::new(game_config))
+                }
+                Command::UpdateHazards => Box::new(HazardCommandHandler::new(
+                    self.hazard_behaviors.clone(),
+                    game_config,
+                )),
+                Command::UpdateGround => Box::new(GroundCommandHandler::new(
+                    physics_config,
+                    game_config,
+                )),
+                Command::UpdateProjectiles => Box::new(ProjectileCommandHandler::new()),
                 Command::CheatCode(powerup) => Box::new(CheatCodeCommandHandler::new(
                     client_command.client_id,
                     powerup,
+                    game_config,
                 )),
                 Command::CheatCodeControl(_command) => Box::new(
                     CheatCodeControlCommandHandler::new(client_command.client_id, _command),
@@ -214,6 +341,7 @@ impl Executor {
                 Command::WeatherCheatKey(_weather) => Box::new(WeatherCheatKeyCommandHandler::new(
                     client_command.client_id,
                     _weather,
+                    game_config,
                 )),
                 Command::Wave => Box::new(WaveCommandHandler::new(
                     client_command.client_id,
@@ -227,12 +355,6 @@ impl Executor {
             if let Err(e) = handler.handle(&mut game_state, &mut physics_state, &mut game_events) {
                 error!("Failed to execute command: {:?}", e);
             }
-            // TOOD: test that this fixes the land sound
-            // JumpResetCommandHandler::new(client_command.client_id).handle(
-            //     &mut game_state,
-            //     &mut physics_state,
-            //     &mut game_events,
-            // ).unwrap_or(());
         }
 
         info!("GameState: {:?}", game_state);
@@ -241,34 +363,129 @@ impl Executor {
     pub(crate) fn step(&self, delta_time: f32) {
         self.physics_state.borrow_mut().set_delta_time(delta_time);
         self.physics_state.borrow_mut().step();
+        self.physics_state
+            .borrow_mut()
+            .record_poses(self.config().physics.lag_compensation.max_rewind);
 
+        self.handle_contacts();
         self.sync_states(delta_time); // after physics step, need to sync game state
+        self.stream_physics_debug(delta_time);
+    }
+
+    /// Sends the physics debug frame to the clients that asked for it, every so often
+    fn stream_physics_debug(&self, delta_time: f32) {
+        let debug_subscribers = self.debug_subscribers.borrow();
+        if debug_subscribers.is_empty() {
+            return;
+        }
+        let mut debug_timer = self.debug_timer.borrow_mut();
+        *debug_timer += delta_time;
+        if *debug_timer < PHYSICS_DEBUG_PERIOD {
+            return;
+        }
+        *debug_timer = 0.0;
+
+        let frame = self.physics_state.borrow().debug_frame();
+        self.game_events.borrow_mut().add(
+            GameEvent::PhysicsDebug(frame),
+            Recipients::Multiple(debug_subscribers.iter().map(|id| *id as u8).collect()),
+        );
+    }
+
+    /// How long ago the client saw the world it acts on, in seconds
+    fn rewind(&self, client_id: u32, physics_config: &physics_config::ConfigPhysics) -> f32 {
+        let lag_compensation = &physics_config.lag_compensation;
+        let latency = self
+            .latencies
+            .borrow()
+            .get(&client_id)
+            .copied()
+            .unwrap_or(0.0);
+        (latency + lag_compensation.interpolation_delay).min(lag_compensation.max_rewind)
+    }
+
+    /// Hands the contacts of the physics step to gameplay
+    fn handle_contacts(&self) {
+        let mut game_state = self.game_state.lock().unwrap();
+        let mut physics_state = self.physics_state.borrow_mut();
+        let mut game_events = self.game_events.borrow_mut();
+
+        let events = physics_state.drain_contact_events();
+        if events.is_empty() {
+            return;
+        }
+        let handler = ContactCommandHandler::new(events, self.config().game.clone());
+        if let Err(e) = handler.handle(&mut game_state, &mut physics_state, &mut game_events) {
+            error!("Failed to handle contacts: {:?}", e);
+        }
     }
 
     fn sync_states(&self, delta_time: f32) {
         let mut game_state = self.game_state.lock().unwrap();
-        let physics_state = self.physics_state.borrow();
+        let mut physics_state = self.physics_state.borrow_mut();
 
-        let game_config = self.config_instance.game.clone();
+        let mut game_events = self.game_events.borrow_mut();
+
+        let game_config = self.config().game.clone();
 
         // update player positions
         for (_id, player) in game_state.players.iter_mut() {
-            let rigid_body = physics_state.get_entity_rigid_body(player.id).unwrap();
+            let rigid_body = physics_state
+                .get_entity_rigid_body(Entity::player(player.id))
+                .unwrap();
             player.transform.translation = rigid_body.position().translation.vector;
             player.transform.rotation = rigid_body.position().rotation.coords.into();
         }
 
+        // update hazard positions
+        for hazard in game_state.world.hazards.values_mut() {
+            if let Some(rigid_body) = physics_state.get_entity_rigid_body(hazard.entity) {
+                hazard.transform.translation = rigid_body.position().translation.vector;
+                hazard.transform.rotation = rigid_body.position().rotation.coords.into();
+            }
+        }
+
+        // update projectile positions
+        for (entity, projectile) in game_state.world.projectiles.iter_mut() {
+            if let Some(rigid_body) = physics_state.get_entity_rigid_body(*entity) {
+                projectile.position = *rigid_body.translation();
+                projectile.velocity = *rigid_body.linvel();
+            }
+        }
+
         // update the cooldowns
         game_state.update_cooldowns(delta_time);
+        game_state.update_last_hits(delta_time);
         game_state.update_action_states(Duration::from_secs_f32(delta_time));
+        game_state.update_charges(delta_time, &self.config().physics.attack_config);
 
         // update the powerup counters for players
         game_state.update_player_status_effect(delta_time);
 
         // update the powerup for each server location
-        game_state.update_powerup_respawn(delta_time);
+        game_state.update_powerup_respawn(
+            delta_time,
+            &game_config.powerup_config,
+            &mut physics_state.rng,
+        );
 
-        if let Some(id) = game_state.update_player_on_flag_times(delta_time, game_config.clone()) {
+        let players_on_flag = game_state.players_on_flag();
+        let contested = players_on_flag.len() > 1;
+
+        // the match is won by score, or by the match clock once time is up
+        let prev_phase = game_state.match_clock.phase;
+        let winner = game_state
+            .update_player_on_flag_times(delta_time, game_config.clone())
+            .or_else(|| {
+                game_state.update_match_clock(delta_time, contested, &game_config.match_config)
+            });
+        if game_state.match_clock.phase != prev_phase {
+            game_events.add(
+                GameEvent::MatchPhaseChanged(game_state.match_clock.phase),
+                Recipients::All,
+            );
+        }
+        if let Some(id) = winner {
             println!("Winner is {}, game finished!", id);
             game_state.game_winner = Some(id);
             game_state.life_cycle_state = Ended;
@@ -276,10 +493,24 @@ impl Executor {
                 id,
                 game_state.players_customization.get(&id).unwrap().clone(),
             ));
+            let summary = game_state.match_summary();
+            write_match_summary(&summary, game_state.game_start_time);
+            game_state.match_summary = Some(summary);
+            game_events.add(GameEvent::MatchEnded { winner: id }, Recipients::All);
         }
         let pptw = game_state.previous_tick_winner.clone();
-        game_state.previous_tick_winner = game_state.has_single_winner(game_config);
-        let mut game_events = self.game_events.borrow_mut();
+        game_state.previous_tick_winner = game_state.has_single_winner();
+
+        // announce changes of who holds the flag
+        if contested && !*self.flag_contested.borrow() {
+            game_events.add(GameEvent::FlagContested(players_on_flag), Recipients::All);
+        }
+        *self.flag_contested.borrow_mut() = contested;
+        if let Some(id) = game_state.previous_tick_winner {
+            if pptw != Some(id) {
+                game_events.add(GameEvent::FlagCaptured(id), Recipients::All);
+            }
+        }
         holding_flag_sound(pptw, game_state.previous_tick_winner.clone(), &mut game_events);
     }
 
@@ -299,7 +530,11 @@ impl Executor {
     }
 
     pub(crate) fn check_respawn_players(&self) -> Vec<u32> {
-        self.game_state()
+        let game_state = self.game_state();
+        if !game_state.respawns_enabled() {
+            return Vec::new();
+        }
+        game_state
             .players
             .iter()
             .filter(|(_, player)| {
@@ -314,7 +549,11 @@ impl Executor {
         let mut game_state = self.game_state.lock().unwrap();
         let prev_winner = game_state.game_winner;
         let prev_player_customization = game_state.players_customization.clone();
+        let prev_match_summary = game_state.match_summary.clone();
         if game_state.life_cycle_state == Ended {
+            // the scene is not rebuilt, its hazards and zones carry on into the next match
+            let hazards = std::mem::take(&mut game_state.world.hazards);
+            let mut zones = std::mem::take(&mut game_state.world.zones);
             let mut physics_state = self.physics_state.borrow_mut();
             let mut game_events = self.game_events.borrow_mut();
             let mut ready_players = self.ready_players.borrow_mut();
@@ -322,7 +561,15 @@ impl Executor {
 
             // Remove all players from physics state
             for player_id in game_state.players.keys() {
-                physics_state.remove_entity(*player_id);
+                physics_state.remove_entity(Entity::player(*player_id));
+            }
+            // projectiles in flight don't carry on into the next match
+            let projectiles = physics_state
+                .entities
+                .of_kind(EntityKind::Projectile)
+                .collect::<Vec<_>>();
+            for entity in projectiles {
+                physics_state.remove_entity(entity);
             }
 
             // Reset other instance variables
@@ -334,9 +581,17 @@ impl Executor {
                 ));
                 game_state.players_customization = prev_player_customization;
             }
+            game_state.match_summary = prev_match_summary;
+            game_state.world.hazards = hazards;
+            // removed players leave no intersection events behind
+            for zone in zones.values_mut() {
+                zone.occupants.clear();
+            }
+            game_state.world.zones = zones;
             game_events.clear();
             ready_players.clear();
             *spawn_command_pushed = false;
+            *self.flag_contested.borrow_mut() = false;
         }
     }
 
@@ -349,11 +604,14 @@ impl Executor {
         commands.push(ClientCommand::server_issued(Command::UpdateWeather));
         commands.push(ClientCommand::server_issued(Command::WeatherEffects));
         commands.push(ClientCommand::server_issued(Command::StatusEffects));
+        commands.push(ClientCommand::server_issued(Command::UpdateHazards));
+        commands.push(ClientCommand::server_issued(Command::UpdateGround));
+        commands.push(ClientCommand::server_issued(Command::UpdateProjectiles));
 
         // keep this in a block to return game state after we're done
         {
             let mut game_state = self.game_state.lock().unwrap();
-            let game_config = self.config_instance.game.clone();
+            let game_config = self.config().game.clone();
 
             // check if players are on a power up
             let players_to_powerup = game_state.check_powerup_pickup(game_config);
@@ -390,6 +648,18 @@ impl Executor {
     }
 }
 
+fn write_match_summary(summary: &MatchSummary, game_start_time: Duration) {
+    if let Err(e) = std::fs::create_dir_all(MATCH_SUMMARY_DIR) {
+        error!("Failed to create match summary directory: {:?}", e);
+        return;
+    }
+    let path = format!("{}/match_{}.json", MATCH_SUMMARY_DIR, game_start_time.as_secs());
+    match to_file(summary, &path) {
+        Ok(()) => info!("Match summary written to {}", path),
+        Err(e) => error!("Failed to write match summary: {:?}", e),
+    }
+}
+
 fn holding_flag_sound(_ppw: Option<u32>, pw: Option<u32>, game_events: &mut dyn GameEventCollector) {
     if let Some(_) = pw {
         game_events.add(
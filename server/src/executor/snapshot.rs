@@ -0,0 +1,53 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use common::core::states::GameState;
+use serde::{Deserialize, Serialize};
+
+use crate::executor::Executor;
+use crate::simulation::snapshot::PhysicsSnapshot;
+
+/// Everything needed to resume a match where it was, see Executor::snapshot.
+/// Latencies are not kept, they are measured again once the clients reconnect.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Snapshot {
+    pub game_state: GameState,
+    pub physics: PhysicsSnapshot,
+    ready_players: Vec<u32>,
+    spawn_command_pushed: bool,
+    flag_contested: bool,
+}
+
+impl Snapshot {
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> bincode::Result<()> {
+        bincode::serialize_into(BufWriter::new(File::create(path)?), self)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> bincode::Result<Self> {
+        bincode::deserialize_from(BufReader::new(File::open(path)?))
+    }
+}
+
+impl Executor {
+    /// The state of the simulation between two ticks
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            game_state: self.game_state(),
+            physics: self.physics_state.borrow().snapshot(),
+            ready_players: self.ready_players.borrow().clone(),
+            spawn_command_pushed: *self.spawn_command_pushed.borrow(),
+            flag_contested: *self.flag_contested.borrow(),
+        }
+    }
+
+    /// Resumes the simulation from a snapshot, pending game events are dropped
+    pub fn restore(&self, snapshot: Snapshot) {
+        *self.game_state.lock().unwrap() = snapshot.game_state;
+        self.physics_state.borrow_mut().restore(snapshot.physics);
+        *self.ready_players.borrow_mut() = snapshot.ready_players;
+        *self.spawn_command_pushed.borrow_mut() = snapshot.spawn_command_pushed;
+        *self.flag_contested.borrow_mut() = snapshot.flag_contested;
+        self.game_events.borrow_mut().clear();
+    }
+}
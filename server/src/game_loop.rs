@@ -2,6 +2,7 @@ use crate::executor::Executor;
 use crate::outgoing_request::{OutgoingRequest, RequestKind};
 use crate::Recipients;
 use bus::Bus;
+use common::configs::{ConfigTuning, ConfigurationManager};
 use common::core::command::Command;
 
 use common::core::command::Command::{UpdateWeather, WeatherEffects};
@@ -50,6 +51,9 @@ pub struct GameLoop<'a> {
 
     // used to stop the game loop (mostly for testing and debugging purposes)
     running: Arc<AtomicBool>,
+
+    // tunings reloaded by the config watcher, applied at the start of the next tick
+    config_updates: Receiver<ConfigTuning>,
 }
 
 impl GameLoop<'_> {
@@ -59,17 +63,20 @@ impl GameLoop<'_> {
     /// * `executor` - used to execute the commands received from the clients
     /// * `broadcast` - used to broadcast events to the clients (single-producer, multi-consumer)
     /// * `running` - used to stop the game loop (mostly for testing and debugging purposes)
+    /// * `config_updates` - tunings reloaded while the game runs, see config_watcher
     pub fn new(
         commands: Receiver<ClientCommand>,
         executor: &Executor,
         broadcast: Arc<Mutex<Bus<OutgoingRequest>>>,
         running: Arc<AtomicBool>,
+        config_updates: Receiver<ConfigTuning>,
     ) -> GameLoop {
         GameLoop {
             commands,
             executor,
             broadcast,
             running,
+            config_updates,
         }
     }
 
@@ -83,6 +90,16 @@ impl GameLoop<'_> {
             // Reset game if game has ended
             self.executor.reset_game();
 
+            // switch to the latest reloaded tuning between two ticks, so that no tick mixes
+            // the old and the new values, the clients are sent it to match
+            if let Some(tuning) = self.config_updates.try_iter().last() {
+                let config = ConfigurationManager::apply_tuning(tuning);
+                let synced = RequestKind::SyncConfig(Arc::new(config.gameplay()));
+                self.executor.reload_config(config);
+                let request = OutgoingRequest::new(synced, Recipients::All);
+                self.broadcast.lock().unwrap().broadcast(request);
+            }
+
             // consume and collect all messages in the channel
             let mut commands = self.commands.try_iter().collect::<Vec<_>>();
 
@@ -1,3 +1,4 @@
+pub mod config_watcher;
 pub mod executor;
 pub mod game_loop;
 pub mod outgoing_request;
@@ -15,6 +15,7 @@ use common::communication::commons::CSE125_SERVER_ADDR;
 #[allow(unused_imports)]
 use common::communication::commons::DEFAULT_SERVER_ADDR;
 
+use server::config_watcher::watch_configs;
 use server::executor::Executor;
 use threadpool::ThreadPool;
 
@@ -56,10 +57,13 @@ fn main() {
 
     let pool = ThreadPool::new(4);
 
+    // the tuning configs are reloaded when their files change
+    let config_updates = watch_configs();
+
     // starting game loop
     let broadcast_clone = broadcast.clone();
     thread::spawn(move || {
-        GameLoop::new(rx, &executor, broadcast_clone, running.clone()).run();
+        GameLoop::new(rx, &executor, broadcast_clone, running.clone(), config_updates).run();
     });
 
     for stream in listener.incoming() {
@@ -1,17 +1,20 @@
 use crate::Recipients;
 use common::communication::message::{HostRole, Message, Payload};
+use common::configs::ConfigGameplay;
 use common::core::events::GameEvent;
 use common::core::states::GameState;
 use derive_more::Constructor;
+use std::sync::Arc;
 
 /// Outgoing events that are broadcast to the consumer threads.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum RequestKind {
-    SyncGameState,            // ask for a sync of game state
-    SendGameEvent(GameEvent), // send a game event to the clients
+    SyncGameState,                   // ask for a sync of game state
+    SendGameEvent(GameEvent),        // send a game event to the clients
+    SyncConfig(Arc<ConfigGameplay>), // send the rules the server now runs with to the clients
 }
 
-#[derive(Constructor, Debug, Clone, PartialEq)]
+#[derive(Constructor, Debug, Clone)]
 pub struct OutgoingRequest {
     kind: RequestKind,
     recipients: Recipients,
@@ -33,6 +36,10 @@ impl OutgoingRequest {
             RequestKind::SendGameEvent(event) => {
                 Message::new(HostRole::Server, Payload::ServerEvent(event.clone()))
             }
+            RequestKind::SyncConfig(gameplay) => Message::new(
+                HostRole::Server,
+                Payload::ConfigGameplay(Box::new(ConfigGameplay::clone(gameplay))),
+            ),
         }
     }
 }
@@ -0,0 +1,204 @@
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+use common::configs::model_config::ConfigModels;
+use common::configs::scene_config::ConfigSceneGraph;
+use common::utils::stable_hash::StableHasher;
+use log::info;
+use rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const BAKED_LEVEL_PATH: &str = "level.baked";
+// bump when the layout of the baked file changes
+const BAKED_FORMAT_VERSION: u32 = 1;
+
+/// A scene node as the startup handler places it, see StartupCommandHandler::scene_nodes
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BakedNode {
+    pub id: String,
+    pub position: Isometry<Real>,
+    pub collider: Option<Collider>,
+}
+
+impl BakedNode {
+    /// Triangles and convex hulls of the collider, to see what the decomposition costs
+    pub fn counts(&self) -> (usize, usize) {
+        self.collider
+            .as_ref()
+            .map_or((0, 0), |collider| shape_counts(collider.shape()))
+    }
+}
+
+/// The colliders of the scene computed ahead of time by the bake binary,
+/// so that the server doesn't load and decompose every model on startup
+#[derive(Serialize, Deserialize)]
+pub struct BakedLevel {
+    format_version: u32,
+    rapier_version: String,
+    source_hash: u64,          // of the configs and models it was baked from
+    pub nodes: Vec<BakedNode>, // in the order of StartupCommandHandler::scene_nodes
+}
+
+impl BakedLevel {
+    pub fn new(source_hash: u64, nodes: Vec<BakedNode>) -> Self {
+        Self {
+            format_version: BAKED_FORMAT_VERSION,
+            rapier_version: rapier3d::VERSION.to_string(),
+            source_hash,
+            nodes,
+        }
+    }
+
+    /// Hash of the scene, the models and the files of the models the scene uses
+    pub fn source_hash(models: &ConfigModels, scene: &ConfigSceneGraph) -> io::Result<u64> {
+        let mut hasher = StableHasher::default();
+        hasher.write(&serde_json::to_vec(models)?);
+        hasher.write(&serde_json::to_vec(scene)?);
+        for node in scene.nodes.iter().flat_map(|node| node.descendants()) {
+            let Some(model) = node.model.clone().and_then(|model| models.model(model)) else {
+                continue;
+            };
+            hasher.write(&fs::read(&model.path)?);
+        }
+        Ok(hasher.finish())
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> bincode::Result<()> {
+        bincode::serialize_into(BufWriter::new(File::create(path)?), self)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> bincode::Result<Self> {
+        bincode::deserialize_from(BufReader::new(File::open(path)?))
+    }
+
+    /// The baked level at path, if there is one baked from these configs and models
+    /// by this version of the server
+    pub fn load_current<P: AsRef<Path>>(
+        path: P,
+        models: &ConfigModels,
+        scene: &ConfigSceneGraph,
+    ) -> Option<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return None;
+        }
+        let baked = match Self::load(path) {
+            Ok(baked) => baked,
+            Err(e) => {
+                info!("Ignoring the baked level {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        if baked.format_version != BAKED_FORMAT_VERSION || baked.rapier_version != rapier3d::VERSION
+        {
+            info!(
+                "Ignoring the baked level {}, baked by another version",
+                path.display()
+            );
+            return None;
+        }
+        match Self::source_hash(models, scene) {
+            Ok(hash) if hash == baked.source_hash => Some(baked),
+            Ok(_) => {
+                info!(
+                    "Ignoring the baked level {}, the level changed since",
+                    path.display()
+                );
+                None
+            }
+            Err(e) => {
+                info!("Ignoring the baked level {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// The baked node at that place of the walk, if it is that node
+    pub fn node(&self, index: usize, id: &str) -> Option<&BakedNode> {
+        self.nodes.get(index).filter(|node| node.id == id)
+    }
+}
+
+fn shape_counts(shape: &dyn Shape) -> (usize, usize) {
+    match shape.as_typed_shape() {
+        TypedShape::TriMesh(trimesh) => (trimesh.indices().len(), 0),
+        TypedShape::ConvexPolyhedron(hull) => (hull.to_trimesh().1.len(), 1),
+        TypedShape::RoundConvexPolyhedron(hull) => (hull.inner_shape.to_trimesh().1.len(), 1),
+        TypedShape::Compound(compound) => compound
+            .shapes()
+            .iter()
+            .map(|(_, part)| shape_counts(&**part))
+            .fold((0, 0), |(triangles, hulls), (t, h)| {
+                (triangles + t, hulls + h)
+            }),
+        _ => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene(x: f32) -> ConfigSceneGraph {
+        serde_json::from_value(serde_json::json!({
+            "nodes": [{
+                "id": "platform",
+                "transform": { "position": [x, 0.0, 0.0], "rotation": [0.0, 0.0, 0.0, 1.0] },
+                "children": null,
+                "model": null,
+                "decompose": null,
+                "behavior": null
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_load_current() {
+        let models = ConfigModels { models: vec![] };
+        let path = std::env::temp_dir().join(format!("test_level_{}.baked", std::process::id()));
+        let node = BakedNode {
+            id: "platform".to_string(),
+            position: Isometry::identity(),
+            collider: Some(ColliderBuilder::cuboid(1.0, 1.0, 1.0).build()),
+        };
+        let hash = BakedLevel::source_hash(&models, &scene(0.0)).unwrap();
+        BakedLevel::new(hash, vec![node]).save(&path).unwrap();
+
+        let baked = BakedLevel::load_current(&path, &models, &scene(0.0)).unwrap();
+        assert!(baked.node(0, "platform").is_some());
+        assert!(baked.node(0, "other").is_none());
+        // the scene was edited after baking
+        assert!(BakedLevel::load_current(&path, &models, &scene(1.0)).is_none());
+
+        fs::remove_file(&path).unwrap();
+        assert!(BakedLevel::load_current(&path, &models, &scene(0.0)).is_none());
+    }
+
+    #[test]
+    fn test_counts() {
+        let vertices = vec![
+            point![0.0, 0.0, 0.0],
+            point![1.0, 0.0, 0.0],
+            point![0.0, 1.0, 0.0],
+            point![0.0, 0.0, 1.0],
+        ];
+        let indices = vec![[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]];
+        let node = |collider: ColliderBuilder| BakedNode {
+            id: "tetrahedron".to_string(),
+            position: Isometry::identity(),
+            collider: Some(collider.build()),
+        };
+        let trimesh = ColliderBuilder::trimesh(vertices.clone(), indices.clone());
+        assert_eq!(node(trimesh).counts(), (4, 0));
+        // as decompositions come out, without running one
+        let hull = SharedShape::round_convex_hull(&vertices, 0.01).unwrap();
+        let compound = ColliderBuilder::compound(vec![
+            (Isometry::identity(), hull.clone()),
+            (Isometry::translation(2.0, 0.0, 0.0), hull),
+        ]);
+        assert_eq!(node(compound).counts(), (8, 2));
+    }
+}
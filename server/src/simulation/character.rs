@@ -0,0 +1,358 @@
+use crate::simulation::entity::Entity;
+use crate::simulation::physics_state::PhysicsState;
+use common::configs::physics_config::ConfigCharacterController;
+
+use itertools::Itertools;
+use nalgebra_glm::Vec3;
+use rapier3d::control::{CharacterAutostep, CharacterLength, KinematicCharacterController};
+use rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The motion of a player running as a kinematic body, which rapier doesn't integrate
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct KinematicCharacter {
+    pub velocity: Vector<Real>, // knockback, jumps and falling
+    pub force: Vector<Real>,    // applied every step until replaced, as forces on dynamic bodies
+    pub walk: Vector<Real>,     // translation the movement asked for since the last step
+    pub grounded: bool,         // after the last step
+}
+
+impl PhysicsState {
+    /// Sets up the character controller, and whether players spawned from now on are kinematic
+    pub fn configure_characters(&mut self, config: &ConfigCharacterController) {
+        self.character_controller = KinematicCharacterController {
+            offset: CharacterLength::Absolute(config.offset),
+            autostep: (config.autostep_max_height > 0.0).then_some(CharacterAutostep {
+                max_height: CharacterLength::Absolute(config.autostep_max_height),
+                min_width: CharacterLength::Absolute(config.autostep_min_width),
+                include_dynamic_bodies: false,
+            }),
+            max_slope_climb_angle: config.max_slope_climb_angle,
+            min_slope_slide_angle: config.min_slope_slide_angle,
+            snap_to_ground: (config.snap_to_ground > 0.0)
+                .then_some(CharacterLength::Absolute(config.snap_to_ground)),
+            ..Default::default()
+        };
+        self.kinematic_characters = config.kinematic;
+    }
+
+    /// Inserts a player, as a kinematic position-based body if configured so
+    pub fn insert_character(
+        &mut self,
+        player_id: u32,
+        collider: Collider,
+        mut rigid_body: RigidBody,
+    ) {
+        let entity = self.entities.register_player(player_id);
+        // kinematic players still need contacts with the scene, the zones and each other
+        let mut collider = collider;
+        collider.set_active_collision_types(ActiveCollisionTypes::all());
+        if self.kinematic_characters {
+            rigid_body.set_body_type(RigidBodyType::KinematicPositionBased, false);
+            self.character_motions
+                .insert(entity, KinematicCharacter::default());
+        }
+        self.insert_entity(entity, Some(collider), Some(rigid_body));
+    }
+
+    pub fn apply_character_impulse(&mut self, player_id: u32, impulse: Vector<Real>) {
+        let entity = Entity::player(player_id);
+        let Some(body) = self.get_entity_rigid_body(entity) else {
+            return;
+        };
+        let mass = body.mass();
+        if let Some(motion) = self.character_motions.get_mut(&entity) {
+            if mass > 0.0 {
+                motion.velocity += impulse / mass;
+            }
+        } else if let Some(body) = self.get_entity_rigid_body_mut(entity) {
+            body.apply_impulse(impulse, true);
+        }
+    }
+
+    pub fn set_character_linvel(&mut self, player_id: u32, linvel: Vector<Real>) {
+        let entity = Entity::player(player_id);
+        if let Some(motion) = self.character_motions.get_mut(&entity) {
+            motion.velocity = linvel;
+        } else if let Some(body) = self.get_entity_rigid_body_mut(entity) {
+            body.set_linvel(linvel, true);
+        }
+    }
+
+    pub fn character_linvel(&self, player_id: u32) -> Vector<Real> {
+        let entity = Entity::player(player_id);
+        if let Some(motion) = self.character_motions.get(&entity) {
+            return motion.velocity + motion.walk / self.dt();
+        }
+        self.get_entity_rigid_body(entity)
+            .map_or(Vector::zeros(), |body| *body.linvel())
+    }
+
+    /// Replaces the force applied on the player every step
+    pub fn set_character_force(&mut self, player_id: u32, force: Vector<Real>) {
+        let entity = Entity::player(player_id);
+        if let Some(motion) = self.character_motions.get_mut(&entity) {
+            motion.force = force;
+        } else if let Some(body) = self.get_entity_rigid_body_mut(entity) {
+            body.reset_forces(false);
+            body.add_force(force, true);
+        }
+    }
+
+    /// Whether the controller left the player on the ground, None for dynamic players
+    pub fn character_grounded(&self, player_id: u32) -> Option<bool> {
+        let entity = Entity::player(player_id);
+        self.character_motions
+            .get(&entity)
+            .map(|motion| motion.grounded)
+    }
+
+    /// Turns the player towards the rotation, right away for kinematic players
+    pub fn rotate_character(
+        &mut self,
+        player_id: u32,
+        rotation: Rotation<Real>,
+        gain: f32,
+        damping: f32,
+    ) {
+        let entity = Entity::player(player_id);
+        let dt = self.dt();
+        let Some(body) = self.get_entity_rigid_body_mut(entity) else {
+            return;
+        };
+        if body.is_kinematic() {
+            body.set_next_kinematic_rotation(rotation);
+            return;
+        }
+
+        // rotate by applying a torque impulse
+        // (does not guarantee the rotation will be reached, but it will eventually converge to the desired rotation)
+
+        // Step 1: Calculate the angular displacement required to reach the desired rotation
+        let rotation_difference = rotation * body.rotation().inverse();
+
+        // Step 2: Divide the angular displacement by dt to get the desired angular velocity
+        let desired_angular_velocity = rotation_difference.scaled_axis() / dt;
+
+        // Step 3: Calculate the difference between the current and desired angular velocities
+        body.set_angular_damping(damping);
+        let angular_velocity_difference = desired_angular_velocity - body.angvel();
+
+        // Step 4: Calculate the required torque using the gain factor
+        let required_torque = angular_velocity_difference * gain;
+
+        // Step 5: Apply the torque to the player's rigid body
+        body.apply_torque_impulse(required_torque, true);
+    }
+
+    pub fn move_character_with_velocity(&mut self, player_id: u32, desired_translation: Vec3) {
+        let entity = Entity::player(player_id);
+        if let Some(motion) = self.character_motions.get_mut(&entity) {
+            motion.walk += desired_translation;
+            return;
+        }
+
+        let Some(corrected_movement) = self.move_shape(entity, desired_translation) else {
+            return;
+        };
+        let dt = self.dt();
+        let character_body = self.get_entity_rigid_body_mut(entity).unwrap();
+        // set its velocity to the computed movement divided by the timestep length.
+        let mut velocity = corrected_movement.translation / dt;
+
+        // add back y component of velocity, unless the controller climbs a slope or a step
+        velocity.y = character_body.linvel().y.max(velocity.y);
+
+        character_body.set_linvel(velocity, true);
+    }
+
+    /// Moves the kinematic players by their velocity and walk, before the physics step
+    pub(crate) fn move_kinematic_characters(&mut self) {
+        let dt = self.dt();
+        let gravity = self.gravity;
+        for entity in self.character_motions.keys().copied().collect_vec() {
+            let Some(body) = self.get_entity_rigid_body(entity) else {
+                continue;
+            };
+            let motion = self.character_motions[&entity];
+            if !body.is_enabled() {
+                self.character_motions
+                    .insert(entity, KinematicCharacter::default());
+                continue;
+            }
+            let mass = body.mass().max(f32::EPSILON);
+            let damping = body.linear_damping();
+            let friction = self
+                .get_entity_collider(entity)
+                .map_or(0.0, |collider| collider.friction());
+
+            let mut velocity =
+                (motion.velocity + (gravity + motion.force / mass) * dt) / (1.0 + dt * damping);
+            if motion.grounded {
+                // slides to a stop on the ground, as a dynamic body would
+                let horizontal = vector![velocity.x, 0.0, velocity.z];
+                let speed = horizontal.norm();
+                if speed > 0.0 {
+                    let slowed = (speed - friction * gravity.norm() * dt).max(0.0);
+                    velocity -= horizontal * (1.0 - slowed / speed);
+                }
+            }
+
+            let Some(movement) = self.move_shape(entity, velocity * dt + motion.walk) else {
+                continue;
+            };
+            if movement.grounded && velocity.y < 0.0 {
+                velocity.y = 0.0;
+            }
+            if velocity.y > 0.0 {
+                // hit a ceiling
+                velocity.y = velocity.y.min(movement.translation.y.max(0.0) / dt);
+            }
+
+            let body = self.get_entity_rigid_body_mut(entity).unwrap();
+            let translation = body.translation() + movement.translation;
+            body.set_next_kinematic_translation(translation);
+            self.character_motions.insert(
+                entity,
+                KinematicCharacter {
+                    velocity,
+                    force: motion.force,
+                    walk: Vector::zeros(),
+                    grounded: movement.grounded,
+                },
+            );
+        }
+    }
+
+    fn move_shape(
+        &self,
+        entity: Entity,
+        desired_translation: Vector<Real>,
+    ) -> Option<rapier3d::control::EffectiveCharacterMovement> {
+        let character_shape = self.get_entity_collider(entity)?.shape();
+        let character_handle = self.get_entity_handles(entity)?.rigid_body?;
+        let character_pos = self.get_entity_rigid_body(entity)?.position();
+
+        Some(
+            self.character_controller.move_shape(
+                self.dt(),            // The timestep length
+                &self.bodies,         // The RigidBodySet.
+                &self.colliders,      // The ColliderSet.
+                &self.query_pipeline, // The QueryPipeline.
+                character_shape,      // The character’s shape.
+                character_pos,        // The character’s initial position.
+                desired_translation,
+                QueryFilter::default()
+                    // Make sure the the character we are trying to move isn’t considered an obstacle.
+                    .exclude_rigid_body(character_handle)
+                    // zones are no obstacles either
+                    .exclude_sensors(),
+                |_| {},
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::entity::EntityKind;
+
+    fn config(kinematic: bool) -> ConfigCharacterController {
+        ConfigCharacterController {
+            kinematic,
+            max_slope_climb_angle: 0.87,
+            min_slope_slide_angle: 0.87,
+            autostep_max_height: 0.3,
+            autostep_min_width: 0.2,
+            snap_to_ground: 0.3,
+            offset: 0.02,
+        }
+    }
+
+    // a player standing on the ground, with a step ahead along +x
+    fn setup(kinematic: bool) -> PhysicsState {
+        let mut physics_state = PhysicsState::new();
+        physics_state.set_delta_time(1.0 / 30.0);
+        physics_state.configure_characters(&config(kinematic));
+
+        let ground = ColliderBuilder::cuboid(20.0, 0.5, 20.0)
+            .translation(vector![0.0, -0.5, 0.0])
+            .build();
+        physics_state.spawn_entity(EntityKind::SceneNode, None, Some(ground), None);
+        let step = ColliderBuilder::cuboid(5.0, 0.1, 5.0)
+            .translation(vector![6.0, 0.1, 0.0])
+            .build();
+        physics_state.spawn_entity(EntityKind::SceneNode, None, Some(step), None);
+
+        let collider = ColliderBuilder::capsule_y(0.5, 0.25).mass(0.0).build();
+        let rigid_body = RigidBodyBuilder::dynamic()
+            .translation(vector![0.0, 0.8, 0.0])
+            .additional_mass_properties(MassProperties::new(
+                point![0.0, -0.7, 0.0],
+                15.0,
+                vector![1.425, 1.425, 0.45],
+            ))
+            .lock_rotations()
+            .build();
+        physics_state.insert_character(3, collider, rigid_body);
+        physics_state.step();
+        physics_state
+    }
+
+    fn height(physics_state: &PhysicsState) -> f32 {
+        physics_state
+            .get_entity_rigid_body(Entity::player(3))
+            .unwrap()
+            .translation()
+            .y
+    }
+
+    #[test]
+    fn test_kinematic_character() {
+        let mut physics_state = setup(true);
+        let body = physics_state
+            .get_entity_rigid_body(Entity::player(3))
+            .unwrap();
+        assert!(body.is_kinematic());
+        assert!(body.mass() > 0.0);
+
+        // falls onto the ground and stays there
+        for _ in 0..30 {
+            physics_state.step();
+        }
+        assert_eq!(physics_state.character_grounded(3), Some(true));
+        let standing = height(&physics_state);
+        assert!((standing - 0.75).abs() < 0.1);
+
+        // walks up the step
+        for _ in 0..30 {
+            physics_state.move_character_with_velocity(3, vector![0.2, 0.0, 0.0]);
+            physics_state.step();
+        }
+        assert!(height(&physics_state) > standing + 0.15);
+
+        // knockback is slowed down by the ground
+        physics_state.apply_character_impulse(3, vector![0.0, 0.0, 150.0]);
+        physics_state.step();
+        let speed = physics_state.character_linvel(3).z;
+        assert!(speed > 0.0);
+        for _ in 0..90 {
+            physics_state.step();
+        }
+        assert!(physics_state.character_linvel(3).z < speed * 0.1);
+    }
+
+    #[test]
+    fn test_dynamic_character() {
+        let mut physics_state = setup(false);
+        assert!(physics_state
+            .get_entity_rigid_body(Entity::player(3))
+            .unwrap()
+            .is_dynamic());
+        assert_eq!(physics_state.character_grounded(3), None);
+
+        physics_state.set_character_linvel(3, vector![0.0, 5.0, 0.0]);
+        assert_eq!(physics_state.character_linvel(3), vector![0.0, 5.0, 0.0]);
+    }
+}
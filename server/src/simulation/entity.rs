@@ -1,9 +1,161 @@
 use rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-// TODO: decide the types of entities and maybe make it into an enum
-pub type Entity = u32;
+/// Players are their client id, every other entity is given out by the EntityRegistry
+pub use common::core::components::Entity;
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    Player,
+    SceneNode, // static part of the scene
+    Hazard,    // scene node with a behavior
+    Zone,
+    Projectile,
+}
+
+/// What an entity is, and the config node it was made from if any
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EntityInfo {
+    pub kind: EntityKind,
+    pub node_id: Option<String>,
+}
+
+/// Gives out the entity ids, none of them is ever given out twice or taken by a player
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EntityRegistry {
+    next: u32,
+    entities: BTreeMap<Entity, EntityInfo>,
+}
+
+impl Default for EntityRegistry {
+    fn default() -> Self {
+        Self {
+            next: Entity::MAX_PLAYER_ID + 1,
+            entities: BTreeMap::new(),
+        }
+    }
+}
+
+impl EntityRegistry {
+    /// A new entity that isn't a player
+    pub fn allocate(&mut self, kind: EntityKind, node_id: Option<String>) -> Entity {
+        assert_ne!(kind, EntityKind::Player, "players are their client id");
+        let entity = Entity::from_raw(self.next);
+        self.next = self.next.checked_add(1).expect("Ran out of entity ids");
+        self.entities.insert(entity, EntityInfo { kind, node_id });
+        entity
+    }
+
+    /// The entity of a player, its client id
+    pub fn register_player(&mut self, player_id: u32) -> Entity {
+        let entity = Entity::player(player_id);
+        self.entities.insert(
+            entity,
+            EntityInfo {
+                kind: EntityKind::Player,
+                node_id: None,
+            },
+        );
+        entity
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<EntityInfo> {
+        self.entities.remove(&entity)
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&EntityInfo> {
+        self.entities.get(&entity)
+    }
+
+    pub fn kind(&self, entity: Entity) -> Option<EntityKind> {
+        self.get(entity).map(|info| info.kind)
+    }
+
+    /// The entities of that kind, by id
+    pub fn of_kind(&self, kind: EntityKind) -> impl Iterator<Item = Entity> + '_ {
+        self.entities
+            .iter()
+            .filter(move |(_, info)| info.kind == kind)
+            .map(|(entity, _)| *entity)
+    }
+
+    /// The entity made from the config node
+    pub fn by_node(&self, node_id: &str) -> Option<Entity> {
+        self.entities
+            .iter()
+            .find(|(_, info)| info.node_id.as_deref() == Some(node_id))
+            .map(|(entity, _)| *entity)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct EntityHandles {
     pub rigid_body: Option<RigidBodyHandle>,
     pub collider: Option<ColliderHandle>,
 }
+
+/// What happened between the colliders of two entities during a physics step,
+/// only reported for colliders with the matching active events
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContactEvent {
+    Started {
+        entities: (Entity, Entity),
+        sensor: bool,
+    },
+    Stopped {
+        entities: (Entity, Entity),
+        sensor: bool,
+    },
+    Force {
+        entities: (Entity, Entity),
+        magnitude: f32,
+        direction: Vector<f32>, // of the strongest force, applied on the first entity
+    },
+}
+
+impl ContactEvent {
+    pub fn entities(&self) -> (Entity, Entity) {
+        match *self {
+            ContactEvent::Started { entities, .. }
+            | ContactEvent::Stopped { entities, .. }
+            | ContactEvent::Force { entities, .. } => entities,
+        }
+    }
+
+    /// The other entity if this event involves the given one
+    pub fn other(&self, entity: Entity) -> Option<Entity> {
+        match self.entities() {
+            (a, b) if a == entity => Some(b),
+            (a, b) if b == entity => Some(a),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_registry() {
+        let mut registry = EntityRegistry::default();
+        let platform = registry.allocate(EntityKind::Hazard, Some("platform".to_string()));
+        let zone = registry.allocate(EntityKind::Zone, None);
+        let player = registry.register_player(1);
+        assert_eq!(player.player_id(), Some(1));
+        assert!(platform.player_id().is_none() && zone > platform);
+
+        assert_eq!(registry.kind(platform), Some(EntityKind::Hazard));
+        assert_eq!(registry.by_node("platform"), Some(platform));
+        assert_eq!(
+            registry.of_kind(EntityKind::Player).collect::<Vec<_>>(),
+            vec![player]
+        );
+
+        // removed ids are not given out again
+        registry.remove(zone);
+        assert!(registry.get(zone).is_none());
+        assert!(registry.allocate(EntityKind::Projectile, None) > zone);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::simulation::entity::Entity;
+use crate::simulation::physics_state::PhysicsState;
+use crate::simulation::targeting::{TargetHit, TargetQuery};
+
+use rapier3d::parry::utils::hashmap::HashMap;
+use rapier3d::prelude::*;
+use std::collections::VecDeque;
+
+/// Where the colliders of the moving bodies were over the last steps
+#[derive(Default)]
+pub struct PoseHistory {
+    time: f32, // of the latest record, since the first one
+    records: VecDeque<(f32, HashMap<Entity, Isometry<Real>>)>, // oldest first
+}
+
+impl PoseHistory {
+    /// Records the poses of a step lasting dt, keeps the records of the last `keep` seconds
+    pub fn record(&mut self, dt: f32, poses: HashMap<Entity, Isometry<Real>>, keep: f32) {
+        if !self.records.is_empty() {
+            self.time += dt;
+        }
+        self.records.push_back((self.time, poses));
+        // the oldest record kept is at least `keep` seconds old, to interpolate from it
+        while self.records.len() > 1 && self.time - self.records[1].0 >= keep {
+            self.records.pop_front();
+        }
+    }
+
+    /// Where the entity was `rewind` seconds before the latest record, between two records
+    /// if needed, or at the oldest record if the history is shorter
+    pub fn pose_at(&self, entity: Entity, rewind: f32) -> Option<Isometry<Real>> {
+        let time = self.time - rewind.max(0.0);
+        let after = self
+            .records
+            .iter()
+            .position(|(record_time, _)| *record_time >= time)
+            .unwrap_or(self.records.len().checked_sub(1)?);
+        let (after_time, after_poses) = &self.records[after];
+        let after_pose = after_poses.get(&entity)?;
+        if after == 0 {
+            return Some(*after_pose);
+        }
+        let (before_time, before_poses) = &self.records[after - 1];
+        let Some(before_pose) = before_poses.get(&entity) else {
+            return Some(*after_pose);
+        };
+        let t = (time - before_time) / (after_time - before_time);
+        Some(before_pose.lerp_slerp(after_pose, t))
+    }
+}
+
+impl PhysicsState {
+    /// Records where the colliders of the moving bodies are, see PoseHistory::record
+    pub fn record_poses(&mut self, keep: f32) {
+        let poses = self
+            .entity_indices
+            .iter()
+            .filter(|(_, handles)| {
+                handles
+                    .rigid_body
+                    .and_then(|handle| self.bodies.get(handle))
+                    .is_some_and(|body| !body.is_fixed())
+            })
+            .filter_map(|(entity, handles)| {
+                let collider = self.colliders.get(handles.collider?)?;
+                Some((*entity, *collider.position()))
+            })
+            .collect();
+        let dt = self.dt();
+        self.pose_history.record(dt, poses, keep);
+    }
+
+    /// Same as query_targets, with the candidates where they were `rewind` seconds ago.
+    /// Nothing is moved once this returns, impulses apply to where bodies are now.
+    pub fn query_targets_rewound(
+        &mut self,
+        target_query: &TargetQuery,
+        source: Option<Entity>,
+        candidates: &[Entity],
+        rewind: f32,
+    ) -> Vec<TargetHit> {
+        let mut moved = Vec::new();
+        if rewind > 0.0 {
+            for &entity in candidates.iter().filter(|&&entity| Some(entity) != source) {
+                let Some(pose) = self.pose_history.pose_at(entity, rewind) else {
+                    continue;
+                };
+                let Some(handle) = self
+                    .get_entity_handles(entity)
+                    .and_then(|handles| handles.collider)
+                else {
+                    continue;
+                };
+                if let Some(collider) = self.colliders.get_mut(handle) {
+                    moved.push((handle, *collider.position()));
+                    collider.set_position(pose);
+                }
+            }
+        }
+        if moved.is_empty() {
+            let hits = self.query_targets(target_query, source, candidates);
+            self.record_debug_query(target_query, source, candidates, &hits);
+            return hits;
+        }
+
+        self.query_pipeline.update(&self.bodies, &self.colliders);
+        let hits = self.query_targets(target_query, source, candidates);
+        self.record_debug_query(target_query, source, candidates, &hits);
+        for (handle, position) in moved {
+            if let Some(collider) = self.colliders.get_mut(handle) {
+                collider.set_position(position);
+            }
+        }
+        self.query_pipeline.update(&self.bodies, &self.colliders);
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec3;
+
+    const ATTACKER: Entity = Entity::player(1);
+    const TARGET: Entity = Entity::player(2);
+
+    fn pose(x: f32) -> HashMap<Entity, Isometry<Real>> {
+        let mut poses = HashMap::default();
+        poses.insert(TARGET, Isometry::translation(x, 0.0, 0.0));
+        poses
+    }
+
+    #[test]
+    fn test_pose_history() {
+        let mut history = PoseHistory::default();
+        for (i, x) in [0.0, 1.0, 2.0, 3.0].into_iter().enumerate() {
+            history.record(if i == 0 { 0.0 } else { 0.1 }, pose(x), 0.15);
+        }
+        // the first record is too old to be kept
+        assert_eq!(history.records.len(), 3);
+
+        let x_at = |rewind| history.pose_at(TARGET, rewind).unwrap().translation.x;
+        assert!((x_at(0.0) - 3.0).abs() < 1e-4);
+        assert!((x_at(0.15) - 1.5).abs() < 1e-4);
+        // longer than the history
+        assert!((x_at(1.0) - 1.0).abs() < 1e-4);
+        assert!(history.pose_at(Entity::player(3), 0.0).is_none());
+    }
+
+    #[test]
+    fn test_rewound_query() {
+        let mut physics_state = PhysicsState::new();
+        physics_state.set_delta_time(0.1);
+        for (entity, x) in [(ATTACKER, 0.0), (TARGET, 5.0)] {
+            let rigid_body = RigidBodyBuilder::dynamic()
+                .translation(vector![x, 0.0, 0.0])
+                .gravity_scale(0.0)
+                .build();
+            let collider = ColliderBuilder::capsule_y(0.5, 0.25).build();
+            physics_state.insert_entity(entity, Some(collider), Some(rigid_body));
+        }
+        physics_state.step();
+        physics_state.record_poses(1.0);
+
+        // the target stepped out of the cone since the attacker saw it
+        physics_state
+            .get_entity_rigid_body_mut(TARGET)
+            .unwrap()
+            .set_linvel(vector![0.0, 0.0, 40.0], true);
+        physics_state.step();
+        physics_state.record_poses(1.0);
+
+        let cone = TargetQuery::Cone {
+            origin: Vec3::zeros(),
+            direction: Vec3::x(),
+            half_angle: 0.5,
+            range: 10.0,
+        };
+        assert!(physics_state
+            .query_targets(&cone, Some(ATTACKER), &[TARGET])
+            .is_empty());
+        let hits = physics_state.query_targets_rewound(&cone, Some(ATTACKER), &[TARGET], 0.1);
+        assert_eq!(hits.len(), 1);
+
+        // and is back where it is now
+        assert!(physics_state
+            .query_targets(&cone, Some(ATTACKER), &[TARGET])
+            .is_empty());
+        let position = physics_state.colliders[physics_state
+            .get_entity_handles(TARGET)
+            .unwrap()
+            .collider
+            .unwrap()]
+        .position();
+        assert!(position.translation.z > 1.0);
+    }
+}
@@ -1,3 +1,10 @@
-mod entity;
+pub mod baked_level;
+pub mod character;
+pub mod entity;
+pub mod lag_compensation;
 pub mod obj_collider;
+pub mod physics_debug;
 pub mod physics_state;
+pub mod projectile;
+pub mod snapshot;
+pub mod targeting;
@@ -1,32 +1,80 @@
-use log::debug;
+use log::{debug, warn};
 use nalgebra::Point3;
 use once_cell::sync::Lazy;
 use rapier3d::geometry::ColliderBuilder;
 use rapier3d::math::Real;
+use serde::{Deserialize, Serialize};
 
 use common::utils::file_cache::{Cache, FileCache};
+use common::utils::stable_hash::StableHasher;
+use std::hash::Hasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tobj;
 
 pub trait FromObject {
     fn from_object_models(models: Vec<tobj::Model>, decompose: bool) -> Self;
 }
 
-static OBJ_COLLIDER_CACHE: Lazy<FileCache<String, ColliderBuilder>> =
+static OBJ_COLLIDER_CACHE: Lazy<FileCache<ColliderCacheKey, ColliderBuilder>> =
     Lazy::new(|| FileCache::new("obj_collider_cache.bin"));
+static OBJ_COLLIDER_CACHE_HITS: AtomicUsize = AtomicUsize::new(0);
+
+/// A collider is built again whenever its meshes, how it is built or rapier change
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct ColliderCacheKey {
+    names: String, // of the models, for debugging
+    content_hash: u64,
+    decompose: bool,
+    rapier_version: String,
+}
+
+impl ColliderCacheKey {
+    fn new(models: &[tobj::Model], decompose: bool) -> Self {
+        Self {
+            names: models.iter().map(|model| model.name.as_str()).collect(),
+            content_hash: content_hash(models),
+            decompose,
+            rapier_version: rapier3d::VERSION.to_string(),
+        }
+    }
+}
+
+fn content_hash(models: &[tobj::Model]) -> u64 {
+    let mut hasher = StableHasher::default();
+    for model in models {
+        hasher.write_u32(model.mesh.positions.len() as u32);
+        for position in model.mesh.positions.iter() {
+            hasher.write_u32(position.to_bits());
+        }
+        hasher.write_u32(model.mesh.indices.len() as u32);
+        for index in model.mesh.indices.iter() {
+            hasher.write_u32(*index);
+        }
+    }
+    hasher.finish()
+}
+
+/// How many colliders were found in the cache so far
+pub fn collider_cache_hits() -> usize {
+    OBJ_COLLIDER_CACHE_HITS.load(Ordering::Relaxed)
+}
+
+/// Writes the colliders built since the last flush to the cache file
+pub fn flush_collider_cache() {
+    if let Err(e) = OBJ_COLLIDER_CACHE.flush() {
+        warn!("Failed to save the collider cache: {:#}", e);
+    }
+}
 
 impl FromObject for ColliderBuilder {
     /// Create a collider from a list of object models (combine all the meshes into one collider)
     fn from_object_models(models: Vec<tobj::Model>, decompose: bool) -> ColliderBuilder {
         // check cache
-        let mut cache_key = String::new();
-
-        // simple cache key (just use the model name)
-        for model in &models {
-            cache_key.push_str(&model.name);
-        }
+        let cache_key = ColliderCacheKey::new(&models, decompose);
 
         if let Some(cached_value) = OBJ_COLLIDER_CACHE.get(&cache_key) {
-            debug!("Found cached collider for {}", cache_key);
+            debug!("Found cached collider for {}", cache_key.names);
+            OBJ_COLLIDER_CACHE_HITS.fetch_add(1, Ordering::Relaxed);
             return cached_value;
         }
 
@@ -60,7 +108,7 @@ impl FromObject for ColliderBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::simulation::obj_collider::FromObject;
+    use crate::simulation::obj_collider::{ColliderCacheKey, FromObject};
     use approx::relative_eq;
     use rapier3d::geometry::ColliderBuilder;
     use rapier3d::prelude::Isometry;
@@ -85,4 +133,21 @@ mod tests {
         relative_eq!(aabb.maxs.y, 3.599072);
         relative_eq!(aabb.maxs.z, 4.615514);
     }
+
+    #[test]
+    fn test_cache_key() {
+        let mesh = tobj::Mesh {
+            positions: vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+            indices: vec![0, 1, 2],
+            ..Default::default()
+        };
+        let mut model = tobj::Model::new(mesh, "triangle".to_string());
+        let key = ColliderCacheKey::new(&[model.clone()], false);
+        assert_eq!(key, ColliderCacheKey::new(&[model.clone()], false));
+        assert_ne!(key, ColliderCacheKey::new(&[model.clone()], true));
+
+        // same name, moved vertex
+        model.mesh.positions[4] = 0.5;
+        assert_ne!(key, ColliderCacheKey::new(&[model], false));
+    }
 }
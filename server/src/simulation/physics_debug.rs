@@ -0,0 +1,307 @@
+use crate::simulation::entity::Entity;
+use crate::simulation::physics_state::PhysicsState;
+use crate::simulation::targeting::{TargetHit, TargetQuery};
+use common::core::physics_debug::*;
+
+use nalgebra_glm as glm;
+use nalgebra_glm::Vec3;
+use rapier3d::prelude::*;
+use std::collections::{BTreeSet, VecDeque};
+
+// attack queries kept for the debug stream
+const MAX_DEBUG_QUERIES: usize = 16;
+
+/// The latest attack queries, recorded only while a client watches the debug stream
+#[derive(Default)]
+pub struct DebugRecorder {
+    enabled: bool,
+    queries: VecDeque<DebugQuery>, // oldest first
+}
+
+impl From<&TargetQuery> for DebugVolume {
+    fn from(target_query: &TargetQuery) -> Self {
+        match *target_query {
+            TargetQuery::Cone {
+                origin,
+                direction,
+                half_angle,
+                range,
+            } => DebugVolume::Cone {
+                origin,
+                direction,
+                half_angle,
+                range,
+            },
+            TargetQuery::Sphere { center, radius } => DebugVolume::Sphere { center, radius },
+            TargetQuery::CapsuleSweep {
+                origin,
+                direction,
+                distance,
+                radius,
+            } => DebugVolume::CapsuleSweep {
+                origin,
+                direction,
+                distance,
+                radius,
+            },
+        }
+    }
+}
+
+impl PhysicsState {
+    pub fn set_debug_recording(&mut self, enabled: bool) {
+        self.debug.enabled = enabled;
+        if !enabled {
+            self.debug.queries.clear();
+        }
+    }
+
+    /// Records the query with a ray towards each candidate it reaches,
+    /// ending where the ray was blocked for the candidates that weren't hit
+    pub(crate) fn record_debug_query(
+        &mut self,
+        target_query: &TargetQuery,
+        source: Option<Entity>,
+        candidates: &[Entity],
+        hits: &[TargetHit],
+    ) {
+        if !self.debug.enabled {
+            return;
+        }
+        let filter = self.target_filter(source);
+        let rays = candidates
+            .iter()
+            .filter(|&&entity| Some(entity) != source)
+            .filter_map(|&entity| {
+                let collider = self
+                    .colliders
+                    .get(self.get_entity_handles(entity)?.collider?)?;
+                let center: Vec3 = collider.position().translation.vector;
+                let (eye, max_toi) = self.target_eye(target_query, collider)?;
+                if hits.iter().any(|hit| hit.entity == entity) {
+                    return Some(DebugRay {
+                        from: eye,
+                        to: center,
+                        hit: true,
+                    });
+                }
+                let to_center = center - eye;
+                if glm::length(&to_center) <= f32::EPSILON {
+                    return None;
+                }
+                let ray = Ray::new(eye.into(), glm::normalize(&to_center));
+                let toi = self
+                    .query_pipeline
+                    .cast_ray(&self.bodies, &self.colliders, &ray, max_toi, true, filter)
+                    .map_or(max_toi, |(_, toi)| toi);
+                Some(DebugRay {
+                    from: eye,
+                    to: ray.point_at(toi.min(glm::length(&to_center))).coords,
+                    hit: false,
+                })
+            })
+            .collect();
+
+        self.debug.queries.push_back(DebugQuery {
+            volume: target_query.into(),
+            rays,
+        });
+        while self.debug.queries.len() > MAX_DEBUG_QUERIES {
+            self.debug.queries.pop_front();
+        }
+    }
+
+    /// The outlines of the enabled colliders and the latest attack queries
+    pub fn debug_frame(&self) -> PhysicsDebugFrame {
+        let mut colliders = Vec::new();
+        for (_, collider) in self.colliders.iter().filter(|(_, c)| c.is_enabled()) {
+            let moving = collider
+                .parent()
+                .and_then(|handle| self.bodies.get(handle))
+                .is_some_and(|body| !body.is_fixed());
+            let mut outlines = Vec::new();
+            collect_outlines(collider.shape(), collider.position(), &mut outlines);
+            colliders.extend(outlines.into_iter().map(|outline| DebugCollider {
+                outline,
+                sensor: collider.is_sensor(),
+                moving,
+            }));
+        }
+        PhysicsDebugFrame {
+            colliders,
+            queries: self.debug.queries.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Capsules and balls as they are, boxes and convex hulls as wireframes, the rest as AABBs
+fn collect_outlines(
+    shape: &dyn Shape,
+    position: &Isometry<Real>,
+    outlines: &mut Vec<DebugOutline>,
+) {
+    match shape.as_typed_shape() {
+        TypedShape::Ball(ball) => outlines.push(DebugOutline::Capsule {
+            a: position.translation.vector,
+            b: position.translation.vector,
+            radius: ball.radius,
+        }),
+        TypedShape::Capsule(capsule) => outlines.push(DebugOutline::Capsule {
+            a: (position * capsule.segment.a).coords,
+            b: (position * capsule.segment.b).coords,
+            radius: capsule.radius,
+        }),
+        TypedShape::Cuboid(cuboid) => {
+            let corner = |i: usize| {
+                let sign = |bit: usize| if i & bit == 0 { -1.0 } else { 1.0 };
+                let local = point![
+                    sign(1) * cuboid.half_extents.x,
+                    sign(2) * cuboid.half_extents.y,
+                    sign(4) * cuboid.half_extents.z
+                ];
+                (position * local).coords
+            };
+            outlines.push(DebugOutline::Wireframe(box_lines(std::array::from_fn(
+                corner,
+            ))));
+        }
+        TypedShape::ConvexPolyhedron(hull) => {
+            outlines.push(hull_wireframe(hull, position));
+        }
+        TypedShape::RoundConvexPolyhedron(hull) => {
+            outlines.push(hull_wireframe(&hull.inner_shape, position));
+        }
+        TypedShape::Compound(compound) => {
+            for (part_position, part) in compound.shapes() {
+                collect_outlines(&**part, &(position * part_position), outlines);
+            }
+        }
+        _ => {
+            let aabb = shape.compute_aabb(position);
+            outlines.push(DebugOutline::Aabb {
+                mins: aabb.mins.coords,
+                maxs: aabb.maxs.coords,
+            });
+        }
+    }
+}
+
+fn hull_wireframe(hull: &ConvexPolyhedron, position: &Isometry<Real>) -> DebugOutline {
+    let (points, triangles) = hull.to_trimesh();
+    let edges = triangles
+        .iter()
+        .flat_map(|[a, b, c]| [(*a, *b), (*b, *c), (*c, *a)])
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect::<BTreeSet<_>>();
+    DebugOutline::Wireframe(
+        edges
+            .into_iter()
+            .map(|(a, b)| {
+                (
+                    (position * points[a as usize]).coords,
+                    (position * points[b as usize]).coords,
+                )
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::entity::EntityKind;
+
+    #[test]
+    fn test_debug_frame() {
+        let mut physics_state = PhysicsState::new();
+        let ground = ColliderBuilder::trimesh(
+            vec![
+                point![-5.0, 0.0, -5.0],
+                point![5.0, 0.0, -5.0],
+                point![0.0, 0.0, 5.0],
+            ],
+            vec![[0, 1, 2]],
+        )
+        .build();
+        physics_state.spawn_entity(EntityKind::SceneNode, None, Some(ground), None);
+        let player = ColliderBuilder::capsule_y(0.5, 0.25).build();
+        let body = RigidBodyBuilder::dynamic()
+            .translation(vector![0.0, 1.0, 0.0])
+            .build();
+        physics_state.insert_entity(Entity::player(1), Some(player), Some(body));
+        let zone = ColliderBuilder::cuboid(1.0, 1.0, 1.0).sensor(true).build();
+        physics_state.spawn_entity(EntityKind::Zone, None, Some(zone), None);
+
+        let frame = physics_state.debug_frame();
+        assert_eq!(frame.colliders.len(), 3);
+        assert!(frame.queries.is_empty());
+        let capsule = frame
+            .colliders
+            .iter()
+            .find(|collider| matches!(collider.outline, DebugOutline::Capsule { .. }))
+            .unwrap();
+        assert!(capsule.moving && !capsule.sensor);
+        assert_eq!(
+            capsule.outline,
+            DebugOutline::Capsule {
+                a: Vec3::new(0.0, 0.5, 0.0),
+                b: Vec3::new(0.0, 1.5, 0.0),
+                radius: 0.25
+            }
+        );
+        assert!(frame.colliders.iter().any(|collider| collider.sensor
+            && matches!(&collider.outline, DebugOutline::Wireframe(lines) if lines.len() == 12)));
+    }
+
+    #[test]
+    fn test_recorded_rays() {
+        let mut physics_state = PhysicsState::new();
+        // the attacker at the origin, one target in the open and one behind a wall
+        let [attacker, open, covered] = [1, 2, 3].map(Entity::player);
+        for (entity, position) in [
+            (attacker, Vec3::zeros()),
+            (open, Vec3::z() * 5.0),
+            (covered, Vec3::x() * 5.0),
+        ] {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(position)
+                .gravity_scale(0.0)
+                .build();
+            let collider = ColliderBuilder::capsule_y(0.5, 0.25).build();
+            physics_state.insert_entity(entity, Some(collider), Some(body));
+        }
+        let wall = ColliderBuilder::cuboid(0.1, 5.0, 5.0)
+            .translation(vector![2.5, 0.0, 0.0])
+            .build();
+        physics_state.spawn_entity(EntityKind::SceneNode, None, Some(wall), None);
+        physics_state.step();
+
+        let sphere = TargetQuery::Sphere {
+            center: Vec3::zeros(),
+            radius: 10.0,
+        };
+        // nothing is recorded until a client watches
+        physics_state.query_targets_rewound(&sphere, Some(attacker), &[open, covered], 0.0);
+        assert!(physics_state.debug_frame().queries.is_empty());
+
+        physics_state.set_debug_recording(true);
+        let hits = physics_state.query_targets_rewound(
+            &sphere,
+            Some(attacker),
+            &[attacker, open, covered],
+            0.0,
+        );
+        assert_eq!(hits.len(), 1);
+        let queries = physics_state.debug_frame().queries;
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].volume, DebugVolume::from(&sphere));
+
+        let rays = &queries[0].rays;
+        assert_eq!(rays.len(), 2);
+        assert!(rays[0].hit);
+        assert!(glm::distance(&rays[0].to, &(Vec3::z() * 5.0)) < 1e-3);
+        // stopped by the wall
+        assert!(!rays[1].hit);
+        assert!((rays[1].to.x - 2.4).abs() < 1e-3);
+    }
+}
@@ -1,9 +1,12 @@
-use crate::simulation::entity::{Entity, EntityHandles};
+use crate::simulation::character::KinematicCharacter;
+use crate::simulation::entity::{ContactEvent, Entity, EntityHandles, EntityKind, EntityRegistry};
+use crate::simulation::lag_compensation::PoseHistory;
+use crate::simulation::physics_debug::DebugRecorder;
+use crate::simulation::snapshot::SimulationRng;
 
 use rapier3d::parry::utils::hashmap::HashMap;
 use rapier3d::prelude::*;
 
-use nalgebra_glm::Vec3;
 use rapier3d::control::KinematicCharacterController;
 use rapier3d::crossbeam;
 
@@ -22,7 +25,14 @@ pub struct PhysicsState {
     pub integration_parameters: IntegrationParameters,
     pub gravity: Vector<f32>,
     pub entity_indices: HashMap<Entity, EntityHandles>,
+    pub entities: EntityRegistry, // what the entities are
     pub character_controller: KinematicCharacterController,
+    pub kinematic_characters: bool, // whether players spawn as kinematic bodies
+    pub character_motions: HashMap<Entity, KinematicCharacter>, // of the kinematic players
+    pub contact_events: Vec<ContactEvent>, // since the last drain
+    pub pose_history: PoseHistory,
+    pub rng: SimulationRng,
+    pub debug: DebugRecorder, // of the physics debug stream
 }
 
 impl PhysicsState {
@@ -43,6 +53,8 @@ impl PhysicsState {
     }
 
     pub fn step(&mut self) {
+        self.move_kinematic_characters();
+
         // Initialize the event collector.
         let (collision_send, collision_recv) = crossbeam::channel::unbounded();
         let (contact_force_send, contact_force_recv) = crossbeam::channel::unbounded();
@@ -63,27 +75,78 @@ impl PhysicsState {
             &(),
             &event_handler,
         );
+        // events of colliders removed since are dropped, their entity is unknown
         while let Ok(collision_event) = collision_recv.try_recv() {
-            // Handle the collision event.
-            //println!("Received collision event: {:?}", collision_event);
-            panic!("Collision event received")
+            let Some(entities) =
+                self.entities_of(collision_event.collider1(), collision_event.collider2())
+            else {
+                continue;
+            };
+            let sensor = collision_event.sensor();
+            self.contact_events.push(if collision_event.started() {
+                ContactEvent::Started { entities, sensor }
+            } else {
+                ContactEvent::Stopped { entities, sensor }
+            });
         }
 
         while let Ok(contact_force_event) = contact_force_recv.try_recv() {
-            // Handle the contact force event.
-            //println!("Received contact force event: {:?}", contact_force_event);
-            panic!("Contact force event received")
+            let Some(entities) =
+                self.entities_of(contact_force_event.collider1, contact_force_event.collider2)
+            else {
+                continue;
+            };
+            self.contact_events.push(ContactEvent::Force {
+                entities,
+                magnitude: contact_force_event.total_force_magnitude,
+                direction: contact_force_event.max_force_direction,
+            });
         }
 
         self.query_pipeline.update(&self.bodies, &self.colliders);
     }
 
+    /// Takes the contact events of the steps since the last call
+    pub fn drain_contact_events(&mut self) -> Vec<ContactEvent> {
+        std::mem::take(&mut self.contact_events)
+    }
+
+    /// Which events the collider of the entity reports, none by default
+    pub fn set_active_events(&mut self, entity: Entity, active_events: ActiveEvents) {
+        if let Some(collider) = self.get_entity_collider_mut(entity) {
+            collider.set_active_events(active_events);
+        }
+    }
+
+    /// The entity a collider was inserted for
+    pub fn collider_entity(&self, collider: ColliderHandle) -> Option<Entity> {
+        self.colliders
+            .get(collider)
+            .map(|collider| Entity::from_raw(collider.user_data as u32))
+    }
+
+    fn entities_of(
+        &self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> Option<(Entity, Entity)> {
+        Some((
+            self.collider_entity(collider1)?,
+            self.collider_entity(collider2)?,
+        ))
+    }
+
     pub fn insert_entity(
         &mut self,
         entity: Entity,
         collider: Option<Collider>,
         rigid_body: Option<RigidBody>,
     ) -> Option<EntityHandles> {
+        // maps the collider back to the entity in the contact events
+        let collider = collider.map(|mut collider| {
+            collider.user_data = entity.raw() as u128;
+            collider
+        });
         let (rigid_body_handle, collider_handle) = match (collider, rigid_body) {
             // if both are provided, insert the rigid body first, then the collider (with parent rigid body)
             (Some(collider), Some(rigid_body)) => {
@@ -116,6 +179,19 @@ impl PhysicsState {
         )
     }
 
+    /// Inserts a new entity that isn't a player, under an id of its own
+    pub fn spawn_entity(
+        &mut self,
+        kind: EntityKind,
+        node_id: Option<String>,
+        collider: Option<Collider>,
+        rigid_body: Option<RigidBody>,
+    ) -> Entity {
+        let entity = self.entities.allocate(kind, node_id);
+        self.insert_entity(entity, collider, rigid_body);
+        entity
+    }
+
     pub fn get_entity_handles(&self, entity: Entity) -> Option<&EntityHandles> {
         self.entity_indices.get(&entity)
     }
@@ -126,6 +202,8 @@ impl PhysicsState {
 
     pub fn remove_entity(&mut self, entity: Entity) -> Option<EntityHandles> {
         let entity_handles = self.entity_indices.remove(&entity)?;
+        self.entities.remove(entity);
+        self.character_motions.remove(&entity);
         // remove rigid body and collider
         if let Some(rigid_body_handle) = entity_handles.rigid_body {
             self.bodies.remove(
@@ -164,40 +242,6 @@ impl PhysicsState {
         self.colliders
             .get_mut(self.get_entity_handles(entity)?.collider?)
     }
-
-    pub fn move_character_with_velocity(&mut self, entity: Entity, desired_translation: Vec3) {
-        let character_shape = self.get_entity_collider(entity).unwrap().shape();
-        let character_handle = self.get_entity_handles(entity).unwrap().rigid_body.unwrap();
-        let character_pos = self.get_entity_rigid_body(entity).unwrap().position();
-
-        let dt = self.integration_parameters.dt;
-        let _gravity = self.gravity;
-        // Calculate the possible movement.
-        let corrected_movement = self.character_controller.move_shape(
-            dt,                   // The timestep length
-            &self.bodies,         // The RigidBodySet.
-            &self.colliders,      // The ColliderSet.
-            &self.query_pipeline, // The QueryPipeline.
-            character_shape,      // The character’s shape.
-            character_pos,        // The character’s initial position.
-            desired_translation,
-            QueryFilter::default()
-                // Make sure the the character we are trying to move isn’t considered an obstacle.
-                .exclude_rigid_body(character_handle),
-            |_| {},
-        );
-
-        let character_body = self.get_entity_rigid_body_mut(entity).unwrap();
-        // set its velocity to the computed movement divided by the timestep length.
-
-        let mut velocity = corrected_movement.translation / dt;
-
-        // add back y component of velocity
-        velocity.y = character_body.linvel().y;
-
-        character_body.set_linvel(velocity, true);
-        // character_body.set_translation(character_body.translation() + corrected_movement.translation, true);
-    }
 }
 
 #[cfg(test)]
@@ -215,10 +259,11 @@ mod tests {
     #[test]
     fn test_adding_bodies_and_colliders() {
         let mut physics_state = PhysicsState::new();
+        let (ground, ball) = (Entity::from_raw(0), Entity::from_raw(1));
 
         /* Create the ground. */
         let collider = ColliderBuilder::cuboid(100.0, 0.1, 100.0).build();
-        physics_state.insert_entity(0, Some(collider), None);
+        physics_state.insert_entity(ground, Some(collider), None);
 
         /* Create the bounding ball. */
         let rigid_body = RigidBodyBuilder::dynamic()
@@ -226,7 +271,7 @@ mod tests {
             .build();
         let collider = ColliderBuilder::ball(0.5).restitution(0.7).build();
 
-        physics_state.insert_entity(1, Some(collider), Some(rigid_body));
+        physics_state.insert_entity(ball, Some(collider), Some(rigid_body));
 
         let tick_duration = 30; // 30ms per tick
         let mut last_instant = Instant::now();
@@ -237,7 +282,7 @@ mod tests {
             sleep(Duration::from_millis(tick_duration));
         }
         let ball_pos = physics_state
-            .get_entity_rigid_body(1)
+            .get_entity_rigid_body(ball)
             .unwrap()
             .position()
             .translation
@@ -245,4 +290,73 @@ mod tests {
         // should be around 0.59585696 += epsilon
         assert!(ball_pos > 0.5 && ball_pos < 0.7);
     }
+
+    #[test]
+    fn test_contact_events() {
+        let mut physics_state = PhysicsState::new();
+        let (ground, ball) = (Entity::from_raw(7), Entity::from_raw(42));
+        physics_state.set_delta_time(1.0 / 60.0);
+
+        let collider = ColliderBuilder::cuboid(100.0, 0.1, 100.0).build();
+        physics_state.insert_entity(ground, Some(collider), None);
+
+        let rigid_body = RigidBodyBuilder::dynamic()
+            .translation(vector![0.0, 1.0, 0.0])
+            .build();
+        let collider = ColliderBuilder::ball(0.5).build();
+        physics_state.insert_entity(ball, Some(collider), Some(rigid_body));
+        physics_state.set_active_events(ball, ActiveEvents::COLLISION_EVENTS);
+
+        for _ in 0..60 {
+            physics_state.step();
+        }
+        let events = physics_state.drain_contact_events();
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, ContactEvent::Started { .. })
+                && event.other(ball) == Some(ground)));
+        assert!(physics_state.drain_contact_events().is_empty());
+    }
+
+    #[test]
+    fn test_sensor_events() {
+        let mut physics_state = PhysicsState::new();
+        let (zone, ball) = (Entity::from_raw(7), Entity::from_raw(42));
+        physics_state.set_delta_time(1.0 / 60.0);
+
+        let collider = ColliderBuilder::cylinder(2.0, 3.0).sensor(true).build();
+        physics_state.insert_entity(
+            zone,
+            Some(collider),
+            Some(RigidBodyBuilder::fixed().build()),
+        );
+
+        let rigid_body = RigidBodyBuilder::dynamic().gravity_scale(0.0).build();
+        let collider = ColliderBuilder::ball(0.5).build();
+        physics_state.insert_entity(ball, Some(collider), Some(rigid_body));
+        physics_state.set_active_events(ball, ActiveEvents::COLLISION_EVENTS);
+
+        physics_state.step();
+        assert!(physics_state
+            .drain_contact_events()
+            .iter()
+            .any(
+                |event| matches!(event, ContactEvent::Started { sensor: true, .. })
+                    && event.other(ball) == Some(zone)
+            ));
+
+        // dying disables the body, which leaves the sensor
+        physics_state
+            .get_entity_rigid_body_mut(ball)
+            .unwrap()
+            .set_enabled(false);
+        physics_state.step();
+        assert!(physics_state
+            .drain_contact_events()
+            .iter()
+            .any(
+                |event| matches!(event, ContactEvent::Stopped { sensor: true, .. })
+                    && event.other(ball) == Some(zone)
+            ));
+    }
 }
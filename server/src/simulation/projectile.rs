@@ -0,0 +1,79 @@
+use crate::simulation::entity::{Entity, EntityKind};
+use crate::simulation::physics_state::PhysicsState;
+
+use nalgebra_glm::Vec3;
+use rapier3d::prelude::*;
+
+// so that gravity moves it, sensors have no mass of their own
+const PROJECTILE_MASS: f32 = 1.0;
+
+impl PhysicsState {
+    /// Inserts a projectile flying from origin, what it touches comes as sensor contact events
+    pub fn launch_projectile(
+        &mut self,
+        origin: Vec3,
+        velocity: Vec3,
+        radius: f32,
+        gravity_scale: f32,
+    ) -> Entity {
+        let rigid_body = RigidBodyBuilder::dynamic()
+            .translation(origin)
+            .linvel(velocity)
+            .gravity_scale(gravity_scale)
+            .additional_mass(PROJECTILE_MASS)
+            .build();
+        // a sensor doesn't push what it hits before the hit is handled
+        let collider = ColliderBuilder::ball(radius)
+            .sensor(true)
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+            .build();
+        self.spawn_entity(
+            EntityKind::Projectile,
+            None,
+            Some(collider),
+            Some(rigid_body),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::entity::ContactEvent;
+
+    #[test]
+    fn test_projectile_hits_wall() {
+        let mut physics_state = PhysicsState::new();
+        let wall = ColliderBuilder::cuboid(0.1, 5.0, 5.0)
+            .translation(vector![3.0, 0.0, 0.0])
+            .build();
+        let wall = physics_state.spawn_entity(EntityKind::SceneNode, None, Some(wall), None);
+        let projectile =
+            physics_state.launch_projectile(Vec3::zeros(), Vec3::x() * 10.0, 0.25, 1.0);
+        assert_eq!(
+            physics_state.entities.kind(projectile),
+            Some(EntityKind::Projectile)
+        );
+
+        let mut hit = false;
+        for _ in 0..30 {
+            physics_state.step();
+            hit |= physics_state
+                .drain_contact_events()
+                .iter()
+                .any(|event| match *event {
+                    ContactEvent::Started { sensor, .. } => {
+                        sensor && event.other(projectile) == Some(wall)
+                    }
+                    _ => false,
+                });
+        }
+        assert!(hit);
+        // falls with gravity, goes through the wall as it is a sensor
+        let position = physics_state
+            .get_entity_rigid_body(projectile)
+            .unwrap()
+            .translation();
+        assert!(position.x > 3.0 && position.y < 0.0);
+    }
+}
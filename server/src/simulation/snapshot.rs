@@ -0,0 +1,154 @@
+use crate::simulation::character::KinematicCharacter;
+use crate::simulation::entity::{Entity, EntityHandles, EntityRegistry};
+use crate::simulation::lag_compensation::PoseHistory;
+use crate::simulation::physics_state::PhysicsState;
+
+use rand::{Error, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rapier3d::parry::utils::hashmap::HashMap;
+use rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Randomness of the simulation, kept in the snapshots so a restored match draws the same numbers
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SimulationRng(ChaCha8Rng);
+
+impl SimulationRng {
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+impl Default for SimulationRng {
+    fn default() -> Self {
+        Self(ChaCha8Rng::from_entropy())
+    }
+}
+
+impl RngCore for SimulationRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+/// The state of a PhysicsState that the pipelines don't rebuild on their own.
+/// The pose history isn't kept, attacks right after a restore aren't rewound.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PhysicsSnapshot {
+    islands: IslandManager,
+    broad_phase: BroadPhase,
+    narrow_phase: NarrowPhase,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    joints: ImpulseJointSet,
+    multibody_joints: MultibodyJointSet,
+    integration_parameters: IntegrationParameters,
+    gravity: Vector<f32>,
+    entity_indices: HashMap<Entity, EntityHandles>,
+    entities: EntityRegistry,
+    character_motions: HashMap<Entity, KinematicCharacter>,
+    rng: SimulationRng,
+}
+
+impl PhysicsState {
+    pub fn snapshot(&self) -> PhysicsSnapshot {
+        PhysicsSnapshot {
+            islands: self.islands.clone(),
+            broad_phase: self.broad_phase.clone(),
+            narrow_phase: self.narrow_phase.clone(),
+            bodies: self.bodies.clone(),
+            colliders: self.colliders.clone(),
+            joints: self.joints.clone(),
+            multibody_joints: self.multibody_joints.clone(),
+            integration_parameters: self.integration_parameters,
+            gravity: self.gravity,
+            entity_indices: self.entity_indices.clone(),
+            entities: self.entities.clone(),
+            character_motions: self.character_motions.clone(),
+            rng: self.rng.clone(),
+        }
+    }
+
+    /// Replaces the simulation with the snapshot, the character controller settings are kept
+    pub fn restore(&mut self, snapshot: PhysicsSnapshot) {
+        self.physics_pipeline = PhysicsPipeline::new();
+        self.islands = snapshot.islands;
+        self.broad_phase = snapshot.broad_phase;
+        self.narrow_phase = snapshot.narrow_phase;
+        self.bodies = snapshot.bodies;
+        self.colliders = snapshot.colliders;
+        self.joints = snapshot.joints;
+        self.multibody_joints = snapshot.multibody_joints;
+        self.ccd_solver = CCDSolver::new();
+        self.integration_parameters = snapshot.integration_parameters;
+        self.gravity = snapshot.gravity;
+        self.entity_indices = snapshot.entity_indices;
+        self.entities = snapshot.entities;
+        self.character_motions = snapshot.character_motions;
+        self.rng = snapshot.rng;
+        self.contact_events.clear();
+        self.pose_history = PoseHistory::default();
+        self.query_pipeline = QueryPipeline::new();
+        self.query_pipeline.update(&self.bodies, &self.colliders);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    const GROUND: Entity = Entity::from_raw(0);
+    const BALL: Entity = Entity::from_raw(1);
+
+    fn setup() -> PhysicsState {
+        let mut physics_state = PhysicsState::new();
+        physics_state.rng = SimulationRng::seed_from_u64(7);
+        let ground = ColliderBuilder::cuboid(10.0, 0.1, 10.0).build();
+        physics_state.insert_entity(GROUND, Some(ground), None);
+        let rigid_body = RigidBodyBuilder::dynamic()
+            .translation(vector![0.0, 3.0, 0.0])
+            .build();
+        let collider = ColliderBuilder::ball(0.5).restitution(0.7).build();
+        physics_state.insert_entity(BALL, Some(collider), Some(rigid_body));
+        physics_state
+    }
+
+    fn run(physics_state: &mut PhysicsState, steps: usize) -> (Isometry<Real>, f32) {
+        for _ in 0..steps {
+            physics_state.step();
+        }
+        let position = *physics_state
+            .get_entity_rigid_body(BALL)
+            .unwrap()
+            .position();
+        (position, physics_state.rng.gen())
+    }
+
+    #[test]
+    fn test_snapshot_restores_the_simulation() {
+        let mut physics_state = setup();
+        run(&mut physics_state, 20);
+        let snapshot = physics_state.snapshot();
+        let expected = run(&mut physics_state, 40);
+
+        // through bincode, as snapshots are saved
+        let bytes = bincode::serialize(&snapshot).unwrap();
+        let mut restored = PhysicsState::new();
+        restored.restore(bincode::deserialize(&bytes).unwrap());
+        assert_eq!(run(&mut restored, 40), expected);
+        assert!(restored.get_entity_handles(BALL).is_some());
+    }
+}
@@ -0,0 +1,339 @@
+use crate::simulation::entity::Entity;
+use crate::simulation::physics_state::PhysicsState;
+
+use nalgebra_glm as glm;
+use nalgebra_glm::Vec3;
+use rapier3d::parry::query;
+use rapier3d::parry::shape::Capsule;
+use rapier3d::prelude::*;
+
+// rays are cast towards the center of a target and around it, see sample_points
+const SAMPLE_SPREAD: f32 = 0.8; // fraction of the target's half extents
+
+/// The volume an attack reaches
+#[derive(Debug, Clone, Copy)]
+pub enum TargetQuery {
+    // targets whose center is within half_angle of direction
+    Cone {
+        origin: Vec3,
+        direction: Vec3,
+        half_angle: f32,
+        range: f32,
+    },
+    Sphere {
+        center: Vec3,
+        radius: f32,
+    },
+    // a ball of the radius moving distance along direction, seen from its closest position
+    CapsuleSweep {
+        origin: Vec3,
+        direction: Vec3,
+        distance: f32,
+        radius: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TargetHit {
+    pub entity: Entity,
+    pub distance: f32,   // to the closest exposed point of the target
+    pub direction: Vec3, // from where the target is seen to its center, normalized
+    pub exposure: f32,   // fraction of the sample rays reaching the target, above 0
+}
+
+impl PhysicsState {
+    /// Which of the candidates the query reaches, closest first.
+    /// Sensors don't block the rays, the source (if any) is never hit.
+    pub fn query_targets(
+        &self,
+        target_query: &TargetQuery,
+        source: Option<Entity>,
+        candidates: &[Entity],
+    ) -> Vec<TargetHit> {
+        let filter = self.target_filter(source);
+        let mut hits = candidates
+            .iter()
+            .filter(|&&entity| Some(entity) != source)
+            .filter_map(|&entity| self.query_target(target_query, filter, entity))
+            .collect::<Vec<_>>();
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    fn query_target(
+        &self,
+        target_query: &TargetQuery,
+        filter: QueryFilter,
+        entity: Entity,
+    ) -> Option<TargetHit> {
+        let collider_handle = self.get_entity_handles(entity)?.collider?;
+        let collider = self.colliders.get(collider_handle)?;
+        if !collider.is_enabled() {
+            return None;
+        }
+        let center: Vec3 = collider.position().translation.vector;
+
+        let (eye, max_toi) = self.target_eye(target_query, collider)?;
+
+        let to_center = center - eye;
+        let direction = if glm::length(&to_center) > f32::EPSILON {
+            glm::normalize(&to_center)
+        } else {
+            Vec3::y()
+        };
+
+        let samples = sample_points(collider, &direction);
+        let tois = samples
+            .iter()
+            .filter_map(|sample| {
+                let to_sample = sample - eye;
+                if glm::length(&to_sample) <= f32::EPSILON {
+                    return Some(0.0);
+                }
+                let ray = Ray::new(eye.into(), glm::normalize(&to_sample));
+                let (handle, toi) = self.query_pipeline.cast_ray(
+                    &self.bodies,
+                    &self.colliders,
+                    &ray,
+                    max_toi,
+                    true,
+                    filter,
+                )?;
+                (handle == collider_handle).then_some(toi)
+            })
+            .collect::<Vec<_>>();
+
+        let distance = tois.iter().copied().reduce(f32::min)?;
+        Some(TargetHit {
+            entity,
+            distance,
+            direction,
+            exposure: tois.len() as f32 / samples.len() as f32,
+        })
+    }
+
+    /// Rays towards the targets ignore sensors and the source
+    pub(crate) fn target_filter(&self, source: Option<Entity>) -> QueryFilter<'_> {
+        let filter = QueryFilter::default().exclude_sensors();
+        match source
+            .and_then(|entity| self.get_entity_handles(entity))
+            .and_then(|handles| handles.collider)
+        {
+            Some(source_collider) => filter.exclude_collider(source_collider),
+            None => filter,
+        }
+    }
+
+    /// Where the target is seen from, and how far rays may go, if the query reaches it
+    pub(crate) fn target_eye(
+        &self,
+        target_query: &TargetQuery,
+        collider: &Collider,
+    ) -> Option<(Vec3, f32)> {
+        let center: Vec3 = collider.position().translation.vector;
+        Some(match *target_query {
+            TargetQuery::Cone {
+                origin,
+                direction,
+                half_angle,
+                range,
+            } => {
+                if glm::angle(&direction, &(center - origin)) > half_angle {
+                    return None;
+                }
+                (origin, range)
+            }
+            TargetQuery::Sphere {
+                center: origin,
+                radius,
+            } => (origin, radius),
+            TargetQuery::CapsuleSweep {
+                origin,
+                direction,
+                distance,
+                radius,
+            } => {
+                let end = origin + glm::normalize(&direction) * distance;
+                let capsule = Capsule::new(origin.into(), end.into(), radius);
+                let touched = query::intersection_test(
+                    &Isometry::identity(),
+                    &capsule,
+                    collider.position(),
+                    collider.shape(),
+                )
+                .unwrap_or(false);
+                if !touched {
+                    return None;
+                }
+                let eye = closest_point_on_segment(&origin, &end, &center);
+                // the sweep touches the target, the rays only check for cover
+                (
+                    eye,
+                    glm::distance(&eye, &center)
+                        + collider.shape().compute_local_aabb().half_extents().norm(),
+                )
+            }
+        })
+    }
+}
+
+/// The center of the collider, above and below it, and on both of its sides as seen along direction
+fn sample_points(collider: &Collider, direction: &Vec3) -> Vec<Vec3> {
+    let aabb = collider.compute_aabb();
+    let center: Vec3 = aabb.center().coords;
+    let half_extents: Vec3 = aabb.half_extents() * SAMPLE_SPREAD;
+
+    let side = glm::cross(direction, &Vec3::y());
+    let side = if glm::length(&side) > f32::EPSILON {
+        glm::normalize(&side)
+    } else {
+        Vec3::x()
+    };
+    // how far the box reaches along the side direction
+    let side_reach = side.abs().dot(&half_extents);
+
+    vec![
+        center,
+        center + Vec3::y() * half_extents.y,
+        center - Vec3::y() * half_extents.y,
+        center + side * side_reach,
+        center - side * side_reach,
+    ]
+}
+
+fn closest_point_on_segment(a: &Vec3, b: &Vec3, point: &Vec3) -> Vec3 {
+    let ab = b - a;
+    let length_squared = glm::length2(&ab);
+    if length_squared <= f32::EPSILON {
+        return *a;
+    }
+    let t = ((point - a).dot(&ab) / length_squared).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ATTACKER: Entity = Entity::player(1);
+    const TARGET: Entity = Entity::player(2);
+
+    // a player facing +x at the origin, a target 5 units away
+    fn setup() -> PhysicsState {
+        let mut physics_state = PhysicsState::new();
+        for (entity, x) in [(ATTACKER, 0.0), (TARGET, 5.0)] {
+            let rigid_body = RigidBodyBuilder::dynamic()
+                .translation(vector![x, 0.0, 0.0])
+                .build();
+            let collider = ColliderBuilder::capsule_y(0.5, 0.25).build();
+            physics_state.insert_entity(entity, Some(collider), Some(rigid_body));
+        }
+        refresh(&mut physics_state);
+        physics_state
+    }
+
+    fn add_wall(physics_state: &mut PhysicsState, entity: Entity, center: Vector<f32>) {
+        let collider = ColliderBuilder::cuboid(0.1, 0.2, 0.2)
+            .translation(center)
+            .build();
+        physics_state.insert_entity(entity, Some(collider), None);
+        refresh(physics_state);
+    }
+
+    fn refresh(physics_state: &mut PhysicsState) {
+        physics_state
+            .query_pipeline
+            .update(&physics_state.bodies, &physics_state.colliders);
+    }
+
+    fn cone(half_angle: f32, range: f32) -> TargetQuery {
+        TargetQuery::Cone {
+            origin: Vec3::zeros(),
+            direction: Vec3::x(),
+            half_angle,
+            range,
+        }
+    }
+
+    #[test]
+    fn test_cone_query() {
+        let physics_state = setup();
+        let hits =
+            physics_state.query_targets(&cone(0.5, 10.0), Some(ATTACKER), &[ATTACKER, TARGET]);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entity, TARGET);
+        assert_eq!(hits[0].exposure, 1.0);
+        assert!((hits[0].distance - 4.75).abs() < 1e-3);
+
+        // out of range, or behind the attacker
+        assert!(physics_state
+            .query_targets(&cone(0.5, 4.0), Some(ATTACKER), &[TARGET])
+            .is_empty());
+        let behind = TargetQuery::Cone {
+            origin: Vec3::zeros(),
+            direction: -Vec3::x(),
+            half_angle: 0.5,
+            range: 10.0,
+        };
+        assert!(physics_state
+            .query_targets(&behind, Some(ATTACKER), &[TARGET])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_exposure_behind_cover() {
+        let mut physics_state = setup();
+        // covers the center ray only, a single ray would miss the target
+        add_wall(
+            &mut physics_state,
+            Entity::from_raw(300),
+            vector![2.5, 0.0, 0.0],
+        );
+        let hits = physics_state.query_targets(&cone(0.5, 10.0), Some(ATTACKER), &[TARGET]);
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].exposure > 0.0 && hits[0].exposure < 1.0);
+
+        // sensors are no cover
+        let mut physics_state = setup();
+        let sensor = ColliderBuilder::cuboid(0.5, 2.0, 2.0)
+            .translation(vector![2.5, 0.0, 0.0])
+            .sensor(true)
+            .build();
+        physics_state.insert_entity(Entity::from_raw(300), Some(sensor), None);
+        refresh(&mut physics_state);
+        let hits = physics_state.query_targets(&cone(0.5, 10.0), Some(ATTACKER), &[TARGET]);
+        assert_eq!(hits[0].exposure, 1.0);
+    }
+
+    #[test]
+    fn test_sphere_and_sweep_queries() {
+        let physics_state = setup();
+        let sphere = |radius| TargetQuery::Sphere {
+            center: Vec3::zeros(),
+            radius,
+        };
+        assert_eq!(
+            physics_state
+                .query_targets(&sphere(6.0), Some(ATTACKER), &[ATTACKER, TARGET])
+                .len(),
+            1
+        );
+        assert!(physics_state
+            .query_targets(&sphere(4.0), Some(ATTACKER), &[TARGET])
+            .is_empty());
+
+        // passing by the target, closer than the radius
+        let sweep = |radius| TargetQuery::CapsuleSweep {
+            origin: Vec3::new(0.0, 0.0, 1.0),
+            direction: Vec3::x(),
+            distance: 10.0,
+            radius,
+        };
+        let hits = physics_state.query_targets(&sweep(1.0), None, &[TARGET]);
+        assert_eq!(hits.len(), 1);
+        assert!((hits[0].direction - -Vec3::z()).norm() < 1e-3);
+        assert!(physics_state
+            .query_targets(&sweep(0.5), None, &[TARGET])
+            .is_empty());
+    }
+}